@@ -1,111 +1,788 @@
-use gloo_storage::{LocalStorage, Storage};
-use spell_card::{Area, CastTime, Defence, Heightened, RollResult, SpellCard, SpellOverview, SpellType};
-use spell_book::{SpellBook, SpellBookProps};
+use backups::{local_time_string, record_backup, BackupRestore, Backups, Snapshot};
+use catalog::Catalog;
+use gloo_events::EventListener;
+use spell_card::{Area, CastTime, Defence, Heightened, RollResult, SpellCard, SpellOverview, SpellSource, SpellType};
+use spell_book::{assign_card_ids, CharacterProfile, SpellBook, SpellBookEntry, SpellBookProps};
 use spell_card_creator::SpellCardCreator;
+use encounter::Encounter;
+use drafts::{draft_key, DraftList};
+use filters::{FilterBar, SpellFilter};
+use history::{History, UndoRedo};
+use localization::{Localization, LocalizationSetting};
+use merge::{match_incoming, MergeView};
+use foundry::{FoundryExporter, FoundryImporter};
+use pathbuilder::PathbuilderImporter;
+use print_sheet::PrintSheet;
+use router::{navigate, Route};
+use share_link::{decode_cards, ShareLink, SharedCards};
+use storage::{Backend, KeyValueStore, StorageUsage};
+use sync::{keep_edits_made_during_sync, last_synced_cards, note_local_edits, SyncConflict, SyncOutcome, SyncPanel};
+use terminology::{Terminology, TerminologySetting};
+use text_export::TextExporter;
+use wasm_bindgen::JsCast;
+use web_sys::{window, EventTarget, HtmlInputElement, HtmlSelectElement};
+use yew::platform::spawn_local;
 use yew::prelude::*;
 
 mod spell_card;
+mod area_grid;
+mod backups;
+mod card_image;
+mod catalog;
+mod degree_of_success;
+mod drafts;
+mod encounter;
+mod file_io;
+mod filters;
+mod foundry;
+mod history;
+mod localization;
+mod merge;
+mod pathbuilder;
+mod print_sheet;
+mod router;
+mod share_link;
 mod spell_book;
 mod spell_card_creator;
+mod stat_block;
+mod storage;
+mod sync;
+mod terminology;
+mod text_export;
 
-#[derive(Clone, Copy)]
-enum Page {
-    Overview,
-    NewCard
+const BOOK_HISTORY_KEY: &str = "SpellBookHistory";
+
+// Cards waiting to be merged into a book, card by card and field by field
+#[derive(PartialEq, Clone)]
+struct PendingMerge {
+    book: usize,
+    incoming: Vec<SpellCard>,
+    base: Vec<SpellCard>,
+    source: String,
 }
 
+#[derive(Clone)]
 struct State {
-    spells: Vec<SpellCard>,
-    page: Page
+    store: Backend,
+    books: Vec<SpellBookProps>,
+    current_book: usize,
+    history: History<Vec<SpellBookProps>>,
+    // Shown when the saved books could not be read and were replaced
+    notice: Option<String>,
+    pending_merge: Option<PendingMerge>
+}
+
+impl State {
+    fn book(&self) -> &SpellBookProps {
+        &self.books[self.current_book]
+    }
+
+    fn spells(&self) -> Vec<SpellCard> {
+        self.book().spells.clone()
+    }
+
+    // Looks in the current book first, links to cards may come from other books
+    fn find_card(&self, id: &str) -> Option<&SpellCard> {
+        self.book().spells.iter()
+            .chain(self.books.iter().flat_map(|book| book.spells.iter()))
+            .find(|card| card.id == id)
+    }
+
+    // Applies a change to the current book and saves all books
+    fn with_book(&self, change: impl FnOnce(&mut SpellBookProps)) -> State {
+        let mut books: Vec<SpellBookProps> = self.books.clone();
+        change(&mut books[self.current_book]);
+        self.with_books(books)
+    }
+
+    // Replaces all books after an edit made on this device
+    fn with_books(&self, books: Vec<SpellBookProps>) -> State {
+        note_local_edits(&self.books, &books);
+        self.replaced_books(books)
+    }
+
+    // Replaces all books, keeping the previous ones to undo to
+    fn replaced_books(&self, books: Vec<SpellBookProps>) -> State {
+        let (books, _) = assign_card_ids(books);
+        let mut history: History<Vec<SpellBookProps>> = self.history.clone();
+        history.record(self.books.clone());
+        history.save(BOOK_HISTORY_KEY);
+        save_books(&self.store, &books);
+        State { books, history, ..self.clone() }
+    }
+
+    fn undone(&self, redo: bool) -> State {
+        let mut history: History<Vec<SpellBookProps>> = self.history.clone();
+        let books: Option<Vec<SpellBookProps>> = if redo {
+            history.redo(self.books.clone())
+        } else {
+            history.undo(self.books.clone())
+        };
+        match books {
+            Some(books) if !books.is_empty() => {
+                note_local_edits(&self.books, &books);
+                history.save(BOOK_HISTORY_KEY);
+                save_books(&self.store, &books);
+                let current_book: usize = self.current_book.min(books.len() - 1);
+                State { books, current_book, history, ..self.clone() }
+            },
+            _ => self.clone(),
+        }
+    }
+}
+
+#[derive(PartialEq, Properties, Clone)]
+struct LoadedAppProps {
+    store: Backend,
+    books: Vec<SpellBookProps>,
+    notice: Option<String>
 }
 
+// Storage is opened asynchronously, the app itself starts once the books have been read
 #[function_component(App)]
 pub fn app() -> Html {
-    let state = use_state(|| State {
-        spells: match LocalStorage::get::<SpellBookProps>("SpellBook") {
-            Ok(sbp) => sbp.spells,
-            Err(_) => {
-                let cards: Vec<SpellCard> = vec![
-                    SpellCard {
-                        spell_name: "Lightningbolt".to_string(),
-                        cast_time: CastTime::Reaction,
-                        spell_type: SpellType::Cantrip,
-                        spell_level: 1,
-                        link: "https://2e.aonprd.com/Spells.aspx?ID=1509".to_string(),
-                        traits: format_string_vec(vec!["Lightning"]),
-                        overview: vec![SpellOverview::Range(30),
-                                    SpellOverview::Targets("1 or 2 creatures".to_string()),
-                                    SpellOverview::Defence(Defence::Fortitude)],
-                        spell_effect: "Electric arcs jump between you and the target(s).".to_string(),
-                        roll_effect: vec![],
-                        heightened: vec![Heightened::Repeat(2, "Increase damage by 1d4".to_string())]
-                    },
-                    SpellCard {
-                        spell_name: "Fireball".to_string(),
-                        cast_time: CastTime::Triple,
-                        spell_type: SpellType::Spell,
-                        spell_level: 3,
-                        link: "https://2e.aonprd.com/Spells.aspx?ID=1565".to_string(),
-                        traits: format_string_vec(vec!["Fire", "AoE"]),
-                        overview: vec![SpellOverview::Range(20),
-                                       SpellOverview::Area(Area::Burst(15))],
-                        spell_effect: "Cast a fireball\nTry to avoid your friends or they might want to try to kill you until you have died four times\nEach creature in the affected area makes a Reflex save".to_string(),
-                        roll_effect: vec![RollResult::CriticalSuccess("The creature is unaffected".to_string()),
-                                          RollResult::Success("The creature takes half damage".to_string()),
-                                          RollResult::CriticalFailure("The creature takes double damage and 3d6 persistent fire damage".to_string())],
-                        heightened: vec![Heightened::Repeat(1, "Increase damage by 1d6".to_string()),
-                                         Heightened::Single(5, "Increase persistent damage by 1d6".to_string())]
-                    },
-                    SpellCard {
-                        spell_name: "Thunderstorm".to_string(),
-                        cast_time: CastTime::Double,
-                        spell_type: SpellType::Spell,
-                        spell_level: 3,
-                        link: "https://2e.aonprd.com/Spells.aspx?ID=1509".to_string(),
-                        traits: format_string_vec(vec!["Lightning"]),
-                        overview: vec![SpellOverview::Range(30),
-                                    SpellOverview::Targets("1 or 2 creatures".to_string()),
-                                    SpellOverview::Defence(Defence::Fortitude)],
-                        spell_effect: "Electric arcs jump between you and the target(s).".to_string(),
-                        roll_effect: vec![],
-                        heightened: vec![Heightened::Repeat(2, "Increase damage by 1d4".to_string())]
+    let loaded: UseStateHandle<Option<Result<LoadedAppProps, String>>> = use_state(|| None);
+    {
+        let loaded: UseStateHandle<Option<Result<LoadedAppProps, String>>> = loaded.clone();
+        use_effect_with((), move |_| {
+            spawn_local(async move {
+                let store: Backend = Backend::open().await;
+                let props: Result<LoadedAppProps, String> = load_books(&store, Localization::load()).await.map(|(books, notice)| {
+                    // Cards saved before they had ids get one, once
+                    let (books, migrated) = assign_card_ids(books);
+                    if migrated {
+                        save_books(&store, &books);
                     }
-                ];
-                let sbp = SpellBookProps { spells: cards };
-                _ = LocalStorage::set("SpellBook", &sbp);
-                sbp.spells
-            },
+                    LoadedAppProps { store, books, notice }
+                });
+                loaded.set(Some(props))
+            });
+        });
+    }
+    let reload: Callback<MouseEvent> = Callback::from(|_| {
+        if let Some(window) = web_sys::window() {
+            _ = window.location().reload();
+        }
+    });
+
+    match &*loaded {
+        Some(Ok(props)) => html! { <LoadedApp ..props.clone() /> },
+        Some(Err(error)) => html! {
+            <main>
+                <p class="import-warnings">{error}</p>
+                <button onclick={reload}>{Localization::load().t("Reload")}</button>
+            </main>
+        },
+        None => html! {
+            <main>
+                <p>{Localization::load().t("Loading spellbooks…")}</p>
+            </main>
         },
-        page: Page::NewCard
+    }
+}
+
+#[function_component]
+fn LoadedApp(props: &LoadedAppProps) -> Html {
+    let state = use_state(|| State {
+        store: props.store.clone(),
+        books: props.books.clone(),
+        current_book: 0,
+        history: History::load(BOOK_HISTORY_KEY),
+        notice: props.notice.clone(),
+        pending_merge: None
     });
+    let route: UseStateHandle<Route> = use_state(Route::current);
+    let terminology: UseStateHandle<Terminology> = use_state(Terminology::load);
+    let localization: UseStateHandle<Localization> = use_state(Localization::load);
+
+    // Follow the address bar, including the browser's back and forward buttons
+    {
+        let route: UseStateHandle<Route> = route.clone();
+        use_effect_with((), move |_| {
+            let listener: Option<EventListener> = window().map(|window| {
+                EventListener::new(&window, "hashchange", move |_| route.set(Route::current()))
+            });
+            move || drop(listener)
+        });
+    }
 
-    match state.page {
-        Page::Overview =>
+    // Checked again whenever the books change, the backups grow with them
+    let storage_usage: UseStateHandle<Option<StorageUsage>> = use_state(|| None);
+    {
+        let storage_usage: UseStateHandle<Option<StorageUsage>> = storage_usage.clone();
+        let store: Backend = state.store.clone();
+        use_effect_with(state.books.clone(), move |_| {
+            spawn_local(async move {
+                storage_usage.set(store.usage().await)
+            });
+        });
+    }
+
+    // Versions of the cards as of the last sync, which imports of synced cards are merged against
+    let synced_cards: UseStateHandle<Vec<SpellCard>> = use_state(Vec::new);
+    {
+        let synced_cards: UseStateHandle<Vec<SpellCard>> = synced_cards.clone();
+        let store: Backend = state.store.clone();
+        use_effect_with(state.books.clone(), move |_| {
+            spawn_local(async move {
+                synced_cards.set(last_synced_cards(&store).await)
+            });
+        });
+    }
+
+    // # Callback functions
+    // Cards colliding with ones already in the book are merged instead of added twice
+    let add_cards = |source: &'static str| {
+        let state = state.clone();
+        let localization: Localization = *localization;
+        let base: Vec<SpellCard> = (*synced_cards).clone();
+        Callback::from(move |cards: Vec<SpellCard>| {
+            let (collisions, new_cards, duplicates) = match_incoming(&state.book().spells, &cards, &base, localization);
+            if collisions.is_empty() && duplicates.is_empty() {
+                state.set(state.with_book(|book| book.spells.extend(new_cards.into_iter().map(SpellCard::with_id))));
+                navigate(&Route::default())
+            } else {
+                let pending_merge: PendingMerge = PendingMerge { book: state.current_book, incoming: cards, base: base.clone(), source: source.to_string() };
+                state.set(State { pending_merge: Some(pending_merge), ..(*state).clone() });
+                navigate(&Route::Merge)
+            }
+        })
+    };
+    let add_book: Callback<SpellBookProps> = {
+        let state = state.clone();
+        Callback::from(move |book: SpellBookProps| {
+            let mut books: Vec<SpellBookProps> = state.books.clone();
+            books.push(book);
+            let current_book: usize = books.len() - 1;
+            state.set(State { current_book, ..state.with_books(books) });
+            navigate(&Route::default())
+        })
+    };
+    // An edited card replaces the card with its id in whichever book holds it
+    let save_card = |id: Option<String>| {
+        let state = state.clone();
+        Callback::from(move |card: SpellCard| {
+            let card: SpellCard = SpellCard { id: id.clone().unwrap_or_default(), ..card }.with_id();
+            let saved_id: String = card.id.clone();
+            let mut books: Vec<SpellBookProps> = state.books.clone();
+            let existing: Option<&mut SpellCard> = books.iter_mut()
+                .flat_map(|book| book.spells.iter_mut())
+                .find(|c| c.id == saved_id);
+            match existing {
+                Some(existing) => *existing = card,
+                None => books[state.current_book].spells.push(card),
+            }
+            state.set(state.with_books(books));
+            navigate(&Route::CardDetail(saved_id))
+        })
+    };
+    let select_book: Callback<Event> = {
+        let state = state.clone();
+        Callback::from(move |e: Event| {
+            let target: Option<EventTarget> = e.target();
+
+            let input: Option<HtmlSelectElement> = target.and_then(|t: EventTarget| t.dyn_into::<HtmlSelectElement>().ok());
+            if let Some(input) = input {
+                let current_book: usize = input.value().parse::<usize>().unwrap_or(0).min(state.books.len() - 1);
+                state.set(State { current_book, ..(*state).clone() })
+            }
+        })
+    };
+    let terminology_change: Callback<Terminology> = {
+        let terminology: UseStateHandle<Terminology> = terminology.clone();
+        Callback::from(move |new_terminology: Terminology| terminology.set(new_terminology))
+    };
+    let localization_change: Callback<Localization> = {
+        let localization: UseStateHandle<Localization> = localization.clone();
+        Callback::from(move |new_localization: Localization| localization.set(new_localization))
+    };
+    let rename_book: Callback<Event> = {
+        let state = state.clone();
+        Callback::from(move |e: Event| {
+            let target: Option<EventTarget> = e.target();
+
+            let input: Option<HtmlInputElement> = target.and_then(|t: EventTarget| t.dyn_into::<HtmlInputElement>().ok());
+            if let Some(input) = input {
+                state.set(state.with_book(|book| book.name = input.value().trim().to_string()))
+            }
+        })
+    };
+    let undo: Callback<()> = {
+        let state = state.clone();
+        Callback::from(move |_| state.set(state.undone(false)))
+    };
+    let redo: Callback<()> = {
+        let state = state.clone();
+        Callback::from(move |_| state.set(state.undone(true)))
+    };
+    let undo_redo: Html = html! {
+        <UndoRedo
+            can_undo={state.history.can_undo()}
+            can_redo={state.history.can_redo()}
+            unsaved={state.history.is_unsaved()}
+            on_undo={undo}
+            on_redo={redo}
+        />
+    };
+    let restore_backup: Callback<Vec<SpellBookProps>> = {
+        let state = state.clone();
+        Callback::from(move |books: Vec<SpellBookProps>| {
+            let current_book: usize = state.current_book.min(books.len().saturating_sub(1));
+            state.set(State { current_book, notice: None, ..state.with_books(books) });
+            navigate(&Route::default())
+        })
+    };
+    let merge_backup: Callback<SpellBookProps> = {
+        let state = state.clone();
+        Callback::from(move |backup: SpellBookProps| {
+            if let Some(book) = state.books.iter().position(|b| b.display_name() == backup.display_name()) {
+                // The backup is an earlier state of the same book, so it is also what both sides started from
+                let pending_merge: PendingMerge = PendingMerge { book, incoming: backup.spells.clone(), base: backup.spells, source: "The backup".to_string() };
+                state.set(State { pending_merge: Some(pending_merge), ..(*state).clone() });
+                navigate(&Route::Merge)
+            }
+        })
+    };
+    // Offers the versions the sync did not keep, one book at a time
+    let review_sync: Callback<Vec<SyncConflict>> = {
+        let state = state.clone();
+        Callback::from(move |conflicts: Vec<SyncConflict>| {
+            let Some(first) = conflicts.first() else {
+                return;
+            };
+            let Some(book) = state.books.iter().position(|b| b.display_name() == first.book) else {
+                return;
+            };
+            let in_book: Vec<&SyncConflict> = conflicts.iter().filter(|c| c.book == first.book).collect();
+            let incoming: Vec<SpellCard> = in_book.iter()
+                .filter_map(|c| if c.kept_local { c.remote.clone() } else { c.local.clone() })
+                .collect();
+            let base: Vec<SpellCard> = in_book.iter().filter_map(|c| c.base.clone()).collect();
+            let pending_merge: PendingMerge = PendingMerge { book, incoming, base, source: "The versions the sync set aside".to_string() };
+            state.set(State { pending_merge: Some(pending_merge), ..(*state).clone() });
+            navigate(&Route::Merge)
+        })
+    };
+    let finish_merge: Callback<Vec<SpellCard>> = {
+        let state = state.clone();
+        Callback::from(move |merged: Vec<SpellCard>| {
+            if let Some(pending) = &state.pending_merge {
+                let mut books: Vec<SpellBookProps> = state.books.clone();
+                books[pending.book].spells = merged;
+                state.set(State { current_book: pending.book, pending_merge: None, ..state.with_books(books) });
+            }
+            navigate(&Route::default())
+        })
+    };
+    let cancel_merge: Callback<MouseEvent> = {
+        let state = state.clone();
+        Callback::from(move |_| {
+            state.set(State { pending_merge: None, ..(*state).clone() });
+            navigate(&Route::default())
+        })
+    };
+    let apply_sync: Callback<SyncOutcome> = {
+        let state = state.clone();
+        Callback::from(move |outcome: SyncOutcome| {
+            let books: Vec<SpellBookProps> = keep_edits_made_during_sync(&outcome, &state.books);
+            if books != state.books && !books.is_empty() {
+                let current_book: usize = state.current_book.min(books.len() - 1);
+                state.set(State { current_book, ..state.replaced_books(books) })
+            }
+        })
+    };
+    let storage_warning: Html = html! {
+        if let Some(usage) = storage_usage.filter(|usage| usage.nearly_full()) {
+            <p class="import-warnings">
+                {localization.tf(
+                    "Browser storage is nearly full ({} of about {} MB used). Export your spellbooks to a file, older backups are removed when space runs out.",
+                    &[&format!("{:.1}", usage.used / 1048576.0), &format!("{:.0}", usage.quota / 1048576.0)]
+                )}
+            </p>
+        }
+    };
+    let add_from_catalog: Callback<SpellCard> = {
+        let state = state.clone();
+        Callback::from(move |card: SpellCard| state.set(state.with_book(|book| book.spells.push(card))))
+    };
+    let character_change: Callback<CharacterProfile> = {
+        let state = state.clone();
+        Callback::from(move |character: CharacterProfile| state.set(state.with_book(|book| book.character = Some(character))))
+    };
+    let filter_change: Callback<SpellFilter> = Callback::from(|filter: SpellFilter| navigate(&Route::Overview(filter)));
+    let catalog_filter_change: Callback<SpellFilter> = Callback::from(|filter: SpellFilter| navigate(&Route::Catalog(filter)));
+    let go_to = |route: Route| Callback::from(move |_: MouseEvent| navigate(&route));
+    let all_spells: Vec<SpellCard> = state.books.iter().flat_map(|book| book.spells.clone()).collect();
+
+    let page: Html = match &*route {
+        Route::Overview(filter) =>
             html! {
                 <main>
-                    <h1>{ "Spellbook overview" }</h1>
-                    <SpellBook spells={state.spells.clone()} />
-                    <button onclick={move |_| {
-                        state.set(State { spells: state.spells.clone(), page: Page::NewCard })
-                    }}>
-                        {"Add new card"}
+                    <h1>{localization.t("Spellbook overview")}</h1>
+                    if let Some(notice) = &state.notice {
+                        <p class="import-warnings">
+                            {notice}
+                            <a href={Route::Backups.to_hash()}>{localization.t("See backups")}</a>
+                        </p>
+                    }
+                    {storage_warning}
+                    {undo_redo}
+                    <select onchange={select_book}>
+                        { state.books.iter().enumerate().map(|(index, book)| {
+                            html!{
+                                <option value={index.to_string()} selected={index == state.current_book}>
+                                    {book.display_name()}
+                                </option>
+                            }
+                        }).collect::<Html>() }
+                    </select>
+                    if let Some(character) = &state.book().character {
+                        {character.to_html(*localization)}
+                    }
+                    <FilterBar filter={filter.clone()} on_change={filter_change} />
+                    <DraftList />
+                    <SpellBook spells={state.spells()} filter={filter.clone()} />
+                    <FoundryExporter spells={state.spells()} />
+                    <TextExporter name={state.book().display_name()} spells={state.spells()} />
+                    <ShareLink cards={state.spells()} label={localization.t("Share spellbook link")} />
+                    <button onclick={go_to(Route::NewCard)}>
+                        {localization.t("Add new card")}
+                    </button>
+                    <button onclick={go_to(Route::Encounter)}>
+                        {localization.t("Start encounter")}
+                    </button>
+                    <button onclick={go_to(Route::Import)}>
+                        {localization.t("Import spells")}
+                    </button>
+                    <button onclick={go_to(Route::Catalog(SpellFilter::default()))}>
+                        {localization.t("Browse spell catalog")}
+                    </button>
+                    <button onclick={go_to(Route::Print)}>
+                        {localization.t("Print cards")}
+                    </button>
+                    <button onclick={go_to(Route::Settings)}>
+                        {localization.t("Settings")}
                     </button>
                 </main>
             },
-        Page::NewCard =>
+        Route::NewCard =>
             html! {
                 <main>
-                    <h1>{"Add a New Card"}</h1>
-                    <SpellCardCreator 
-                        on_cancellation={move |_| {
-                            state.set(State { spells: state.spells.clone(), page: Page::Overview })
-                        }}
+                    <h1>{localization.t("Add a New Card")}</h1>
+                    <SpellCardCreator
+                        key={"new"}
+                        draft_key={draft_key(None)}
+                        on_card_completion={save_card(None)}
+                        on_cancellation={go_to(Route::default())}
                     />
                 </main>
+            },
+        Route::EditCard(id) =>
+            html! {
+                <main>
+                    if let Some(card) = state.find_card(id) {
+                        <h1>{localization.tf("Edit {}", &[&card.spell_name])}</h1>
+                        <SpellCardCreator
+                            key={format!("edit-{}", id)}
+                            card={card.clone()}
+                            draft_key={draft_key(Some(id))}
+                            on_card_completion={save_card(Some(id.clone()))}
+                            on_cancellation={go_to(Route::CardDetail(id.clone()))}
+                        />
+                    } else {
+                        <h1>{localization.t("Card not found")}</h1>
+                        <button onclick={go_to(Route::default())}>
+                            {localization.t("Back to overview")}
+                        </button>
+                    }
+                </main>
+            },
+        Route::CardDetail(id) =>
+            html! {
+                <main>
+                    if let Some(card) = state.find_card(id) {
+                        <h1>{card.spell_name.clone()}</h1>
+                        {undo_redo}
+                        <SpellBookEntry key={id.clone()} card={card.clone()} />
+                        <button onclick={go_to(Route::EditCard(id.clone()))}>
+                            {localization.t("Edit card")}
+                        </button>
+                    } else {
+                        <h1>{localization.t("Card not found")}</h1>
+                    }
+                    <button onclick={go_to(Route::default())}>
+                        {localization.t("Back to overview")}
+                    </button>
+                </main>
+            },
+        Route::Settings =>
+            html! {
+                <main>
+                    <h1>{localization.t("Settings")}</h1>
+                    {undo_redo}
+                    <label>
+                        {localization.t("Spellbook name:")}
+                        <input
+                            onchange={rename_book}
+                            type="text"
+                            placeholder={localization.t("My spellbook")}
+                            value={state.book().name.clone()}
+                        />
+                    </label>
+                    <LocalizationSetting localization={*localization} on_change={localization_change} />
+                    <TerminologySetting terminology={*terminology} on_change={terminology_change} />
+                    <SyncPanel store={state.store.clone()} books={state.books.clone()} on_synced={apply_sync} on_review={review_sync} />
+                    <button onclick={go_to(Route::Backups)}>
+                        {localization.t("Restore a backup")}
+                    </button>
+                    <button onclick={go_to(Route::default())}>
+                        {localization.t("Back to overview")}
+                    </button>
+                </main>
+            },
+        Route::Backups =>
+            html! {
+                <main>
+                    <h1>{localization.t("Backups")}</h1>
+                    {storage_warning}
+                    <BackupRestore store={state.store.clone()} current={state.books.clone()} on_restore={restore_backup} on_merge={merge_backup} />
+                    <button onclick={go_to(Route::Settings)}>
+                        {localization.t("Back to settings")}
+                    </button>
+                </main>
+            },
+        Route::Merge =>
+            html! {
+                <main>
+                    <h1>{localization.t("Merge cards")}</h1>
+                    if let Some(pending) = state.pending_merge.as_ref().filter(|p| p.book < state.books.len()) {
+                        <MergeView
+                            local={state.books[pending.book].spells.clone()}
+                            incoming={pending.incoming.clone()}
+                            base={pending.base.clone()}
+                            source={pending.source.clone()}
+                            on_merge={finish_merge}
+                            on_cancel={cancel_merge}
+                        />
+                    } else {
+                        <p>{localization.t("There is nothing to merge.")}</p>
+                        <button onclick={go_to(Route::default())}>
+                            {localization.t("Back to overview")}
+                        </button>
+                    }
+                </main>
+            },
+        Route::Encounter =>
+            html! {
+                <main>
+                    <h1>{localization.t("Encounter")}</h1>
+                    <Encounter spells={state.spells()} />
+                    <button onclick={go_to(Route::default())}>
+                        {localization.t("Back to overview")}
+                    </button>
+                </main>
+            },
+        Route::Import =>
+            html! {
+                <main>
+                    <h1>{localization.t("Import spells")}</h1>
+                    <FoundryImporter on_import={add_cards("The import")} />
+                    <PathbuilderImporter known_spells={all_spells} on_import={add_book} />
+                    <button onclick={go_to(Route::default())}>
+                        {localization.t("Back to overview")}
+                    </button>
+                </main>
+            },
+        Route::Catalog(filter) =>
+            html! {
+                <main>
+                    <h1>{localization.t("Spell catalog")}</h1>
+                    <p>{localization.tf("Adding to {}", &[&state.book().display_name()])}</p>
+                    <Catalog
+                        filter={filter.clone()}
+                        owned={state.book().spells.iter().map(|card| card.spell_name.clone()).collect::<Vec<String>>()}
+                        character={state.book().character.clone()}
+                        other_books={state.books.iter().enumerate().filter(|(index, _)| *index != state.current_book).map(|(_, book)| book.clone()).collect::<Vec<SpellBookProps>>()}
+                        on_filter_change={catalog_filter_change}
+                        on_character_change={character_change}
+                        on_add={add_from_catalog}
+                    />
+                    <button onclick={go_to(Route::default())}>
+                        {localization.t("Back to overview")}
+                    </button>
+                </main>
+            },
+        Route::Print =>
+            html! {
+                <main>
+                    <h1 class="no-print">{localization.t("Print cards")}</h1>
+                    <PrintSheet spells={state.spells()} />
+                    <button class="no-print" onclick={go_to(Route::default())}>
+                        {localization.t("Back to overview")}
+                    </button>
+                </main>
+            },
+        Route::Share(payload) =>
+            html! {
+                <main>
+                    <h1>{localization.t("Shared spells")}</h1>
+                    { match decode_cards(payload, *localization) {
+                        Ok(cards) => html!{
+                            <SharedCards
+                                cards={cards}
+                                on_add={add_cards("The shared link")}
+                            />
+                        },
+                        Err(err) => html!{<p class="import-warnings">{err}</p>},
+                    } }
+                    <button onclick={go_to(Route::default())}>
+                        {localization.t("Go to my spellbook")}
+                    </button>
+                </main>
             }
+    };
+
+    // Cards, filters and exports word the rules the way the chosen edition does, in the chosen language
+    html! {
+        <ContextProvider<Localization> context={*localization}>
+            <ContextProvider<Terminology> context={*terminology}>
+                {page}
+            </ContextProvider<Terminology>>
+        </ContextProvider<Localization>>
     }
 }
 
+// Books are stored together under "SpellBooks", a single book saved under "SpellBook" by older versions is moved over.
+// Saved books that cannot be read are kept under another key and replaced by the newest readable backup.
+// When the storage itself cannot be read nothing is saved, so the books are still there once it can be again.
+async fn load_books(store: &Backend, localization: Localization) -> Result<(Vec<SpellBookProps>, Option<String>), String> {
+    let unreadable_storage = |err: String| localization.tf("Your saved spellbooks could not be loaded ({}). Nothing was changed, try reloading the page.", &[&err]);
+    if let Some(raw) = store.get_raw("SpellBooks").await.map_err(unreadable_storage)? {
+        match serde_json::from_str::<Vec<SpellBookProps>>(&raw) {
+            Ok(books) if !books.is_empty() => return Ok((books, None)),
+            Ok(_) => (),
+            Err(_) => {
+                let kept_key: String = format!("SpellBooks-unreadable-{}", js_sys::Date::now());
+                _ = store.set_raw(&kept_key, &raw).await;
+                let backup: Option<Snapshot> = Backups::load(store).await.all().into_iter().find(|snapshot| !snapshot.books.is_empty());
+                if let Some(backup) = backup {
+                    save_books(store, &backup.books);
+                    return Ok((backup.books, Some(localization.tf(
+                        "Your saved spellbooks could not be read and were replaced by the backup from {}. The unreadable data was kept under \"{}\".",
+                        &[&local_time_string(backup.taken_at), &kept_key]
+                    ))));
+                }
+            },
+        }
+    }
+    let legacy: Option<String> = store.get_raw("SpellBook").await.map_err(unreadable_storage)?;
+    let book: SpellBookProps = match legacy.and_then(|raw| serde_json::from_str::<SpellBookProps>(&raw).ok()) {
+        Some(sbp) => sbp,
+        None => SpellBookProps { spells: default_cards(), ..Default::default() },
+    };
+    let books: Vec<SpellBookProps> = vec![book];
+    save_books(store, &books);
+    Ok((books, None))
+}
+
+// Saving runs in the background, writes start in the order they are made
+fn save_books(store: &Backend, books: &[SpellBookProps]) {
+    let store: Backend = store.clone();
+    let books: Vec<SpellBookProps> = books.to_vec();
+    spawn_local(async move {
+        _ = store.set("SpellBooks", &books).await;
+        record_backup(&store, &books).await;
+    });
+}
+
+fn default_cards() -> Vec<SpellCard> {
+    vec![
+        SpellCard {
+            id: String::new(),
+            spell_name: "Lightningbolt".to_string(),
+            cast_time: CastTime::Reaction,
+            spell_type: SpellType::Cantrip,
+            spell_level: 1,
+            link: "https://2e.aonprd.com/Spells.aspx?ID=1509".to_string(),
+            source: SpellSource::Unknown,
+            traits: format_string_vec(vec!["Lightning"]),
+            traditions: format_string_vec(vec!["Arcane", "Primal"]),
+            overview: vec![SpellOverview::Range(30),
+                        SpellOverview::Targets("1 or 2 creatures".to_string()),
+                        SpellOverview::Defence(Defence::Fortitude)],
+            basic_save: true,
+            spell_effect: "Electric arcs jump between you and the target(s).".to_string(),
+            roll_effect: vec![],
+            heightened: vec![Heightened::Repeat(2, "Increase damage by 1d4".to_string())]
+        },
+        SpellCard {
+            id: String::new(),
+            spell_name: "Fireball".to_string(),
+            cast_time: CastTime::Triple,
+            spell_type: SpellType::Spell,
+            spell_level: 3,
+            link: "https://2e.aonprd.com/Spells.aspx?ID=1565".to_string(),
+            source: SpellSource::Unknown,
+            traits: format_string_vec(vec!["Fire", "AoE"]),
+            traditions: format_string_vec(vec!["Arcane", "Primal"]),
+            overview: vec![SpellOverview::Range(20),
+                           SpellOverview::Area(Area::Burst(15))],
+            basic_save: false,
+            spell_effect: "Cast a fireball\nTry to avoid your friends or they might want to try to kill you until you have died four times\nEach creature in the affected area makes a Reflex save".to_string(),
+            roll_effect: vec![RollResult::CriticalSuccess("The creature is unaffected".to_string()),
+                              RollResult::Success("The creature takes half damage".to_string()),
+                              RollResult::CriticalFailure("The creature takes double damage and 3d6 persistent fire damage".to_string())],
+            heightened: vec![Heightened::Repeat(1, "Increase damage by 1d6".to_string()),
+                             Heightened::Single(5, "Increase persistent damage by 1d6".to_string())]
+        },
+        SpellCard {
+            id: String::new(),
+            spell_name: "Thunderstorm".to_string(),
+            cast_time: CastTime::Double,
+            spell_type: SpellType::Spell,
+            spell_level: 3,
+            link: "https://2e.aonprd.com/Spells.aspx?ID=1509".to_string(),
+            source: SpellSource::Unknown,
+            traits: format_string_vec(vec!["Lightning"]),
+            traditions: format_string_vec(vec!["Primal"]),
+            overview: vec![SpellOverview::Range(30),
+                        SpellOverview::Targets("1 or 2 creatures".to_string()),
+                        SpellOverview::Defence(Defence::Fortitude)],
+            basic_save: true,
+            spell_effect: "Electric arcs jump between you and the target(s).".to_string(),
+            roll_effect: vec![],
+            heightened: vec![Heightened::Repeat(2, "Increase damage by 1d4".to_string())]
+        }
+    ]
+}
+
 fn format_string_vec(input:Vec<&str>) -> Vec<String> {
     input.iter().map(|s| s.to_string()).collect()
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use storage::{block_on, MemoryStore};
+
+    #[test]
+    fn unreadable_storage_is_left_alone() {
+        let memory: MemoryStore = MemoryStore::default();
+        block_on(memory.set_raw("SpellBooks", "[{\"spells\":[]}]")).unwrap();
+        let store: Backend = Backend::Memory(memory.with_read_error("UnknownError: the database is locked"));
+        let result: Result<(Vec<SpellBookProps>, Option<String>), String> = block_on(load_books(&store, Localization::default()));
+        assert!(result.is_err_and(|error| error.contains("UnknownError: the database is locked")));
+        // Neither replaced by the default cards nor moved
+        assert_eq!(block_on(memory.get_raw("SpellBooks")), Ok(Some("[{\"spells\":[]}]".to_string())));
+        assert_eq!(block_on(memory.get_raw("SpellBook")), Ok(None));
+    }
+
+    #[test]
+    fn saved_books_are_loaded() {
+        let memory: MemoryStore = MemoryStore::default();
+        let books: Vec<SpellBookProps> = vec![SpellBookProps { spells: vec![SpellCard { spell_name: "Fear".to_string(), ..SpellCard::empty() }], ..Default::default() }];
+        block_on(memory.set("SpellBooks", &books)).unwrap();
+        let result: Result<(Vec<SpellBookProps>, Option<String>), String> = block_on(load_books(&Backend::Memory(memory), Localization::default()));
+        assert!(result.is_ok_and(|(loaded, notice)| loaded == books && notice.is_none()));
+    }
+}
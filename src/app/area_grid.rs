@@ -0,0 +1,295 @@
+use std::collections::HashSet;
+use std::fmt::Display;
+use wasm_bindgen::JsCast;
+use web_sys::{EventTarget, HtmlSelectElement};
+use yew::prelude::*;
+
+use super::localization::{Localization, Units};
+use super::spell_card::Area;
+
+pub const SQUARE_FT: u8 = 5;
+const CELL_PX: i32 = 12;
+
+#[derive(PartialEq, Eq, Clone, Copy)]
+pub enum Direction {
+    East,
+    SouthEast,
+    South,
+    SouthWest,
+    West,
+    NorthWest,
+    North,
+    NorthEast,
+}
+
+impl Display for Direction {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let direction_str: &str = match self {
+            Direction::East      => "East",
+            Direction::SouthEast => "South-east",
+            Direction::South     => "South",
+            Direction::SouthWest => "South-west",
+            Direction::West      => "West",
+            Direction::NorthWest => "North-west",
+            Direction::North     => "North",
+            Direction::NorthEast => "North-east",
+        };
+        write!(f, "{}", direction_str)
+    }
+}
+
+impl Direction {
+    pub const ALL: [Direction; 8] = [
+        Direction::East,
+        Direction::SouthEast,
+        Direction::South,
+        Direction::SouthWest,
+        Direction::West,
+        Direction::NorthWest,
+        Direction::North,
+        Direction::NorthEast,
+    ];
+
+    fn is_diagonal(&self) -> bool {
+        (*self as u8) % 2 == 1
+    }
+
+    // Clockwise quarter turns from the east or south-east base shape
+    fn quarter_turns(&self) -> u8 {
+        (*self as u8) / 2
+    }
+}
+
+// Distance in feet of moving `dx` by `dy` squares, where every second diagonal costs 10ft
+pub fn grid_distance(dx: i32, dy: i32) -> i32 {
+    let (dx, dy): (i32, i32) = (dx.abs(), dy.abs());
+    (dx.max(dy) + dx.min(dy) / 2) * SQUARE_FT as i32
+}
+
+// Steps needed to reach the square starting at `i` from the grid line at 0
+fn steps_from_corner(i: i32) -> i32 {
+    if i >= 0 { i + 1 } else { -i }
+}
+
+// Steps needed to reach the square starting at `i` from a creature occupying squares `0..size`
+fn steps_from_space(i: i32, size: i32) -> i32 {
+    if i < 0 { -i } else if i >= size { i - size + 1 } else { 0 }
+}
+
+fn rotate_clockwise((i, j): (i32, i32)) -> (i32, i32) {
+    (-j - 1, i)
+}
+
+// Squares covered by an area, relative to the origin grid intersection at (0, 0).
+// Emanations are measured from a creature of `size` squares whose top-left corner is at the origin.
+pub fn covered_squares(area: &Area, direction: Direction, size: u8) -> HashSet<(i32, i32)> {
+    let (length, width) = area.get_aoe_val();
+    let length: i32 = length as i32;
+    let reach: i32 = length / SQUARE_FT as i32 + size as i32 + 1;
+    let mut squares: HashSet<(i32, i32)> = HashSet::new();
+
+    match area {
+        Area::Burst(_) => {
+            for i in -reach..=reach {
+                for j in -reach..=reach {
+                    if grid_distance(steps_from_corner(i), steps_from_corner(j)) <= length {
+                        squares.insert((i, j));
+                    }
+                }
+            }
+        },
+        Area::Emanation(_) => {
+            let size: i32 = size as i32;
+            for i in -reach..=reach {
+                for j in -reach..=reach {
+                    if grid_distance(steps_from_space(i, size), steps_from_space(j, size)) <= length {
+                        squares.insert((i, j));
+                    }
+                }
+            }
+        },
+        Area::Cone(_) => {
+            for i in 0..=reach {
+                for j in -reach..=reach {
+                    let in_cone: bool = if direction.is_diagonal() {
+                        j >= 0
+                    } else {
+                        // Within 45 degrees of the eastward direction
+                        (2 * j + 1).abs() <= 2 * i + 1
+                    };
+                    if in_cone && grid_distance(steps_from_corner(i), steps_from_corner(j)) <= length {
+                        squares.insert((i, j));
+                    }
+                }
+            }
+        },
+        Area::Line(_, _) => {
+            let width_squares: i32 = (width.unwrap_or(SQUARE_FT) / SQUARE_FT).max(1) as i32;
+            if direction.is_diagonal() {
+                let mut k: i32 = 0;
+                while grid_distance(k + 1, k + 1) <= length {
+                    for w in 0..width_squares {
+                        squares.insert((k + w, k));
+                    }
+                    k += 1;
+                }
+            } else {
+                let length_squares: i32 = length / SQUARE_FT as i32;
+                for i in 0..length_squares {
+                    for j in -(width_squares / 2)..(width_squares - width_squares / 2) {
+                        squares.insert((i, j));
+                    }
+                }
+            }
+        },
+    }
+
+    match area {
+        Area::Cone(_) | Area::Line(_, _) => squares.into_iter().map(|mut square| {
+            for _ in 0..direction.quarter_turns() {
+                square = rotate_clockwise(square);
+            }
+            square
+        }).collect(),
+        _ => squares,
+    }
+}
+
+#[derive(PartialEq, Properties)]
+pub struct AreaGridProps {
+    pub area: Area,
+}
+
+struct AreaGridState {
+    origin: (i32, i32),
+    direction: Direction,
+    size: u8,
+}
+
+#[function_component]
+pub fn AreaGrid(props: &AreaGridProps) -> Html {
+    let AreaGridProps {
+        area
+    } = props;
+
+    let localization: Localization = use_context::<Localization>().unwrap_or_default();
+
+    let half_extent: i32 = area.get_aoe_val().0 as i32 / SQUARE_FT as i32 + 3;
+    let state: UseStateHandle<AreaGridState> = use_state(|| AreaGridState {
+        origin: (0, 0),
+        direction: Direction::East,
+        size: 1,
+    });
+
+    // # Callback functions
+    let direction_change: Callback<Event> = {
+        let state: UseStateHandle<AreaGridState> = state.clone();
+
+        Callback::from(move |e: Event| {
+            let target: Option<EventTarget> = e.target();
+
+            let input: Option<HtmlSelectElement> = target.and_then(|t: EventTarget| t.dyn_into::<HtmlSelectElement>().ok());
+            if let Some(input) = input {
+                let index: usize = input.value().parse::<usize>().unwrap_or(0);
+                state.set(AreaGridState { origin: state.origin, direction: Direction::ALL[index], size: state.size })
+            }
+        })
+    };
+    let size_change: Callback<Event> = {
+        let state: UseStateHandle<AreaGridState> = state.clone();
+
+        Callback::from(move |e: Event| {
+            let target: Option<EventTarget> = e.target();
+
+            let input: Option<HtmlSelectElement> = target.and_then(|t: EventTarget| t.dyn_into::<HtmlSelectElement>().ok());
+            if let Some(input) = input {
+                let size: u8 = input.value().parse::<u8>().unwrap_or(1);
+                state.set(AreaGridState { origin: state.origin, direction: state.direction, size })
+            }
+        })
+    };
+    let origin_change = |origin: (i32, i32)| {
+        let state: UseStateHandle<AreaGridState> = state.clone();
+
+        Callback::from(move |_| {
+            state.set(AreaGridState { origin, direction: state.direction, size: state.size })
+        })
+    };
+
+    let covered: HashSet<(i32, i32)> = covered_squares(area, state.direction, state.size);
+    let (origin_x, origin_y) = state.origin;
+    let grid_px: i32 = half_extent * 2 * CELL_PX;
+    let is_emanation: bool = matches!(area, Area::Emanation(_));
+
+    html! {
+        <div class="area-grid">
+            if matches!(area, Area::Cone(_) | Area::Line(_, _)) {
+                <label>
+                    {localization.t("Direction:")}
+                    <select onchange={direction_change}>
+                        { Direction::ALL.iter().enumerate().map(|(index, direction)| {
+                            html!{
+                                <option value={index.to_string()} selected={*direction == state.direction}>
+                                    {localization.t(&direction.to_string())}
+                                </option>
+                            }
+                        }).collect::<Html>() }
+                    </select>
+                </label>
+            }
+            if is_emanation {
+                <label>
+                    {localization.t("Creature size:")}
+                    <select onchange={size_change}>
+                        <option value={"1"} selected={state.size == 1}>{localization.t("Medium or smaller")}</option>
+                        <option value={"2"} selected={state.size == 2}>{localization.t("Large")}</option>
+                        <option value={"3"} selected={state.size == 3}>{localization.t("Huge")}</option>
+                        <option value={"4"} selected={state.size == 4}>{localization.t("Gargantuan")}</option>
+                    </select>
+                </label>
+            }
+            <svg
+                viewBox={format!("0 0 {} {}", grid_px, grid_px)}
+                width={grid_px.to_string()}
+                height={grid_px.to_string()}
+            >
+                { (-half_extent..half_extent).flat_map(|y| (-half_extent..half_extent).map(move |x| (x, y))).map(|(x, y)| {
+                    let relative: (i32, i32) = (x - origin_x, y - origin_y);
+                    let in_space: bool = is_emanation
+                        && (0..state.size as i32).contains(&relative.0)
+                        && (0..state.size as i32).contains(&relative.1);
+                    let class: &str = if in_space {
+                        "area-grid-origin-space"
+                    } else if covered.contains(&relative) {
+                        "area-grid-covered"
+                    } else {
+                        "area-grid-square"
+                    };
+                    html!{
+                        <rect
+                            key={format!("{},{}", x, y)}
+                            class={class}
+                            x={((x + half_extent) * CELL_PX).to_string()}
+                            y={((y + half_extent) * CELL_PX).to_string()}
+                            width={CELL_PX.to_string()}
+                            height={CELL_PX.to_string()}
+                            onclick={origin_change((x, y))}
+                        />
+                    }
+                }).collect::<Html>() }
+                if !is_emanation {
+                    <circle
+                        class="area-grid-origin"
+                        cx={((origin_x + half_extent) * CELL_PX).to_string()}
+                        cy={((origin_y + half_extent) * CELL_PX).to_string()}
+                        r={(CELL_PX / 4).to_string()}
+                    />
+                }
+            </svg>
+            <div>{localization.tf("{} ({} squares, click to move the origin)", &[&localization.area(area), &covered.len()])}</div>
+            if localization.units != Units::Squares {
+                <div>{localization.tf("One square is {}", &[&localization.distance(SQUARE_FT)])}</div>
+            }
+        </div>
+    }
+}
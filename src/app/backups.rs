@@ -0,0 +1,289 @@
+use std::cell::{Cell, RefCell};
+use std::collections::VecDeque;
+use js_sys::Date;
+use serde::{Deserialize, Serialize};
+use wasm_bindgen::JsValue;
+use yew::platform::spawn_local;
+use yew::prelude::*;
+
+use super::localization::Localization;
+use super::merge::same_card;
+use super::spell_book::SpellBookProps;
+use super::spell_card::SpellCard;
+use super::storage::{is_quota_error, Backend, KeyValueStore};
+
+const BACKUPS_KEY: &str = "SpellBookBackups";
+const MAX_RECENT: usize = 10;
+const MAX_DAILY: usize = 14;
+
+thread_local! {
+    // Books saved while a backup is being written wait here, so each backup reads the one before it
+    static PENDING_BACKUPS: RefCell<VecDeque<Vec<SpellBookProps>>> = RefCell::default();
+    static RECORDING_BACKUPS: Cell<bool> = const { Cell::new(false) };
+    // Why the latest backup could not be written, shown with the backups
+    static BACKUP_ERROR: RefCell<Option<String>> = RefCell::default();
+}
+
+#[derive(PartialEq, Clone, Serialize, Deserialize)]
+pub struct Snapshot {
+    // Milliseconds since the epoch, as given by `Date.now()`
+    pub taken_at: f64,
+    pub books: Vec<SpellBookProps>,
+}
+
+// The last changes, plus the first state seen on each day
+#[derive(PartialEq, Clone, Default, Serialize, Deserialize)]
+pub struct Backups {
+    pub recent: Vec<Snapshot>,
+    pub daily: Vec<Snapshot>,
+}
+
+impl Backups {
+    pub async fn load(store: &Backend) -> Backups {
+        store.get::<Backups>(BACKUPS_KEY).await.ok().flatten().unwrap_or_default()
+    }
+
+    // Newest first, daily snapshots that are also among the recent ones are left out
+    pub fn all(&self) -> Vec<Snapshot> {
+        let mut snapshots: Vec<Snapshot> = self.recent.clone();
+        snapshots.extend(self.daily.iter().filter(|daily| !self.recent.iter().any(|recent| recent.taken_at == daily.taken_at)).cloned());
+        snapshots.sort_by(|a, b| b.taken_at.total_cmp(&a.taken_at));
+        snapshots
+    }
+}
+
+pub fn local_time_string(milliseconds: f64) -> String {
+    Date::new(&JsValue::from_f64(milliseconds)).to_locale_string("default", &JsValue::UNDEFINED).into()
+}
+
+fn day_of(milliseconds: f64) -> String {
+    let date: Date = Date::new(&JsValue::from_f64(milliseconds));
+    format!("{}-{}-{}", date.get_full_year(), date.get_month(), date.get_date())
+}
+
+// Called whenever the books are saved, backups are recorded one at a time in the order they come in
+pub async fn record_backup(store: &Backend, books: &[SpellBookProps]) {
+    PENDING_BACKUPS.with_borrow_mut(|pending| pending.push_back(books.to_vec()));
+    if RECORDING_BACKUPS.replace(true) {
+        return;
+    }
+    while let Some(books) = PENDING_BACKUPS.with_borrow_mut(|pending| pending.pop_front()) {
+        let result: Result<(), String> = record_snapshot(store, &books).await;
+        BACKUP_ERROR.set(result.err());
+    }
+    RECORDING_BACKUPS.set(false);
+}
+
+pub fn backup_error() -> Option<String> {
+    BACKUP_ERROR.with_borrow(|error| error.clone())
+}
+
+async fn record_snapshot(store: &Backend, books: &[SpellBookProps]) -> Result<(), String> {
+    // Backups that cannot be read are left as they are rather than replaced by this one snapshot
+    let mut backups: Backups = store.get::<Backups>(BACKUPS_KEY).await?.unwrap_or_default();
+    if backups.recent.last().is_some_and(|last| last.books == books) {
+        return Ok(());
+    }
+    let snapshot: Snapshot = Snapshot { taken_at: Date::now(), books: books.to_vec() };
+    if !backups.daily.iter().any(|daily| day_of(daily.taken_at) == day_of(snapshot.taken_at)) {
+        backups.daily.push(snapshot.clone());
+    }
+    backups.recent.push(snapshot);
+    trim(&mut backups.recent, MAX_RECENT);
+    trim(&mut backups.daily, MAX_DAILY);
+    write_backups(store, &mut backups).await
+}
+
+// When storage is full the oldest snapshots make room, the books themselves are saved separately.
+// Any other failure leaves the stored backups as they are
+async fn write_backups(store: &impl KeyValueStore, backups: &mut Backups) -> Result<(), String> {
+    loop {
+        match store.set(BACKUPS_KEY, &*backups).await {
+            Ok(()) => return Ok(()),
+            Err(err) if is_quota_error(&err) => {
+                if backups.daily.len() > 1 {
+                    backups.daily.remove(0);
+                } else if backups.recent.len() > 1 {
+                    backups.recent.remove(0);
+                } else {
+                    _ = store.delete(BACKUPS_KEY).await;
+                    return Err(err);
+                }
+            },
+            Err(err) => return Err(err),
+        }
+    }
+}
+
+fn trim(snapshots: &mut Vec<Snapshot>, max: usize) {
+    if snapshots.len() > max {
+        snapshots.drain(..snapshots.len() - max);
+    }
+}
+
+#[derive(PartialEq, Default)]
+pub struct BookDiff {
+    pub added: Vec<String>,
+    pub removed: Vec<String>,
+    pub changed: Vec<String>,
+}
+
+// What restoring `snapshot` would change compared to `current`
+pub fn diff_book(snapshot: &[SpellCard], current: &[SpellCard]) -> BookDiff {
+    let mut diff: BookDiff = BookDiff::default();
+    for card in snapshot {
+        match current.iter().find(|c| same_card(c, card)) {
+            None => diff.added.push(card.spell_name.clone()),
+            Some(current_card) if current_card != card => diff.changed.push(card.spell_name.clone()),
+            Some(_) => (),
+        }
+    }
+    diff.removed = current.iter()
+        .filter(|card| !snapshot.iter().any(|c| same_card(c, card)))
+        .map(|card| card.spell_name.clone())
+        .collect();
+    diff
+}
+
+#[derive(PartialEq, Properties)]
+pub struct BackupRestoreProps {
+    pub store: Backend,
+    pub current: Vec<SpellBookProps>,
+    pub on_restore: Callback<Vec<SpellBookProps>>,
+    // Picks card by card and field by field what to take from a book in the backup
+    pub on_merge: Callback<SpellBookProps>,
+}
+
+#[function_component]
+pub fn BackupRestore(props: &BackupRestoreProps) -> Html {
+    let BackupRestoreProps {
+        store,
+        current,
+        on_restore,
+        on_merge
+    } = props;
+
+    let localization: Localization = use_context::<Localization>().unwrap_or_default();
+
+    let snapshots: UseStateHandle<Option<Vec<Snapshot>>> = use_state(|| None);
+    {
+        let snapshots: UseStateHandle<Option<Vec<Snapshot>>> = snapshots.clone();
+        use_effect_with(store.clone(), move |store: &Backend| {
+            let store: Backend = store.clone();
+            spawn_local(async move {
+                snapshots.set(Some(Backups::load(&store).await.all()))
+            });
+        });
+    }
+    let restore = |books: &Vec<SpellBookProps>| {
+        let books: Vec<SpellBookProps> = books.clone();
+        on_restore.reform(move |_: MouseEvent| books.clone())
+    };
+    let merge = |book: &SpellBookProps| {
+        let book: SpellBookProps = book.clone();
+        on_merge.reform(move |_: MouseEvent| book.clone())
+    };
+    let diff_list = |label: &str, names: &[String]| html!{
+        if !names.is_empty() {
+            <div>{format!("{}: {}", localization.t(label), names.join(", "))}</div>
+        }
+    };
+
+    html! {
+        <div class="backup-restore">
+            if let Some(error) = backup_error() {
+                <p class="import-warnings">{localization.tf("The latest backup could not be saved: {}", &[&error])}</p>
+            }
+            if snapshots.is_none() {
+                <p>{localization.t("Loading backups…")}</p>
+            } else if snapshots.as_ref().is_some_and(|s| s.is_empty()) {
+                <p>{localization.t("No backups have been made yet.")}</p>
+            }
+            { snapshots.iter().flatten().map(|snapshot| {
+                html!{
+                    <div class="backup-snapshot" key={snapshot.taken_at.to_string()}>
+                        <b>{local_time_string(snapshot.taken_at)}</b>
+                        { snapshot.books.iter().map(|book| {
+                            let current_book: Option<&SpellBookProps> = current.iter().find(|c| c.display_name() == book.display_name());
+                            let current_spells: &[SpellCard] = current_book.map(|c| c.spells.as_slice()).unwrap_or_default();
+                            let diff: BookDiff = diff_book(&book.spells, current_spells);
+                            html!{
+                                <div class="backup-book">
+                                    {localization.tf("{}: {} cards", &[&book.display_name(), &book.spells.len()])}
+                                    if let Some(current_book) = current_book {
+                                        {localization.tf(" (currently {})", &[&current_book.spells.len()])}
+                                    } else {
+                                        {localization.t(" (not in your current spellbooks)")}
+                                    }
+                                    if diff == BookDiff::default() {
+                                        <div>{localization.t("Same as now")}</div>
+                                    }
+                                    {diff_list("Would bring back", &diff.added)}
+                                    {diff_list("Would remove", &diff.removed)}
+                                    {diff_list("Would change", &diff.changed)}
+                                    if current_book.is_some() && diff != BookDiff::default() {
+                                        <button onclick={merge(book)}>{localization.t("Merge into this book")}</button>
+                                    }
+                                </div>
+                            }
+                        }).collect::<Html>() }
+                        <button onclick={restore(&snapshot.books)}>{localization.t("Restore this backup")}</button>
+                    </div>
+                }
+            }).collect::<Html>() }
+        </div>
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use super::super::storage::{block_on, MemoryStore};
+
+    fn snapshot(taken_at: f64) -> Snapshot {
+        Snapshot { taken_at, books: vec![SpellBookProps { spells: vec![SpellCard::empty()], ..Default::default() }] }
+    }
+
+    fn backups(recent: usize, daily: usize) -> Backups {
+        Backups {
+            recent: (0..recent).map(|i| snapshot(i as f64)).collect(),
+            daily: (0..daily).map(|i| snapshot(100.0 + i as f64)).collect(),
+        }
+    }
+
+    fn stored_size(backups: &Backups) -> usize {
+        BACKUPS_KEY.len() + serde_json::to_string(backups).unwrap().len()
+    }
+
+    #[test]
+    fn a_full_storage_drops_the_oldest_daily_snapshots_first() {
+        let mut full: Backups = backups(3, 3);
+        let quota: usize = stored_size(&backups(3, 1));
+        let store: MemoryStore = MemoryStore::default().with_quota(quota);
+        assert_eq!(block_on(write_backups(&store, &mut full)), Ok(()));
+        assert_eq!(full.recent.len(), 3);
+        assert_eq!(full.daily.iter().map(|s| s.taken_at).collect::<Vec<f64>>(), vec![102.0]);
+        assert!(block_on(store.get::<Backups>(BACKUPS_KEY)) == Ok(Some(full)));
+    }
+
+    #[test]
+    fn a_full_storage_then_drops_the_oldest_recent_snapshots() {
+        let mut full: Backups = backups(3, 3);
+        let quota: usize = stored_size(&backups(2, 1));
+        let store: MemoryStore = MemoryStore::default().with_quota(quota);
+        assert_eq!(block_on(write_backups(&store, &mut full)), Ok(()));
+        assert_eq!(full.recent.iter().map(|s| s.taken_at).collect::<Vec<f64>>(), vec![1.0, 2.0]);
+        assert_eq!(full.daily.len(), 1);
+    }
+
+    #[test]
+    fn other_failures_keep_the_stored_backups() {
+        let store: MemoryStore = MemoryStore::default();
+        block_on(store.set(BACKUPS_KEY, &backups(3, 3))).unwrap();
+        let mut new_backups: Backups = backups(4, 3);
+        let result: Result<(), String> = block_on(write_backups(&store.with_write_error("UnknownError: the disk is gone"), &mut new_backups));
+        assert_eq!(result, Err("UnknownError: the disk is gone".to_string()));
+        assert_eq!(new_backups.recent.len(), 4);
+        assert!(block_on(store.get::<Backups>(BACKUPS_KEY)) == Ok(Some(backups(3, 3))));
+    }
+}
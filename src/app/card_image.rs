@@ -0,0 +1,193 @@
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use gloo_net::http::Request;
+use wasm_bindgen::closure::Closure;
+use wasm_bindgen::{JsCast, JsValue};
+use web_sys::{window, CanvasRenderingContext2d, CssRule, CssStyleSheet, Document, DomRect, Element, EventTarget, HtmlCanvasElement, HtmlImageElement, HtmlSelectElement, StyleSheetList, XmlSerializer};
+use yew::platform::spawn_local;
+use yew::prelude::*;
+
+use super::file_io::{download_file, download_url, file_name_for};
+use super::localization::Localization;
+
+// Font files copied next to index.html by Trunk, with the family names used in index.scss
+const CARD_FONTS: [(&str, &str, &str, &str); 4] = [
+    ("Standard text", "normal", "Oswald-Light.ttf", "font/ttf"),
+    ("Standard text", "bold", "BrassLBr.ttf", "font/ttf"),
+    ("Highlight text", "normal", "Kirsty%20Rg.otf", "font/otf"),
+    ("Actions", "normal", "Pathfinder2eActions.ttf", "font/ttf"),
+];
+
+// CSS pixels per inch, used to go from the card's on-screen size to the requested DPI
+const CSS_DPI: f64 = 96.0;
+
+async fn embedded_font_faces() -> Result<String, String> {
+    let mut css: String = String::new();
+    for (family, weight, file, mime_type) in CARD_FONTS {
+        let bytes: Vec<u8> = Request::get(file).send().await
+            .map_err(|err| err.to_string())?
+            .binary().await
+            .map_err(|err| err.to_string())?;
+        css.push_str(&format!(
+            "@font-face {{ font-family: \"{}\"; font-weight: {}; src: url(data:{};base64,{}); }}\n",
+            family, weight, mime_type, STANDARD.encode(bytes)
+        ));
+    }
+    Ok(css)
+}
+
+// Copies the page's own style rules, leaving out the font faces that point at files
+fn page_css(document: &Document) -> String {
+    let mut css: String = String::new();
+    let sheets: StyleSheetList = document.style_sheets();
+    for sheet_index in 0..sheets.length() {
+        let sheet: Option<CssStyleSheet> = sheets.item(sheet_index).and_then(|s| s.dyn_into::<CssStyleSheet>().ok());
+        let rules = sheet.and_then(|s| s.css_rules().ok());
+        if let Some(rules) = rules {
+            for rule_index in 0..rules.length() {
+                if let Some(rule) = rules.item(rule_index) {
+                    if rule.type_() != CssRule::FONT_FACE_RULE {
+                        css.push_str(&rule.css_text());
+                        css.push('\n');
+                    }
+                }
+            }
+        }
+    }
+    css
+}
+
+// Builds a standalone SVG of a rendered `.spellcard` element, returning it with its size in CSS pixels
+pub async fn card_svg(card: &Element, localization: Localization) -> Result<(String, f64, f64), String> {
+    let document: Document = window().and_then(|w| w.document()).ok_or(localization.t("No document"))?;
+    let rect: DomRect = card.get_bounding_client_rect();
+    let (width, height) = (rect.width(), rect.height());
+    let markup: String = XmlSerializer::new()
+        .and_then(|serializer| serializer.serialize_to_string(card))
+        .map_err(|_| localization.t("Could not serialise the card"))?;
+    let css: String = format!(
+        "{}{}\n.spellcard {{ margin: 0 !important; }}",
+        embedded_font_faces().await?,
+        page_css(&document)
+    );
+    let svg: String = format!(
+        concat!(
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{w}\" height=\"{h}\" viewBox=\"0 0 {w} {h}\">",
+            "<foreignObject x=\"0\" y=\"0\" width=\"{w}\" height=\"{h}\">",
+            "<div xmlns=\"http://www.w3.org/1999/xhtml\"><style><![CDATA[{css}]]></style>{markup}</div>",
+            "</foreignObject></svg>"
+        ),
+        w = width, h = height, css = css, markup = markup
+    );
+    Ok((svg, width, height))
+}
+
+pub fn svg_data_url(svg: &str) -> String {
+    format!("data:image/svg+xml;base64,{}", STANDARD.encode(svg))
+}
+
+// Draws the SVG onto a canvas scaled to the given DPI and downloads the result as a PNG
+pub fn download_png(file_name: String, svg: &str, width: f64, height: f64, dpi: u32) -> Result<(), JsValue> {
+    let document: Document = window().and_then(|w| w.document()).ok_or(JsValue::from_str("No document"))?;
+    let scale: f64 = dpi as f64 / CSS_DPI;
+    let canvas: HtmlCanvasElement = document.create_element("canvas")?.dyn_into::<HtmlCanvasElement>()?;
+    canvas.set_width((width * scale).round() as u32);
+    canvas.set_height((height * scale).round() as u32);
+    let image: HtmlImageElement = HtmlImageElement::new()?;
+
+    let loaded_image: HtmlImageElement = image.clone();
+    let on_load: Closure<dyn FnMut()> = Closure::once(move || {
+        let context: Option<CanvasRenderingContext2d> = canvas.get_context("2d").ok()
+            .flatten()
+            .and_then(|c| c.dyn_into::<CanvasRenderingContext2d>().ok());
+        if let Some(context) = context {
+            _ = context.scale(scale, scale);
+            _ = context.draw_image_with_html_image_element(&loaded_image, 0.0, 0.0);
+            if let Ok(url) = canvas.to_data_url_with_type("image/png") {
+                _ = download_url(&file_name, &url);
+            }
+        }
+    });
+    image.set_onload(Some(on_load.as_ref().unchecked_ref()));
+    // The image keeps the callback until it has loaded
+    on_load.forget();
+    image.set_src(&svg_data_url(svg));
+    Ok(())
+}
+
+#[derive(PartialEq, Properties)]
+pub struct CardImageExportProps {
+    // Element containing the rendered card
+    pub card_ref: NodeRef,
+    pub spell_name: String,
+}
+
+#[function_component]
+pub fn CardImageExport(props: &CardImageExportProps) -> Html {
+    let CardImageExportProps {
+        card_ref,
+        spell_name
+    } = props;
+
+    let localization: Localization = use_context::<Localization>().unwrap_or_default();
+
+    let dpi: UseStateHandle<u32> = use_state(|| 300);
+    let error: UseStateHandle<Option<String>> = use_state(|| None);
+
+    // # Callback functions
+    let dpi_change: Callback<Event> = {
+        let dpi: UseStateHandle<u32> = dpi.clone();
+
+        Callback::from(move |e: Event| {
+            let target: Option<EventTarget> = e.target();
+
+            let input: Option<HtmlSelectElement> = target.and_then(|t: EventTarget| t.dyn_into::<HtmlSelectElement>().ok());
+            if let Some(input) = input {
+                dpi.set(input.value().parse::<u32>().unwrap_or(300))
+            }
+        })
+    };
+    let export = |as_png: bool| {
+        let card_ref: NodeRef = card_ref.clone();
+        let spell_name: String = spell_name.clone();
+        let dpi: u32 = *dpi;
+        let error: UseStateHandle<Option<String>> = error.clone();
+
+        Callback::from(move |_: MouseEvent| {
+            let card: Option<Element> = card_ref.cast::<Element>()
+                .and_then(|container| container.query_selector(".spellcard").ok().flatten());
+            let spell_name: String = spell_name.clone();
+            let error: UseStateHandle<Option<String>> = error.clone();
+            if let Some(card) = card {
+                spawn_local(async move {
+                    match card_svg(&card, localization).await {
+                        Ok((svg, width, height)) => {
+                            let result: Result<(), JsValue> = if as_png {
+                                download_png(file_name_for(&spell_name, "png"), &svg, width, height, dpi)
+                            } else {
+                                download_file(&file_name_for(&spell_name, "svg"), "image/svg+xml", &svg)
+                            };
+                            error.set(result.err().map(|_| localization.t("The download could not be started")))
+                        },
+                        Err(err) => error.set(Some(err)),
+                    }
+                });
+            }
+        })
+    };
+
+    html! {
+        <div class="card-image-export">
+            <button onclick={export(false)}>{"SVG"}</button>
+            <button onclick={export(true)}>{"PNG"}</button>
+            <select onchange={dpi_change}>
+                { [150u32, 300, 600].iter().map(|option| {
+                    html!{<option value={option.to_string()} selected={*option == *dpi}>{format!("{} DPI", option)}</option>}
+                }).collect::<Html>() }
+            </select>
+            if let Some(err) = &*error {
+                <span class="import-warnings">{err}</span>
+            }
+        </div>
+    }
+}
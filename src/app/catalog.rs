@@ -0,0 +1,222 @@
+use gloo_net::http::{Request, Response};
+use wasm_bindgen::JsCast;
+use web_sys::{EventTarget, HtmlInputElement};
+use yew::platform::spawn_local;
+use yew::prelude::*;
+
+use super::filters::{FilterBar, SpellFilter};
+use super::localization::Localization;
+use super::spell_book::{CharacterProfile, SpellBookProps};
+use super::spell_card::{generate_id, SpellCard, SpellType};
+use super::terminology::{renamed_spell, same_spell_name};
+
+// Copied next to the app by trunk and only fetched once the catalog or the Pathbuilder import is opened
+const CATALOG_URL: &str = "core-spells.json";
+
+pub async fn fetch_catalog(localization: Localization) -> Result<Vec<SpellCard>, String> {
+    let response: Response = Request::get(CATALOG_URL).send().await.map_err(|err| err.to_string())?;
+    if !response.ok() {
+        return Err(localization.tf("The catalog could not be loaded (status {})", &[&response.status()]));
+    }
+    response.json::<Vec<SpellCard>>().await.map_err(|err| err.to_string())
+}
+
+// The card added to a spellbook gets its own id, so editing it there leaves the catalog untouched
+pub fn copy_from_catalog(card: &SpellCard) -> SpellCard {
+    SpellCard { id: generate_id(), ..card.clone() }
+}
+
+fn rarity(card: &SpellCard) -> Option<&str> {
+    card.traits.iter()
+        .map(|t| t.as_str())
+        .find(|t| ["Uncommon", "Rare", "Unique"].iter().any(|rarity| t.eq_ignore_ascii_case(rarity)))
+}
+
+// The character's spell lists, such as "arcane or occult"
+fn spell_lists(character: &CharacterProfile, localization: Localization) -> String {
+    character.traditions().iter()
+        .map(|tradition| localization.t(&tradition.to_lowercase()))
+        .collect::<Vec<String>>()
+        .join(&format!(" {} ", localization.t("or")))
+}
+
+// Why the character could not learn the spell, empty when it can
+pub fn exclusion_reasons(card: &SpellCard, character: &CharacterProfile, localization: Localization) -> Vec<String> {
+    let mut reasons: Vec<String> = Vec::new();
+    if card.spell_type == SpellType::Focus {
+        reasons.push(localization.t("Focus spells are granted by class features"));
+    }
+    let traditions: Vec<String> = character.traditions();
+    let on_list: bool = card.traditions.iter().any(|t| traditions.iter().any(|c| c.eq_ignore_ascii_case(t)));
+    // Rituals belong to no tradition
+    if card.spell_type != SpellType::Ritual && !on_list {
+        reasons.push(localization.tf("Not on the {} spell list", &[&spell_lists(character, localization)]));
+    }
+    let max_rank: u8 = character.max_spell_rank();
+    if card.spell_type != SpellType::Cantrip && card.spell_level > max_rank {
+        reasons.push(localization.tf("Rank {} is above rank {}, the highest at level {}", &[&card.spell_level, &max_rank, &character.level]));
+    }
+    match rarity(card) {
+        Some(rarity) if rarity.eq_ignore_ascii_case("uncommon") && !character.uncommon_unlocked =>
+            reasons.push(localization.t("Uncommon, and uncommon spells are not unlocked")),
+        Some(rarity) if !rarity.eq_ignore_ascii_case("uncommon") && !character.rare_unlocked =>
+            reasons.push(localization.tf("{}, and rare spells are not unlocked", &[&localization.t(rarity)])),
+        _ => (),
+    }
+    reasons
+}
+
+#[derive(PartialEq, Properties)]
+pub struct CatalogProps {
+    pub filter: SpellFilter,
+    // Names of the spells already in the current spellbook
+    pub owned: Vec<String>,
+    // Character of the current spellbook, which limits the spells offered
+    #[prop_or_default]
+    pub character: Option<CharacterProfile>,
+    // Spellbooks other than the current one, their cards are offered next to the catalog's
+    #[prop_or_default]
+    pub other_books: Vec<SpellBookProps>,
+    pub on_filter_change: Callback<SpellFilter>,
+    pub on_character_change: Callback<CharacterProfile>,
+    pub on_add: Callback<SpellCard>,
+}
+
+#[function_component]
+pub fn Catalog(props: &CatalogProps) -> Html {
+    let CatalogProps {
+        filter,
+        owned,
+        character,
+        other_books,
+        on_filter_change,
+        on_character_change,
+        on_add
+    } = props;
+
+    let localization: Localization = use_context::<Localization>().unwrap_or_default();
+
+    let catalog: UseStateHandle<Option<Result<Vec<SpellCard>, String>>> = use_state(|| None);
+    {
+        let catalog: UseStateHandle<Option<Result<Vec<SpellCard>, String>>> = catalog.clone();
+        use_effect_with((), move |_| {
+            spawn_local(async move {
+                catalog.set(Some(fetch_catalog(localization).await))
+            });
+        });
+    }
+
+    let show_all: UseStateHandle<bool> = use_state(|| false);
+    // Only a character with a spell list narrows the catalog down
+    let character: Option<&CharacterProfile> = character.as_ref().filter(|c| !c.traditions().is_empty());
+
+    // # Callback functions
+    let add = |card: &SpellCard| {
+        let card: SpellCard = card.clone();
+        on_add.reform(move |_: MouseEvent| copy_from_catalog(&card))
+    };
+    let character_toggle = |update: fn(&mut CharacterProfile, bool)| {
+        let character: Option<CharacterProfile> = character.cloned();
+        let on_character_change: Callback<CharacterProfile> = on_character_change.clone();
+
+        Callback::from(move |e: Event| {
+            let target: Option<EventTarget> = e.target();
+
+            let input: Option<HtmlInputElement> = target.and_then(|t: EventTarget| t.dyn_into::<HtmlInputElement>().ok());
+            if let (Some(input), Some(character)) = (input, character.as_ref()) {
+                let mut new_character: CharacterProfile = character.clone();
+                update(&mut new_character, input.checked());
+                on_character_change.emit(new_character)
+            }
+        })
+    };
+    let show_all_change: Callback<Event> = {
+        let show_all: UseStateHandle<bool> = show_all.clone();
+
+        Callback::from(move |e: Event| {
+            let target: Option<EventTarget> = e.target();
+
+            let input: Option<HtmlInputElement> = target.and_then(|t: EventTarget| t.dyn_into::<HtmlInputElement>().ok());
+            if let Some(input) = input {
+                show_all.set(input.checked())
+            }
+        })
+    };
+
+    html! {
+        <div class="catalog">
+            if let Some(character) = character {
+                <div class="catalog-character">
+                    <div>
+                        {localization.tf(
+                            "Showing what {} can learn: the {} spell list up to rank {}.",
+                            &[&character.name, &spell_lists(character, localization), &character.max_spell_rank()]
+                        )}
+                    </div>
+                    <label>
+                        <input type="checkbox" checked={character.uncommon_unlocked} onchange={character_toggle(|c, v| c.uncommon_unlocked = v)} />
+                        {localization.t("GM unlocked uncommon spells")}
+                    </label>
+                    <label>
+                        <input type="checkbox" checked={character.rare_unlocked} onchange={character_toggle(|c, v| c.rare_unlocked = v)} />
+                        {localization.t("GM unlocked rare spells")}
+                    </label>
+                    <label>
+                        <input type="checkbox" checked={*show_all} onchange={show_all_change} />
+                        {localization.t("Show all spells")}
+                    </label>
+                </div>
+            }
+            <FilterBar filter={filter.clone()} on_change={on_filter_change} />
+            { match &*catalog {
+                None => html!{<p>{localization.t("Loading the catalog…")}</p>},
+                Some(Err(err)) => html!{<p class="import-warnings">{err}</p>},
+                Some(Ok(cards)) => {
+                    let candidates: Vec<(String, &SpellCard)> = cards.iter()
+                        .map(|card| (localization.t("Core catalog"), card))
+                        .chain(other_books.iter().flat_map(|book| book.spells.iter().map(|card| (book.display_name(), card))))
+                        .filter(|(_, card)| filter.matches(card))
+                        .collect();
+                    let checked: Vec<(String, &SpellCard, Vec<String>)> = candidates.into_iter()
+                        .map(|(source, card)| (source, card, character.map(|c| exclusion_reasons(card, c, localization)).unwrap_or_default()))
+                        .collect();
+                    let hidden: usize = if *show_all { 0 } else { checked.iter().filter(|(_, _, reasons)| !reasons.is_empty()).count() };
+                    html!{<>
+                        <p>
+                            {localization.tf("{} spells", &[&(checked.len() - hidden)])}
+                            if hidden > 0 {
+                                {localization.tf(", {} this character cannot learn are hidden", &[&hidden])}
+                            }
+                        </p>
+                        { checked.iter().filter(|(_, _, reasons)| *show_all || reasons.is_empty()).enumerate().map(|(index, (source, card, reasons))| {
+                            let in_book: bool = owned.iter().any(|name| same_spell_name(name, &card.spell_name));
+                            html!{
+                                <div class={classes!("catalog-entry", (!reasons.is_empty()).then_some("catalog-excluded"))} key={format!("{}-{}", index, card.spell_name)}>
+                                    {card.to_html()}
+                                    <div class="catalog-source">{source}</div>
+                                    if let Some(other_name) = renamed_spell(&card.spell_name) {
+                                        <div class="catalog-source">{localization.tf("Also known as {}", &[&other_name])}</div>
+                                    }
+                                    if !reasons.is_empty() {
+                                        <ul class="import-warnings">
+                                            { reasons.iter().map(|reason| html!{<li>{reason}</li>}).collect::<Html>() }
+                                        </ul>
+                                    }
+                                    <button onclick={add(card)}>
+                                        {localization.t(if in_book { "Add another copy" } else { "Add to my spellbook" })}
+                                    </button>
+                                    if in_book {
+                                        <span>{localization.t(" Already in this spellbook")}</span>
+                                    }
+                                </div>
+                            }
+                        }).collect::<Html>() }
+                    </>}
+                },
+            } }
+            <p class="catalog-license">
+                {localization.t("Spells adapted from Pathfinder Player Core, © Paizo Inc., used under the ORC License.")}
+            </p>
+        </div>
+    }
+}
@@ -0,0 +1,363 @@
+use std::fmt::Display;
+use std::sync::LazyLock;
+use regex::Regex;
+use wasm_bindgen::JsCast;
+use web_sys::{EventTarget, HtmlInputElement};
+use yew::prelude::*;
+
+use super::localization::Localization;
+use super::spell_card::{Defence, RollResult};
+
+static DAMAGE_TERM_REGEX: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"^(?:(\d*)d(\d+)|(\d+))$").unwrap());
+static DAMAGE_SIGN_REGEX: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"\s*([+-])\s*").unwrap());
+
+#[derive(PartialEq, Eq, PartialOrd, Ord, Clone, Copy)]
+pub enum DegreeOfSuccess {
+    CriticalSuccess,
+    Success,
+    Failure,
+    CriticalFailure,
+}
+
+impl Display for DegreeOfSuccess {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let degree_str: &str = match self {
+            DegreeOfSuccess::CriticalSuccess => "Critical Success",
+            DegreeOfSuccess::Success         => "Success",
+            DegreeOfSuccess::Failure         => "Failure",
+            DegreeOfSuccess::CriticalFailure => "Critical Failure",
+        };
+        write!(f, "{}", degree_str)
+    }
+}
+
+impl DegreeOfSuccess {
+    pub const ALL: [DegreeOfSuccess; 4] = [
+        DegreeOfSuccess::CriticalSuccess,
+        DegreeOfSuccess::Success,
+        DegreeOfSuccess::Failure,
+        DegreeOfSuccess::CriticalFailure,
+    ];
+
+    // A natural 20 improves the result one step, a natural 1 worsens it one step
+    fn upgrade(self) -> DegreeOfSuccess {
+        match self {
+            DegreeOfSuccess::CriticalSuccess |
+            DegreeOfSuccess::Success         => DegreeOfSuccess::CriticalSuccess,
+            DegreeOfSuccess::Failure         => DegreeOfSuccess::Success,
+            DegreeOfSuccess::CriticalFailure => DegreeOfSuccess::Failure,
+        }
+    }
+
+    fn downgrade(self) -> DegreeOfSuccess {
+        match self {
+            DegreeOfSuccess::CriticalSuccess => DegreeOfSuccess::Success,
+            DegreeOfSuccess::Success         => DegreeOfSuccess::Failure,
+            DegreeOfSuccess::Failure |
+            DegreeOfSuccess::CriticalFailure => DegreeOfSuccess::CriticalFailure,
+        }
+    }
+
+    // Multiplier applied to the damage roll, seen from the one rolling the d20.
+    // Only attacks and basic saves have fixed multipliers, other saves spell out their own effects
+    fn damage_multiplier(&self, defence: &Defence, basic_save: bool) -> Option<f64> {
+        match (defence, self) {
+            (Defence::ArmourClass, DegreeOfSuccess::CriticalSuccess) => Some(2.0),
+            (Defence::ArmourClass, DegreeOfSuccess::Success)         => Some(1.0),
+            (Defence::ArmourClass, _)                                => Some(0.0),
+            _ if !basic_save                                         => None,
+            (_, DegreeOfSuccess::CriticalSuccess)                    => Some(0.0),
+            (_, DegreeOfSuccess::Success)                            => Some(0.5),
+            (_, DegreeOfSuccess::Failure)                            => Some(1.0),
+            (_, DegreeOfSuccess::CriticalFailure)                    => Some(2.0),
+        }
+    }
+}
+
+impl RollResult {
+    pub fn degree(&self) -> DegreeOfSuccess {
+        match self {
+            RollResult::CriticalSuccess(_) => DegreeOfSuccess::CriticalSuccess,
+            RollResult::Success(_)         => DegreeOfSuccess::Success,
+            RollResult::Failure(_)         => DegreeOfSuccess::Failure,
+            RollResult::CriticalFailure(_) => DegreeOfSuccess::CriticalFailure,
+        }
+    }
+}
+
+pub fn degree_of_success(d20: u8, modifier: i16, dc: i16) -> DegreeOfSuccess {
+    // Worked out in i32 so no modifier or DC typed into the form can overflow
+    let total: i32 = d20 as i32 + modifier as i32;
+    let dc: i32 = dc as i32;
+    let degree: DegreeOfSuccess = if total >= dc + 10 {
+        DegreeOfSuccess::CriticalSuccess
+    } else if total >= dc {
+        DegreeOfSuccess::Success
+    } else if total <= dc - 10 {
+        DegreeOfSuccess::CriticalFailure
+    } else {
+        DegreeOfSuccess::Failure
+    };
+    match d20 {
+        20 => degree.upgrade(),
+        1  => degree.downgrade(),
+        _  => degree,
+    }
+}
+
+// Number of d20 faces, out of 20, resulting in each degree of success in `DegreeOfSuccess::ALL` order
+pub fn outcome_counts(modifier: i16, dc: i16) -> [u8; 4] {
+    let mut counts: [u8; 4] = [0; 4];
+    for d20 in 1..=20u8 {
+        counts[degree_of_success(d20, modifier, dc) as usize] += 1;
+    }
+    counts
+}
+
+// Damage dealt on average over all d20 faces, given the counts from `outcome_counts`.
+// None for saves that are not basic saves, as their damage is not fixed by the degree alone
+pub fn expected_damage(average: f64, defence: &Defence, basic_save: bool, counts: &[u8; 4]) -> Option<f64> {
+    DegreeOfSuccess::ALL.iter().zip(counts.iter())
+        .map(|(degree, count)| degree.damage_multiplier(defence, basic_save).map(|multiplier| average * multiplier * (*count as f64) / 20.0))
+        .sum()
+}
+
+// Average of a damage expression such as "6d6", "2d8+4" or "1d4 + 1d6 + 2"
+pub fn average_damage(expression: &str) -> Option<f64> {
+    let mut total: f64 = 0.0;
+    let mut sign: f64 = 1.0;
+    let normalised: String = DAMAGE_SIGN_REGEX.replace_all(expression.trim(), " $1 ").to_string();
+    let mut found_term: bool = false;
+    for token in normalised.split_whitespace() {
+        match token {
+            "+" => sign = 1.0,
+            "-" => sign = -1.0,
+            term => {
+                let captures = DAMAGE_TERM_REGEX.captures(term)?;
+                let value: f64 = if let Some(flat) = captures.get(3) {
+                    flat.as_str().parse::<f64>().ok()?
+                } else {
+                    let count: f64 = match captures.get(1).map(|c| c.as_str()) {
+                        Some("") | None => 1.0,
+                        Some(count) => count.parse::<f64>().ok()?,
+                    };
+                    let sides: f64 = captures.get(2)?.as_str().parse::<f64>().ok()?;
+                    count * (sides + 1.0) / 2.0
+                };
+                total += sign * value;
+                found_term = true;
+            }
+        }
+    }
+    if found_term { Some(total.max(0.0)) } else { None }
+}
+
+#[derive(PartialEq, Properties)]
+pub struct DegreeOfSuccessCalculatorProps {
+    pub defence: Defence,
+    pub basic_save: bool,
+    pub roll_effect: Vec<RollResult>,
+}
+
+struct CalculatorState {
+    modifier: i16,
+    dc: i16,
+    damage: String,
+}
+
+#[function_component]
+pub fn DegreeOfSuccessCalculator(props: &DegreeOfSuccessCalculatorProps) -> Html {
+    let DegreeOfSuccessCalculatorProps {
+        defence,
+        basic_save,
+        roll_effect
+    } = props;
+
+    let localization: Localization = use_context::<Localization>().unwrap_or_default();
+
+    let state: UseStateHandle<CalculatorState> = use_state(|| CalculatorState {
+        modifier: 10,
+        dc: 20,
+        damage: String::new(),
+    });
+
+    // For attacks the caster rolls against the target's AC, for saves the target rolls against the spell DC
+    let is_attack: bool = *defence == Defence::ArmourClass;
+    let (modifier_label, dc_label) = if is_attack {
+        ("Spell attack modifier:", "Target AC:")
+    } else {
+        ("Target save modifier:", "Spell DC:")
+    };
+
+    // # Callback functions
+    let modifier_change: Callback<Event> = {
+        let state: UseStateHandle<CalculatorState> = state.clone();
+
+        Callback::from(move |e: Event| {
+            let target: Option<EventTarget> = e.target();
+
+            let input: Option<HtmlInputElement> = target.and_then(|t: EventTarget| t.dyn_into::<HtmlInputElement>().ok());
+            if let Some(input) = input {
+                if let Ok(modifier) = input.value().parse::<i16>() {
+                    state.set(CalculatorState { modifier, dc: state.dc, damage: state.damage.clone() })
+                }
+            }
+        })
+    };
+    let dc_change: Callback<Event> = {
+        let state: UseStateHandle<CalculatorState> = state.clone();
+
+        Callback::from(move |e: Event| {
+            let target: Option<EventTarget> = e.target();
+
+            let input: Option<HtmlInputElement> = target.and_then(|t: EventTarget| t.dyn_into::<HtmlInputElement>().ok());
+            if let Some(input) = input {
+                if let Ok(dc) = input.value().parse::<i16>() {
+                    state.set(CalculatorState { modifier: state.modifier, dc, damage: state.damage.clone() })
+                }
+            }
+        })
+    };
+    let damage_change: Callback<Event> = {
+        let state: UseStateHandle<CalculatorState> = state.clone();
+
+        Callback::from(move |e: Event| {
+            let target: Option<EventTarget> = e.target();
+
+            let input: Option<HtmlInputElement> = target.and_then(|t: EventTarget| t.dyn_into::<HtmlInputElement>().ok());
+            if let Some(input) = input {
+                state.set(CalculatorState { modifier: state.modifier, dc: state.dc, damage: input.value() })
+            }
+        })
+    };
+
+    let counts: [u8; 4] = outcome_counts(state.modifier, state.dc);
+    let average: Option<f64> = average_damage(&state.damage);
+    let expected_damage: Option<f64> = average.and_then(|avg| expected_damage(avg, defence, *basic_save, &counts));
+
+    html! {
+        <div class="degree-calculator">
+            <label>
+                {localization.t(modifier_label)}
+                <input
+                    onchange={modifier_change}
+                    type="number"
+                    value={state.modifier.to_string()}
+                />
+            </label>
+            <label>
+                {localization.t(dc_label)}
+                <input
+                    onchange={dc_change}
+                    type="number"
+                    value={state.dc.to_string()}
+                />
+            </label>
+            <label>
+                {localization.t("Damage:")}
+                <input
+                    onchange={damage_change}
+                    type="text"
+                    placeholder={localization.t("e.g. 6d6")}
+                    value={state.damage.clone()}
+                />
+            </label>
+            <table>
+                { DegreeOfSuccess::ALL.iter().zip(counts.iter()).map(|(degree, count)| {
+                    let effect: Option<String> = roll_effect.iter().find(|rr| rr.degree() == *degree).map(|rr| rr.effect().to_string());
+                    html!{
+                        <tr key={degree.to_string()}>
+                            <td><b>{localization.t(&degree.to_string())}</b></td>
+                            <td>{format!("{}%", *count as u16 * 5)}</td>
+                            <td>{effect.unwrap_or_default()}</td>
+                        </tr>
+                    }
+                }).collect::<Html>() }
+            </table>
+            if let Some(expected_damage) = expected_damage {
+                <div>
+                    <b>{localization.t("Expected damage: ")}</b>{format!("{:.1}", expected_damage)}
+                    {localization.tf(" (average roll {})", &[&format!("{:.1}", average.unwrap_or_default())])}
+                </div>
+            } else if average.is_some() {
+                <div>{localization.t("Expected damage is only worked out for attacks and basic saves.")}</div>
+            }
+        </div>
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn beating_the_dc_by_ten_is_a_critical() {
+        assert!(degree_of_success(10, 10, 20) == DegreeOfSuccess::Success);
+        assert!(degree_of_success(9, 10, 20) == DegreeOfSuccess::Failure);
+        assert!(degree_of_success(10, 20, 20) == DegreeOfSuccess::CriticalSuccess);
+        assert!(degree_of_success(9, 20, 20) == DegreeOfSuccess::Success);
+        assert!(degree_of_success(10, 0, 20) == DegreeOfSuccess::CriticalFailure);
+        assert!(degree_of_success(11, 0, 20) == DegreeOfSuccess::Failure);
+    }
+
+    #[test]
+    fn natural_twenty_and_one_shift_one_step() {
+        // 20 + 0 against DC 40 is a critical failure, improved to a failure
+        assert!(degree_of_success(20, 0, 40) == DegreeOfSuccess::Failure);
+        assert!(degree_of_success(20, 0, 25) == DegreeOfSuccess::Success);
+        assert!(degree_of_success(20, 10, 20) == DegreeOfSuccess::CriticalSuccess);
+        // 1 + 30 against DC 20 is a critical success, worsened to a success
+        assert!(degree_of_success(1, 30, 20) == DegreeOfSuccess::Success);
+        assert!(degree_of_success(1, 20, 20) == DegreeOfSuccess::Failure);
+        assert!(degree_of_success(1, 0, 20) == DegreeOfSuccess::CriticalFailure);
+    }
+
+    #[test]
+    fn extreme_inputs_do_not_overflow() {
+        assert!(degree_of_success(5, i16::MAX, i16::MAX) == DegreeOfSuccess::Success);
+        assert!(degree_of_success(10, i16::MIN, i16::MAX) == DegreeOfSuccess::CriticalFailure);
+        assert!(degree_of_success(10, i16::MAX, i16::MIN) == DegreeOfSuccess::CriticalSuccess);
+        assert_eq!(outcome_counts(i16::MAX, i16::MIN), [19, 1, 0, 0]);
+    }
+
+    #[test]
+    fn outcome_counts_cover_all_faces() {
+        assert_eq!(outcome_counts(10, 20), [1, 10, 8, 1]);
+        assert_eq!(outcome_counts(-10, 20), [0, 0, 1, 19]);
+        assert_eq!(outcome_counts(40, 20), [19, 1, 0, 0]);
+    }
+
+    #[test]
+    fn basic_saves_halve_and_double_damage() {
+        let save: Defence = Defence::Reflex;
+        assert_eq!(expected_damage(10.0, &save, true, &[20, 0, 0, 0]), Some(0.0));
+        assert_eq!(expected_damage(10.0, &save, true, &[0, 20, 0, 0]), Some(5.0));
+        assert_eq!(expected_damage(10.0, &save, true, &[0, 0, 20, 0]), Some(10.0));
+        assert_eq!(expected_damage(10.0, &save, true, &[0, 0, 0, 20]), Some(20.0));
+        assert_eq!(expected_damage(10.0, &save, true, &[5, 5, 5, 5]), Some(8.75));
+    }
+
+    #[test]
+    fn other_saves_have_no_expected_damage() {
+        assert_eq!(expected_damage(10.0, &Defence::Will, false, &[5, 5, 5, 5]), None);
+        assert_eq!(expected_damage(10.0, &Defence::Fortitude, false, &[0, 0, 20, 0]), None);
+    }
+
+    #[test]
+    fn attacks_double_on_a_critical_hit() {
+        let attack: Defence = Defence::ArmourClass;
+        assert_eq!(expected_damage(10.0, &attack, false, &[20, 0, 0, 0]), Some(20.0));
+        assert_eq!(expected_damage(10.0, &attack, false, &[0, 20, 0, 0]), Some(10.0));
+        assert_eq!(expected_damage(10.0, &attack, false, &[0, 0, 10, 10]), Some(0.0));
+    }
+
+    #[test]
+    fn average_damage_reads_dice_expressions() {
+        assert_eq!(average_damage("6d6"), Some(21.0));
+        assert_eq!(average_damage("2d8+4"), Some(13.0));
+        assert_eq!(average_damage("1d4 + 1d6 + 2"), Some(8.0));
+        assert_eq!(average_damage("d8 - 1"), Some(3.5));
+        assert_eq!(average_damage("fire"), None);
+        assert_eq!(average_damage(""), None);
+    }
+}
@@ -0,0 +1,102 @@
+use gloo_storage::{LocalStorage, Storage};
+use js_sys::Date;
+use serde::{Deserialize, Serialize};
+use yew::prelude::*;
+
+use super::backups::local_time_string;
+use super::localization::Localization;
+use super::router::Route;
+use super::spell_card::SpellCard;
+
+const DRAFTS_KEY: &str = "CreatorDrafts";
+
+#[derive(PartialEq, Clone, Serialize, Deserialize)]
+pub struct Draft {
+    pub key: String,
+    pub card: SpellCard,
+    // Milliseconds since the epoch, as given by `Date.now()`
+    pub saved_at: f64,
+}
+
+impl Draft {
+    pub fn name(&self) -> String {
+        if self.card.spell_name.trim().is_empty() { "Untitled spell".to_string() } else { self.card.spell_name.clone() }
+    }
+
+    // Page the draft was started from, so resuming it opens the same creator
+    pub fn route(&self) -> Route {
+        match self.key.strip_prefix("card-") {
+            Some(id) => Route::EditCard(id.to_string()),
+            None     => Route::NewCard,
+        }
+    }
+
+    pub fn saved_at_string(&self) -> String {
+        local_time_string(self.saved_at)
+    }
+}
+
+// Drafts are keyed by what the creator is working on: "new" for a new card, "card-<id>" when editing
+pub fn draft_key(editing: Option<&str>) -> String {
+    match editing {
+        Some(id) => format!("card-{}", id),
+        None     => "new".to_string(),
+    }
+}
+
+pub fn load_drafts() -> Vec<Draft> {
+    LocalStorage::get::<Vec<Draft>>(DRAFTS_KEY).unwrap_or_default()
+}
+
+pub fn find_draft(key: &str) -> Option<Draft> {
+    load_drafts().into_iter().find(|draft| draft.key == key)
+}
+
+pub fn save_draft(key: &str, card: &SpellCard) {
+    let mut drafts: Vec<Draft> = load_drafts();
+    drafts.retain(|draft| draft.key != key);
+    drafts.push(Draft { key: key.to_string(), card: card.clone(), saved_at: Date::now() });
+    _ = LocalStorage::set(DRAFTS_KEY, drafts);
+}
+
+pub fn discard_draft(key: &str) {
+    let mut drafts: Vec<Draft> = load_drafts();
+    drafts.retain(|draft| draft.key != key);
+    _ = LocalStorage::set(DRAFTS_KEY, drafts);
+}
+
+#[function_component]
+pub fn DraftList() -> Html {
+    let localization: Localization = use_context::<Localization>().unwrap_or_default();
+    let drafts: UseStateHandle<Vec<Draft>> = use_state(load_drafts);
+
+    let discard = |key: &str| {
+        let drafts: UseStateHandle<Vec<Draft>> = drafts.clone();
+        let key: String = key.to_string();
+
+        Callback::from(move |_: MouseEvent| {
+            discard_draft(&key);
+            drafts.set(load_drafts())
+        })
+    };
+
+    html! {
+        if !drafts.is_empty() {
+            <div class="draft-list">
+                <b>{localization.t("Unsaved drafts")}</b>
+                <ul>
+                    { drafts.iter().map(|draft| {
+                        let editing: &str = if draft.route() == Route::NewCard { "new card" } else { "edited card" };
+                        html!{
+                            <li key={draft.key.clone()}>
+                                <a href={draft.route().to_hash()}>{draft.name()}</a>
+                                {localization.tf(" ({}, saved {}) ", &[&localization.t(editing), &draft.saved_at_string()])}
+                                <button onclick={discard(&draft.key)}>{localization.t("Discard")}</button>
+                            </li>
+                        }
+                    }).collect::<Html>() }
+                </ul>
+            </div>
+        }
+    }
+}
@@ -0,0 +1,320 @@
+use std::sync::LazyLock;
+use regex::Regex;
+use yew::prelude::*;
+
+use super::localization::Localization;
+use super::spell_card::{SpellCard, SpellOverview};
+
+static DURATION_AMOUNT_REGEX: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"(\d+)\s*(round|minute|min|hour|day)").unwrap());
+
+#[derive(PartialEq, Eq, Clone, Copy)]
+pub enum EncounterDuration {
+    Rounds(u16),
+    UntilNextTurn,
+    Unlimited,
+}
+
+impl EncounterDuration {
+    // Reads durations such as "1 minute", "3 rounds", "sustained up to 1 minute" or "until the start of your next turn"
+    pub fn parse(duration: &str) -> (EncounterDuration, bool) {
+        let lowered: String = duration.to_lowercase();
+        let sustained: bool = lowered.contains("sustain");
+        let parsed: EncounterDuration = if let Some(captures) = DURATION_AMOUNT_REGEX.captures(&lowered) {
+            let amount: u16 = captures[1].parse::<u16>().unwrap_or(1);
+            match &captures[2] {
+                "round" => EncounterDuration::Rounds(amount),
+                "minute" | "min" => EncounterDuration::Rounds(amount.saturating_mul(10)),
+                _ => EncounterDuration::Unlimited,
+            }
+        } else if lowered.contains("next turn") {
+            EncounterDuration::UntilNextTurn
+        } else if lowered.contains("minute") {
+            EncounterDuration::Rounds(10)
+        } else if lowered.contains("round") {
+            EncounterDuration::Rounds(1)
+        } else if sustained {
+            // "sustained" on its own lasts for up to 10 minutes
+            EncounterDuration::Rounds(100)
+        } else {
+            EncounterDuration::Unlimited
+        };
+        (parsed, sustained)
+    }
+}
+
+#[derive(PartialEq, Clone)]
+pub struct ActiveSpell {
+    pub id: usize,
+    pub spell_name: String,
+    pub duration_text: String,
+    pub cast_round: u16,
+    pub remaining_rounds: Option<u16>,
+    pub sustained: bool,
+    pub sustained_this_round: bool,
+}
+
+impl ActiveSpell {
+    pub fn cast(id: usize, card: &SpellCard, round: u16) -> ActiveSpell {
+        let duration_text: String = match card.get_overview_element("Duration") {
+            Some(SpellOverview::Duration(duration)) => duration,
+            _ => String::new(),
+        };
+        let (duration, sustained) = EncounterDuration::parse(&duration_text);
+        ActiveSpell {
+            id,
+            spell_name: card.spell_name.clone(),
+            duration_text,
+            cast_round: round,
+            remaining_rounds: match duration {
+                EncounterDuration::Rounds(rounds) => Some(rounds),
+                EncounterDuration::UntilNextTurn => Some(1),
+                EncounterDuration::Unlimited => None,
+            },
+            sustained,
+            // Casting a sustained spell counts as the round's sustain
+            sustained_this_round: true,
+        }
+    }
+}
+
+#[derive(PartialEq, Clone)]
+pub struct EncounterState {
+    pub round: u16,
+    pub active: Vec<ActiveSpell>,
+    pub expired: Vec<ActiveSpell>,
+    next_id: usize,
+}
+
+impl EncounterState {
+    pub fn new() -> EncounterState {
+        EncounterState { round: 1, active: Vec::new(), expired: Vec::new(), next_id: 0 }
+    }
+
+    pub fn cast(&self, card: &SpellCard) -> EncounterState {
+        let mut new_state: EncounterState = self.clone();
+        new_state.active.push(ActiveSpell::cast(self.next_id, card, self.round));
+        new_state.next_id += 1;
+        new_state
+    }
+
+    pub fn sustain(&self, id: usize) -> EncounterState {
+        let mut new_state: EncounterState = self.clone();
+        for spell in new_state.active.iter_mut().filter(|s| s.id == id) {
+            spell.sustained_this_round = true;
+        }
+        new_state
+    }
+
+    pub fn dismiss(&self, id: usize) -> EncounterState {
+        let mut new_state: EncounterState = self.clone();
+        let (dismissed, active): (Vec<ActiveSpell>, Vec<ActiveSpell>) = new_state.active.into_iter().partition(|s| s.id == id);
+        new_state.active = active;
+        new_state.expired.extend(dismissed);
+        new_state
+    }
+
+    // Ends the current round, counting down durations and ending spells that ran out or were not sustained
+    pub fn next_round(&self) -> EncounterState {
+        let mut new_state: EncounterState = self.clone();
+        new_state.round += 1;
+        let mut still_active: Vec<ActiveSpell> = Vec::new();
+        for mut spell in new_state.active.into_iter() {
+            spell.remaining_rounds = spell.remaining_rounds.map(|r| r.saturating_sub(1));
+            let ran_out: bool = spell.remaining_rounds == Some(0);
+            let not_sustained: bool = spell.sustained && !spell.sustained_this_round;
+            spell.sustained_this_round = false;
+            if ran_out || not_sustained {
+                new_state.expired.push(spell);
+            } else {
+                still_active.push(spell);
+            }
+        }
+        new_state.active = still_active;
+        new_state
+    }
+}
+
+#[derive(PartialEq, Properties)]
+pub struct EncounterProps {
+    pub spells: Vec<SpellCard>,
+}
+
+#[function_component]
+pub fn Encounter(props: &EncounterProps) -> Html {
+    let EncounterProps {
+        spells
+    } = props;
+
+    let localization: Localization = use_context::<Localization>().unwrap_or_default();
+
+    let state: UseStateHandle<EncounterState> = use_state(EncounterState::new);
+
+    // # Callback functions
+    let cast_spell = |card: &SpellCard| {
+        let state: UseStateHandle<EncounterState> = state.clone();
+        let card: SpellCard = card.clone();
+
+        Callback::from(move |_| {
+            state.set(state.cast(&card))
+        })
+    };
+    let sustain_spell = |id: usize| {
+        let state: UseStateHandle<EncounterState> = state.clone();
+
+        Callback::from(move |_| {
+            state.set(state.sustain(id))
+        })
+    };
+    let dismiss_spell = |id: usize| {
+        let state: UseStateHandle<EncounterState> = state.clone();
+
+        Callback::from(move |_| {
+            state.set(state.dismiss(id))
+        })
+    };
+    let next_round: Callback<MouseEvent> = {
+        let state: UseStateHandle<EncounterState> = state.clone();
+
+        Callback::from(move |_| {
+            state.set(state.next_round())
+        })
+    };
+    let reset_encounter: Callback<MouseEvent> = {
+        let state: UseStateHandle<EncounterState> = state.clone();
+
+        Callback::from(move |_| {
+            state.set(EncounterState::new())
+        })
+    };
+
+    html! {
+        <div class="encounter">
+            <div class="encounter-round">
+                <h2>{localization.tf("Round {}", &[&state.round])}</h2>
+                <button onclick={next_round}>{localization.t("Next round")}</button>
+                <button onclick={reset_encounter}>{localization.t("End encounter")}</button>
+            </div>
+            <h3>{localization.t("Active spells")}</h3>
+            if state.active.is_empty() {
+                <p>{localization.t("No active spells")}</p>
+            }
+            <ul class="encounter-active">
+                { state.active.iter().map(|spell| {
+                    let needs_sustain: bool = spell.sustained && !spell.sustained_this_round;
+                    html!{
+                        <li key={spell.id} class={classes!(needs_sustain.then_some("needs-sustain"))}>
+                            <b>{spell.spell_name.clone()}</b>
+                            {localization.tf(" (cast in round {})", &[&spell.cast_round])}
+                            if !spell.duration_text.is_empty() {
+                                {format!(", {}", spell.duration_text)}
+                            }
+                            {match spell.remaining_rounds {
+                                Some(rounds) => localization.tf(", {} round(s) left", &[&rounds]),
+                                None => String::new(),
+                            }}
+                            if needs_sustain {
+                                <button onclick={sustain_spell(spell.id)}>{localization.t("Sustain")}</button>
+                            } else if spell.sustained {
+                                {localization.t(" (sustained this round)")}
+                            }
+                            <button onclick={dismiss_spell(spell.id)}>{localization.t("Dismiss")}</button>
+                        </li>
+                    }
+                }).collect::<Html>() }
+            </ul>
+            if !state.expired.is_empty() {
+                <h3>{localization.t("Expired")}</h3>
+                <ul class="encounter-expired">
+                    { state.expired.iter().map(|spell| {
+                        html!{<li key={spell.id}>{spell.spell_name.clone()}</li>}
+                    }).collect::<Html>() }
+                </ul>
+            }
+            <h3>{localization.t("Cast a spell")}</h3>
+            <div class="spell-book">
+                { spells.iter().map(|card| {
+                    html!{
+                        <div class="spell-book-entry">
+                            {card.to_html()}
+                            <button onclick={cast_spell(card)}>{localization.t("Cast")}</button>
+                        </div>
+                    }
+                }).collect::<Html>() }
+            </div>
+        </div>
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn card(spell_name: &str, duration: &str) -> SpellCard {
+        SpellCard { spell_name: spell_name.to_string(), overview: vec![SpellOverview::Duration(duration.to_string())], ..SpellCard::empty() }
+    }
+
+    fn active_names(state: &EncounterState) -> Vec<String> {
+        state.active.iter().map(|spell| spell.spell_name.clone()).collect()
+    }
+
+    fn expired_names(state: &EncounterState) -> Vec<String> {
+        state.expired.iter().map(|spell| spell.spell_name.clone()).collect()
+    }
+
+    #[test]
+    fn parses_rounds_and_minutes() {
+        assert!(EncounterDuration::parse("3 rounds") == (EncounterDuration::Rounds(3), false));
+        assert!(EncounterDuration::parse("1 round") == (EncounterDuration::Rounds(1), false));
+        assert!(EncounterDuration::parse("1 minute") == (EncounterDuration::Rounds(10), false));
+        assert!(EncounterDuration::parse("10 min") == (EncounterDuration::Rounds(100), false));
+        assert!(EncounterDuration::parse("until the start of your next turn") == (EncounterDuration::UntilNextTurn, false));
+    }
+
+    #[test]
+    fn parses_sustained_durations() {
+        assert!(EncounterDuration::parse("sustained up to 1 minute") == (EncounterDuration::Rounds(10), true));
+        assert!(EncounterDuration::parse("Sustained") == (EncounterDuration::Rounds(100), true));
+    }
+
+    #[test]
+    fn unparseable_durations_last_the_whole_encounter() {
+        assert!(EncounterDuration::parse("") == (EncounterDuration::Unlimited, false));
+        assert!(EncounterDuration::parse("varies") == (EncounterDuration::Unlimited, false));
+        assert!(EncounterDuration::parse("8 hours") == (EncounterDuration::Unlimited, false));
+        assert!(EncounterDuration::parse("unlimited") == (EncounterDuration::Unlimited, false));
+    }
+
+    #[test]
+    fn next_round_counts_down_and_expires_spells() {
+        let state: EncounterState = EncounterState::new()
+            .cast(&card("Blur", "2 rounds"))
+            .cast(&card("Shield", "until the start of your next turn"))
+            .cast(&card("Light", "varies"));
+        let state: EncounterState = state.next_round();
+        assert_eq!(state.round, 2);
+        assert_eq!(active_names(&state), vec!["Blur", "Light"]);
+        assert_eq!(expired_names(&state), vec!["Shield"]);
+        assert_eq!(state.active[0].remaining_rounds, Some(1));
+        assert_eq!(state.active[1].remaining_rounds, None);
+
+        let state: EncounterState = state.next_round();
+        assert_eq!(active_names(&state), vec!["Light"]);
+        assert_eq!(expired_names(&state), vec!["Shield", "Blur"]);
+    }
+
+    #[test]
+    fn next_round_drops_sustained_spells_that_were_not_sustained() {
+        // Casting counts as the first round's sustain
+        let state: EncounterState = EncounterState::new()
+            .cast(&card("Flaming Sphere", "sustained up to 1 minute"))
+            .cast(&card("Spiritual Weapon", "sustained up to 1 minute"))
+            .next_round();
+        assert_eq!(active_names(&state), vec!["Flaming Sphere", "Spiritual Weapon"]);
+
+        let sustained_id: usize = state.active[0].id;
+        let state: EncounterState = state.sustain(sustained_id).next_round();
+        assert_eq!(active_names(&state), vec!["Flaming Sphere"]);
+        assert_eq!(expired_names(&state), vec!["Spiritual Weapon"]);
+        assert_eq!(state.active[0].remaining_rounds, Some(8));
+    }
+}
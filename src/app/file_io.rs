@@ -0,0 +1,111 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+use std::time::Duration;
+use gloo_file::callbacks::{read_as_text, FileReader};
+use gloo_file::File;
+use wasm_bindgen::{JsCast, JsValue};
+use web_sys::{window, Blob, BlobPropertyBag, Document, EventTarget, FileList, HtmlAnchorElement, HtmlInputElement, Url};
+use yew::platform::spawn_local;
+use yew::platform::time::sleep;
+use yew::prelude::*;
+
+// Some browsers still read the object URL after the click returns, so it is revoked later
+const REVOKE_URL_DELAY: Duration = Duration::from_secs(10);
+
+#[derive(PartialEq, Properties)]
+pub struct FileUploadProps {
+    pub label: AttrValue,
+    #[prop_or(AttrValue::from(".json"))]
+    pub accept: AttrValue,
+    // Called with the file name and text content of every selected file
+    pub on_load: Callback<(String, String)>,
+}
+
+#[function_component]
+pub fn FileUpload(props: &FileUploadProps) -> Html {
+    let FileUploadProps {
+        label,
+        accept,
+        on_load
+    } = props;
+
+    // Readers are cancelled when dropped, so they are kept until the component is removed
+    let readers: Rc<RefCell<Vec<FileReader>>> = use_mut_ref(Vec::new).clone();
+
+    let file_change: Callback<Event> = {
+        let on_load: Callback<(String, String)> = on_load.clone();
+
+        Callback::from(move |e: Event| {
+            let target: Option<EventTarget> = e.target();
+
+            let input: Option<HtmlInputElement> = target.and_then(|t: EventTarget| t.dyn_into::<HtmlInputElement>().ok());
+            let files: Option<FileList> = input.and_then(|input| input.files());
+            if let Some(files) = files {
+                for index in 0..files.length() {
+                    if let Some(file) = files.get(index) {
+                        let file: File = File::from(file);
+                        let file_name: String = file.name();
+                        let on_load: Callback<(String, String)> = on_load.clone();
+                        let reader: FileReader = read_as_text(&file, move |result| {
+                            if let Ok(content) = result {
+                                on_load.emit((file_name, content))
+                            }
+                        });
+                        readers.borrow_mut().push(reader);
+                    }
+                }
+            }
+        })
+    };
+
+    html! {
+        <label class="file-upload">
+            {label}
+            <input
+                onchange={file_change}
+                type="file"
+                accept={accept}
+                multiple={true}
+            />
+        </label>
+    }
+}
+
+// Offers `content` as a file download through a temporary object URL
+pub fn download_file(file_name: &str, mime_type: &str, content: &str) -> Result<(), JsValue> {
+    let parts: js_sys::Array = js_sys::Array::of1(&JsValue::from_str(content));
+    let options: BlobPropertyBag = BlobPropertyBag::new();
+    options.set_type(mime_type);
+    let blob: Blob = Blob::new_with_str_sequence_and_options(&parts, &options)?;
+    download_blob(file_name, &blob)
+}
+
+pub fn download_blob(file_name: &str, blob: &Blob) -> Result<(), JsValue> {
+    let url: String = Url::create_object_url_with_blob(blob)?;
+    let downloaded: Result<(), JsValue> = download_url(file_name, &url);
+    spawn_local(async move {
+        sleep(REVOKE_URL_DELAY).await;
+        _ = Url::revoke_object_url(&url);
+    });
+    downloaded
+}
+
+// Downloads an object or data URL by clicking a temporary link
+pub fn download_url(file_name: &str, url: &str) -> Result<(), JsValue> {
+    let document: Document = window().and_then(|w| w.document()).ok_or(JsValue::from_str("No document"))?;
+    let anchor: HtmlAnchorElement = document.create_element("a")?.dyn_into::<HtmlAnchorElement>()?;
+    anchor.set_href(url);
+    anchor.set_download(file_name);
+    anchor.click();
+    Ok(())
+}
+
+// Turns a card or book name into something safe to use as a file name
+pub fn file_name_for(name: &str, extension: &str) -> String {
+    let stem: String = name.chars()
+        .map(|c| if c.is_alphanumeric() || c == '-' || c == '_' { c } else { '-' })
+        .collect::<String>()
+        .trim_matches('-')
+        .to_lowercase();
+    format!("{}.{}", if stem.is_empty() { "spell" } else { &stem }, extension)
+}
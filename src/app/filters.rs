@@ -0,0 +1,194 @@
+use js_sys::{decode_uri_component, encode_uri_component};
+use wasm_bindgen::JsCast;
+use web_sys::{EventTarget, HtmlInputElement, HtmlSelectElement};
+use yew::prelude::*;
+
+use super::localization::Localization;
+use super::spell_card::{SpellCard, SpellType};
+use super::terminology::{renamed_spell, Terminology};
+
+#[derive(PartialEq, Eq, Clone, Default)]
+pub struct SpellFilter {
+    pub text: String,
+    pub spell_type: Option<SpellType>,
+    pub rank: Option<u8>,
+    pub spell_trait: String,
+    pub tradition: String,
+    // Part of the source, such as "homebrew", "remaster" or a book title
+    pub source: String,
+}
+
+impl SpellFilter {
+    pub fn is_empty(&self) -> bool {
+        *self == SpellFilter::default()
+    }
+
+    pub fn matches(&self, card: &SpellCard) -> bool {
+        let text: String = self.text.to_lowercase();
+        let text_matches: bool = text.is_empty()
+            || card.spell_name.to_lowercase().contains(&text)
+            // Searching by the name from before or after the remaster finds the renamed spell too
+            || renamed_spell(&card.spell_name).is_some_and(|name| name.to_lowercase().contains(&text))
+            || card.spell_effect.to_lowercase().contains(&text);
+        let contains_ignoring_case = |list: &[String], value: &str| {
+            value.is_empty() || list.iter().any(|item| item.eq_ignore_ascii_case(value))
+        };
+        text_matches
+            && self.spell_type.is_none_or(|spell_type| card.spell_type == spell_type)
+            && self.rank.is_none_or(|rank| card.spell_level == rank)
+            && contains_ignoring_case(&card.traits, &self.spell_trait)
+            && contains_ignoring_case(&card.traditions, &self.tradition)
+            && card.source.to_string().to_lowercase().contains(&self.source.to_lowercase())
+    }
+
+    // Query string such as "q=fire&type=spell&rank=3", empty when nothing is filtered
+    pub fn to_query(&self) -> String {
+        let mut pairs: Vec<(&str, String)> = Vec::new();
+        if !self.text.is_empty() {
+            pairs.push(("q", self.text.clone()));
+        }
+        if let Some(spell_type) = self.spell_type {
+            pairs.push(("type", spell_type.to_string().to_lowercase()));
+        }
+        if let Some(rank) = self.rank {
+            pairs.push(("rank", rank.to_string()));
+        }
+        if !self.spell_trait.is_empty() {
+            pairs.push(("trait", self.spell_trait.clone()));
+        }
+        if !self.tradition.is_empty() {
+            pairs.push(("tradition", self.tradition.clone()));
+        }
+        if !self.source.is_empty() {
+            pairs.push(("source", self.source.clone()));
+        }
+        pairs.iter()
+            .map(|(key, value)| format!("{}={}", key, String::from(encode_uri_component(value))))
+            .collect::<Vec<String>>()
+            .join("&")
+    }
+
+    pub fn from_query(query: &str) -> SpellFilter {
+        let mut filter: SpellFilter = SpellFilter::default();
+        for pair in query.split('&').filter(|p| !p.is_empty()) {
+            let (key, value) = pair.split_once('=').unwrap_or((pair, ""));
+            let value: String = decode_uri_component(&value.replace('+', " "))
+                .map(String::from)
+                .unwrap_or_else(|_| value.to_string());
+            match key {
+                "q" => filter.text = value,
+                "type" => filter.spell_type = parse_spell_type(&value),
+                "rank" => filter.rank = value.parse::<u8>().ok(),
+                "trait" => filter.spell_trait = value,
+                "tradition" => filter.tradition = value,
+                "source" => filter.source = value,
+                _ => (),
+            }
+        }
+        filter
+    }
+}
+
+pub fn parse_spell_type(value: &str) -> Option<SpellType> {
+    match value.to_lowercase().as_str() {
+        "cantrip" => Some(SpellType::Cantrip),
+        "focus"   => Some(SpellType::Focus),
+        "spell"   => Some(SpellType::Spell),
+        "ritual"  => Some(SpellType::Ritual),
+        _ => None,
+    }
+}
+
+#[derive(PartialEq, Properties)]
+pub struct FilterBarProps {
+    pub filter: SpellFilter,
+    pub on_change: Callback<SpellFilter>,
+}
+
+#[function_component]
+pub fn FilterBar(props: &FilterBarProps) -> Html {
+    let FilterBarProps {
+        filter,
+        on_change
+    } = props;
+
+    let localization: Localization = use_context::<Localization>().unwrap_or_default();
+
+    let terminology: Terminology = use_context::<Terminology>().unwrap_or_default();
+
+    // Every field shares one callback that rebuilds the filter from the changed input
+    let field_change = |update: fn(&mut SpellFilter, String)| {
+        let filter: SpellFilter = filter.clone();
+        let on_change: Callback<SpellFilter> = on_change.clone();
+
+        Callback::from(move |e: Event| {
+            let target: Option<EventTarget> = e.target();
+
+            let value: Option<String> = target.and_then(|t: EventTarget| {
+                match t.dyn_ref::<HtmlSelectElement>() {
+                    Some(select) => Some(select.value()),
+                    None => t.dyn_into::<HtmlInputElement>().ok().map(|input| input.value()),
+                }
+            });
+            if let Some(value) = value {
+                let mut new_filter: SpellFilter = filter.clone();
+                update(&mut new_filter, value.trim().to_string());
+                on_change.emit(new_filter)
+            }
+        })
+    };
+    let clear_filter: Callback<MouseEvent> = {
+        let on_change: Callback<SpellFilter> = on_change.clone();
+        Callback::from(move |_| on_change.emit(SpellFilter::default()))
+    };
+
+    html! {
+        <div class="filter-bar">
+            <input
+                onchange={field_change(|f, v| f.text = v)}
+                type="search"
+                placeholder={localization.t("Search")}
+                value={filter.text.clone()}
+            />
+            <select onchange={field_change(|f, v| f.spell_type = parse_spell_type(&v))}>
+                <option value={""} selected={filter.spell_type.is_none()}>{localization.t("Any type")}</option>
+                { [SpellType::Cantrip, SpellType::Spell, SpellType::Focus, SpellType::Ritual].iter().map(|spell_type| {
+                    html!{
+                        <option value={spell_type.to_string().to_lowercase()} selected={filter.spell_type == Some(*spell_type)}>
+                            {localization.t(&spell_type.to_string())}
+                        </option>
+                    }
+                }).collect::<Html>() }
+            </select>
+            <input
+                onchange={field_change(|f, v| f.rank = v.parse::<u8>().ok())}
+                type="number"
+                placeholder={localization.t(terminology.rank_label())}
+                min={"1"}
+                max={"10"}
+                value={filter.rank.map(|r| r.to_string()).unwrap_or_default()}
+            />
+            <input
+                onchange={field_change(|f, v| f.spell_trait = v)}
+                type="text"
+                placeholder={localization.t("Trait")}
+                value={filter.spell_trait.clone()}
+            />
+            <input
+                onchange={field_change(|f, v| f.tradition = v)}
+                type="text"
+                placeholder={localization.t("Tradition")}
+                value={filter.tradition.clone()}
+            />
+            <input
+                onchange={field_change(|f, v| f.source = v)}
+                type="text"
+                placeholder={localization.t("Source or homebrew")}
+                value={filter.source.clone()}
+            />
+            if !filter.is_empty() {
+                <button onclick={clear_filter}>{localization.t("Clear filters")}</button>
+            }
+        </div>
+    }
+}
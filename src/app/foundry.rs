@@ -0,0 +1,623 @@
+use std::sync::LazyLock;
+use regex::Regex;
+use serde_json::{json, Map, Value};
+use yew::prelude::*;
+
+use super::file_io::{download_file, file_name_for, FileUpload};
+use super::localization::Localization;
+use super::spell_card::{Area, CastTime, Defence, Heightened, SpellCard, SpellOverview, SpellSource, SpellType};
+use super::stat_block::{parse_body, parse_cast_time, parse_defence, parse_feet};
+
+// Foundry's enrichers and the description HTML, replaced in this order
+static HTML_REPLACEMENTS: LazyLock<[(Regex, &str); 7]> = LazyLock::new(|| [
+    // @Damage[(2d6+4)[fire]] and @Damage[6d6[fire]]
+    (Regex::new(r"@Damage\[\(?([^\[\]()]+)\)?\[([^\]]+)\]\]").unwrap(), "$1 $2"),
+    // @Check[type:reflex|dc:20|basic:true]
+    (Regex::new(r"@Check\[(?:type:)?(\w+)[^\]]*\]").unwrap(), "$1"),
+    // @Template[type:burst|distance:20]
+    (Regex::new(r"@Template\[(?:type:)?(\w+)\|distance:(\d+)[^\]]*\]").unwrap(), "$2-foot $1"),
+    // @UUID[...]{Label} and similar enrichers with a label
+    (Regex::new(r"@\w+\[[^\]]*\]\{([^}]*)\}").unwrap(), "$1"),
+    // [[/r 2d6]]
+    (Regex::new(r"\[\[/\w+\s+([^\]]+)\]\]").unwrap(), "$1"),
+    (Regex::new(r"(?i)<br\s*/?>|<hr\s*/?>|</p>|</li>|</h\d>|</div>").unwrap(), "\n"),
+    (Regex::new(r"<[^>]*>").unwrap(), ""),
+]);
+static SUSTAINED_REGEX: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"(?i)^sustained(\s+up\s+to)?\s*").unwrap());
+
+pub struct FoundryImport {
+    pub cards: Vec<SpellCard>,
+    pub warnings: Vec<String>,
+}
+
+// # Import
+// Reads a single spell item, an array of items, an object with an "items" list or a
+// newline-delimited compendium pack (.db) into cards
+pub fn import_foundry_json(text: &str, localization: Localization) -> FoundryImport {
+    let mut import: FoundryImport = FoundryImport { cards: Vec::new(), warnings: Vec::new() };
+
+    let items: Vec<Value> = match serde_json::from_str::<Value>(text) {
+        Ok(Value::Array(items)) => items,
+        Ok(Value::Object(object)) => match object.get("items") {
+            Some(Value::Array(items)) => items.clone(),
+            _ => vec![Value::Object(object)],
+        },
+        Ok(_) => {
+            import.warnings.push(localization.t("The file does not contain Foundry items"));
+            Vec::new()
+        },
+        Err(_) => text.lines()
+            .filter(|line| !line.trim().is_empty())
+            .filter_map(|line| match serde_json::from_str::<Value>(line) {
+                Ok(item) => Some(item),
+                Err(err) => {
+                    import.warnings.push(localization.tf("Skipped a line that is not valid JSON: {}", &[&err]));
+                    None
+                },
+            })
+            .collect(),
+    };
+
+    for item in items.iter() {
+        let name: &str = item["name"].as_str().unwrap_or("Unnamed item");
+        match item["type"].as_str() {
+            Some("spell") => {
+                let mut warnings: Vec<String> = Vec::new();
+                import.cards.push(foundry_item_to_card(item, &mut warnings, localization));
+                import.warnings.extend(warnings.into_iter().map(|w| format!("{}: {}", name, w)));
+            },
+            other => import.warnings.push(localization.tf("{}: skipped item of type \"{}\"", &[&name, &other.unwrap_or("unknown")])),
+        }
+    }
+    import
+}
+
+fn foundry_item_to_card(item: &Value, warnings: &mut Vec<String>, localization: Localization) -> SpellCard {
+    // Items from before Foundry v10 keep their data under "data" instead of "system"
+    let system: &Value = if item["system"].is_object() { &item["system"] } else { &item["data"] };
+    let mut card: SpellCard = SpellCard::empty();
+
+    // # Header
+    card.spell_name = item["name"].as_str().unwrap_or_default().to_string();
+    card.spell_level = system["level"]["value"].as_u64().unwrap_or(1).clamp(1, 10) as u8;
+    let traits: Vec<String> = string_list(&system["traits"]["value"]);
+    let category: &str = system["category"]["value"].as_str().unwrap_or_default();
+    card.spell_type = if traits.iter().any(|t| t == "cantrip") || category == "cantrip" {
+        SpellType::Cantrip
+    } else if traits.iter().any(|t| t == "focus") || category == "focus" {
+        SpellType::Focus
+    } else if !system["ritual"].is_null() || category == "ritual" {
+        SpellType::Ritual
+    } else {
+        SpellType::Spell
+    };
+    let time: &str = system["time"]["value"].as_str().unwrap_or_default();
+    match foundry_cast_time(time) {
+        Some(cast_time) => card.cast_time = cast_time,
+        None if card.spell_type == SpellType::Ritual => (),
+        None => warnings.push(localization.tf("could not map cast time \"{}\"", &[&time])),
+    }
+    card.link = item["flags"]["core"]["sourceId"].as_str()
+        .filter(|source| source.starts_with("http"))
+        .unwrap_or_default()
+        .to_string();
+    card.source = foundry_source(system);
+
+    // # Middle
+    card.traits = traits.iter().map(|t| capitalise(t)).collect();
+    let traditions: Vec<String> = if system["traits"]["traditions"].is_array() {
+        string_list(&system["traits"]["traditions"])
+    } else {
+        string_list(&system["traditions"]["value"])
+    };
+    card.traditions = traditions.iter().map(|t| capitalise(t)).collect();
+
+    let range: &str = system["range"]["value"].as_str().unwrap_or_default();
+    if !range.is_empty() {
+        match parse_feet(range) {
+            Some(range) => card.overview.push(SpellOverview::Range(range)),
+            None => warnings.push(localization.tf("could not map range \"{}\"", &[&range])),
+        }
+    }
+    if let Some(area) = system["area"].as_object() {
+        let area_type: &str = area.get("type").and_then(|t| t.as_str()).unwrap_or_default();
+        let size: u8 = area.get("value").and_then(number_value).unwrap_or(0).min(u8::MAX as u64) as u8;
+        match area_type {
+            "burst"     => card.overview.push(SpellOverview::Area(Area::Burst(size))),
+            "cone"      => card.overview.push(SpellOverview::Area(Area::Cone(size))),
+            "emanation" => card.overview.push(SpellOverview::Area(Area::Emanation(size))),
+            // The width is kept in the area details, as in "10 feet wide"
+            "line"      => {
+                let width: Option<u8> = area.get("details").and_then(|d| d.as_str()).and_then(parse_feet);
+                card.overview.push(SpellOverview::Area(Area::Line(size, width)));
+            },
+            "cylinder"  => {
+                card.overview.push(SpellOverview::Area(Area::Burst(size)));
+                warnings.push(localization.t("cylinder area mapped to a burst, its height is not kept"));
+            },
+            other => warnings.push(localization.tf("could not map area type \"{}\"", &[&other])),
+        }
+    }
+    let targets: &str = system["target"]["value"].as_str().unwrap_or_default();
+    if !targets.is_empty() {
+        card.overview.push(SpellOverview::Targets(targets.to_string()));
+    }
+    if let Some(defence) = foundry_defence(system, &traits) {
+        card.overview.push(SpellOverview::Defence(defence));
+        // Older items write "basic" as a string instead of a flag
+        card.basic_save = system["defense"]["save"]["basic"].as_bool()
+            .unwrap_or(system["save"]["basic"].as_str() == Some("basic"));
+    }
+    let duration: &str = system["duration"]["value"].as_str().unwrap_or_default();
+    let sustained: bool = system["duration"]["sustained"].as_bool().unwrap_or(false);
+    match (duration.is_empty(), sustained) {
+        (true, true)   => card.overview.push(SpellOverview::Duration("sustained".to_string())),
+        (false, true)  => card.overview.push(SpellOverview::Duration(format!("sustained up to {}", duration))),
+        (false, false) => card.overview.push(SpellOverview::Duration(duration.to_string())),
+        (true, false)  => (),
+    }
+
+    // # Bottom
+    // The description holds the effect, the degrees of success and usually the heightened entries
+    let description: String = html_to_text(system["description"]["value"].as_str().unwrap_or_default());
+    let lines: Vec<&str> = description.lines().map(|l| l.trim()).filter(|l| !l.is_empty()).collect();
+    parse_body(&lines, &mut card, warnings, localization);
+    if card.heightened.is_empty() {
+        card.heightened = foundry_heightening(&system["heightening"], warnings, localization);
+    }
+    card.overview.sort();
+
+    for (key, note) in [
+        ("damage", "structured damage is only kept as written in the description"),
+        ("cost", "cost was not mapped"),
+        ("requirements", "requirements were not mapped"),
+        ("ritual", "ritual casters and checks were not mapped"),
+    ] {
+        if is_filled(&system[key]) {
+            warnings.push(localization.t(note));
+        }
+    }
+    card
+}
+
+// Foundry keeps the book under "publication" since the remaster and under "source" before it
+fn foundry_source(system: &Value) -> SpellSource {
+    let title: &str = system["publication"]["title"].as_str()
+        .or(system["source"]["value"].as_str())
+        .unwrap_or_default()
+        .trim();
+    if title.eq_ignore_ascii_case("homebrew") {
+        let author: &str = system["publication"]["authors"].as_str().unwrap_or_default();
+        return SpellSource::Homebrew { author: author.trim().to_string() };
+    }
+    match SpellSource::from_reference(title) {
+        SpellSource::Published { book, page, remaster } => SpellSource::Published {
+            book,
+            page,
+            remaster: system["publication"]["remaster"].as_bool().unwrap_or(remaster),
+        },
+        source => source,
+    }
+}
+
+fn foundry_publication(source: &SpellSource) -> Value {
+    match source {
+        SpellSource::Unknown => Value::Null,
+        SpellSource::Published { book, page, remaster } => json!({
+            "title": match page {
+                Some(page) => format!("{} pg. {}", book, page),
+                None => book.clone(),
+            },
+            "authors": "",
+            "license": if *remaster { "ORC" } else { "OGL" },
+            "remaster": remaster,
+        }),
+        SpellSource::Homebrew { author } => json!({ "title": "Homebrew", "authors": author, "license": "", "remaster": true }),
+    }
+}
+
+pub fn foundry_cast_time(time: &str) -> Option<CastTime> {
+    match time.trim().to_lowercase().as_str() {
+        "" => None,
+        "1" => Some(CastTime::Single),
+        "2" => Some(CastTime::Double),
+        "3" => Some(CastTime::Triple),
+        "reaction" => Some(CastTime::Reaction),
+        "free" => Some(CastTime::Free),
+        "1 to 3" | "1-3" => Some(CastTime::Range(1, 3)),
+        "1 to 2" | "1-2" => Some(CastTime::Range(1, 2)),
+        "2 to 3" | "2-3" => Some(CastTime::Range(2, 3)),
+        _ => parse_cast_time(time),
+    }
+}
+
+fn foundry_defence(system: &Value, traits: &[String]) -> Option<Defence> {
+    let statistic: &str = system["defense"]["save"]["statistic"].as_str()
+        .or(system["defense"]["passive"]["statistic"].as_str())
+        .or(system["save"]["value"].as_str())
+        .unwrap_or_default();
+    if !statistic.is_empty() {
+        return parse_defence(&statistic.replace('-', " "));
+    }
+    if traits.iter().any(|t| t == "attack") {
+        return Some(Defence::ArmourClass);
+    }
+    None
+}
+
+fn foundry_heightening(heightening: &Value, warnings: &mut Vec<String>, localization: Localization) -> Vec<Heightened> {
+    match heightening["type"].as_str() {
+        Some("interval") => {
+            let interval: u8 = heightening["interval"].as_u64().unwrap_or(1) as u8;
+            let damage: Vec<String> = heightening["damage"].as_object()
+                .map(|damage| damage.values().filter_map(|d| d.as_str().map(|d| d.to_string())).collect())
+                .unwrap_or_default();
+            if damage.is_empty() {
+                warnings.push(localization.t("interval heightening without damage was not mapped"));
+                Vec::new()
+            } else {
+                vec![Heightened::Repeat(interval, format!("The damage increases by {}.", damage.join(" and ")))]
+            }
+        },
+        Some("fixed") => {
+            let levels: Vec<u8> = heightening["levels"].as_object()
+                .map(|levels| levels.keys().filter_map(|l| l.parse::<u8>().ok()).collect())
+                .unwrap_or_default();
+            if !levels.is_empty() {
+                warnings.push(localization.t("fixed heightening was only mapped to its ranks, fill in the effects"));
+            }
+            levels.into_iter().map(|level| Heightened::Single(level, String::new())).collect()
+        },
+        _ => Vec::new(),
+    }
+}
+
+// Strips the description HTML and Foundry's enrichers down to one paragraph per line
+pub fn html_to_text(html: &str) -> String {
+    let mut text: String = html.to_string();
+    for (pattern, replacement) in HTML_REPLACEMENTS.iter() {
+        text = pattern.replace_all(&text, *replacement).to_string();
+    }
+    text.replace("&nbsp;", " ")
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&#39;", "'")
+        .replace("&amp;", "&")
+}
+
+// # Export
+pub fn card_to_foundry_item(card: &SpellCard) -> Value {
+    let mut traits: Vec<String> = card.traits.iter().map(|t| t.to_lowercase()).collect();
+    let type_trait: Option<&str> = match card.spell_type {
+        SpellType::Cantrip => Some("cantrip"),
+        SpellType::Focus   => Some("focus"),
+        SpellType::Spell | SpellType::Ritual => None,
+    };
+    if let Some(type_trait) = type_trait {
+        if !traits.iter().any(|t| t == type_trait) {
+            traits.push(type_trait.to_string());
+        }
+    }
+    let rarity: String = ["uncommon", "rare", "unique"].iter()
+        .find(|rarity| traits.iter().any(|t| t == *rarity))
+        .unwrap_or(&"common")
+        .to_string();
+    traits.retain(|t| *t != rarity);
+
+    let mut system: Map<String, Value> = Map::new();
+    system.insert("description".to_string(), json!({ "value": card_description_html(card), "gm": "" }));
+    system.insert("level".to_string(), json!({ "value": card.spell_level }));
+    system.insert("traits".to_string(), json!({
+        "value": traits,
+        "rarity": rarity,
+        "traditions": card.traditions.iter().map(|t| t.to_lowercase()).collect::<Vec<String>>(),
+    }));
+    system.insert("time".to_string(), json!({ "value": match &card.cast_time {
+        CastTime::Free => "free".to_string(),
+        CastTime::Reaction => "reaction".to_string(),
+        CastTime::Single => "1".to_string(),
+        CastTime::Double => "2".to_string(),
+        CastTime::Triple => "3".to_string(),
+        CastTime::Longer(duration) => duration.clone(),
+        CastTime::Range(min, max) => format!("{} to {}", min, max),
+    }}));
+    // Foundry reads distances in feet, whichever units the app shows
+    system.insert("range".to_string(), json!({ "value": match card.get_overview_element("Range") {
+        Some(SpellOverview::Range(range)) => format!("{} feet", range),
+        _ => String::new(),
+    }}));
+    system.insert("area".to_string(), match card.get_overview_element("Area") {
+        Some(SpellOverview::Area(area)) => {
+            let area_type: &str = match area {
+                Area::Burst(_)     => "burst",
+                Area::Cone(_)      => "cone",
+                Area::Emanation(_) => "emanation",
+                Area::Line(_, _)   => "line",
+            };
+            match area {
+                Area::Line(length, Some(width)) => json!({ "type": area_type, "value": length, "details": format!("{} feet wide", width) }),
+                _ => json!({ "type": area_type, "value": area.get_aoe_val().0 }),
+            }
+        },
+        _ => Value::Null,
+    });
+    system.insert("target".to_string(), json!({ "value": match card.get_overview_element("Targets") {
+        Some(SpellOverview::Targets(targets)) => targets,
+        _ => String::new(),
+    }}));
+    system.insert("defense".to_string(), match card.get_overview_element("Defence") {
+        Some(SpellOverview::Defence(Defence::ArmourClass)) => json!({ "passive": { "statistic": "ac" } }),
+        Some(SpellOverview::Defence(defence)) => json!({ "save": {
+            "statistic": defence.to_string().to_lowercase(),
+            "basic": card.basic_save,
+        }}),
+        _ => Value::Null,
+    });
+    let duration: String = match card.get_overview_element("Duration") {
+        Some(SpellOverview::Duration(duration)) => duration,
+        _ => String::new(),
+    };
+    let sustained: bool = duration.to_lowercase().starts_with("sustained");
+    let duration_value: String = if sustained {
+        SUSTAINED_REGEX.replace(&duration, "").to_string()
+    } else {
+        duration
+    };
+    system.insert("duration".to_string(), json!({ "value": duration_value, "sustained": sustained }));
+    system.insert("heightening".to_string(), foundry_heightening_export(&card.heightened));
+    system.insert("damage".to_string(), json!({}));
+    system.insert("rules".to_string(), json!([]));
+    if card.source != SpellSource::Unknown {
+        system.insert("publication".to_string(), foundry_publication(&card.source));
+    }
+    if card.spell_type == SpellType::Ritual {
+        system.insert("ritual".to_string(), json!({}));
+    }
+
+    json!({
+        "name": card.spell_name,
+        "type": "spell",
+        "img": "systems/pf2e/icons/default-icons/spell.svg",
+        "system": system,
+    })
+}
+
+pub fn export_foundry_json(cards: &[SpellCard]) -> String {
+    let items: Vec<Value> = cards.iter().map(card_to_foundry_item).collect();
+    serde_json::to_string_pretty(&items).unwrap_or_default()
+}
+
+// Heightened entries are written out in the description, Foundry only gets the ranks
+fn foundry_heightening_export(heightened: &[Heightened]) -> Value {
+    if let Some(Heightened::Repeat(interval, _)) = heightened.iter().find(|h| matches!(h, Heightened::Repeat(_, _))) {
+        return json!({ "type": "interval", "interval": interval, "damage": {} });
+    }
+    let levels: Map<String, Value> = heightened.iter().filter_map(|h| match h {
+        Heightened::Single(level, _) => Some((level.to_string(), json!({}))),
+        Heightened::Repeat(_, _) => None,
+    }).collect();
+    if levels.is_empty() {
+        Value::Null
+    } else {
+        json!({ "type": "fixed", "levels": levels })
+    }
+}
+
+fn card_description_html(card: &SpellCard) -> String {
+    let mut html: String = card.spell_effect.split('\n')
+        .filter(|p| !p.trim().is_empty())
+        .map(|p| format!("<p>{}</p>", escape_html(p)))
+        .collect::<String>();
+    if !card.roll_effect.is_empty() {
+        html.push_str("<hr />");
+        for roll_result in card.roll_effect.iter() {
+            html.push_str(&format!("<p><strong>{}</strong> {}</p>", roll_result, escape_html(roll_result.effect())));
+        }
+    }
+    if !card.heightened.is_empty() {
+        html.push_str("<hr />");
+        for heightened in card.heightened.iter() {
+            html.push_str(&format!("<p><strong>{}</strong> {}</p>", heightened, escape_html(heightened.effect())));
+        }
+    }
+    html
+}
+
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+fn string_list(value: &Value) -> Vec<String> {
+    value.as_array()
+        .map(|list| list.iter().filter_map(|v| v.as_str().map(|s| s.to_string())).collect())
+        .unwrap_or_default()
+}
+
+fn number_value(value: &Value) -> Option<u64> {
+    value.as_u64().or(value.as_str().and_then(|s| s.parse::<u64>().ok()))
+}
+
+fn is_filled(value: &Value) -> bool {
+    match value {
+        Value::Null => false,
+        Value::String(s) => !s.is_empty(),
+        Value::Array(a) => !a.is_empty(),
+        Value::Object(o) => o.values().any(is_filled),
+        _ => true,
+    }
+}
+
+fn capitalise(word: &str) -> String {
+    let mut chars = word.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+#[derive(PartialEq, Properties)]
+pub struct FoundryImporterProps {
+    pub on_import: Callback<Vec<SpellCard>>,
+}
+
+#[function_component]
+pub fn FoundryImporter(props: &FoundryImporterProps) -> Html {
+    let FoundryImporterProps {
+        on_import
+    } = props;
+
+    let localization: Localization = use_context::<Localization>().unwrap_or_default();
+
+    let cards: UseStateHandle<Vec<SpellCard>> = use_state(Vec::new);
+    let warnings: UseStateHandle<Vec<String>> = use_state(Vec::new);
+
+    // # Callback functions
+    let file_load: Callback<(String, String)> = {
+        let cards: UseStateHandle<Vec<SpellCard>> = cards.clone();
+        let warnings: UseStateHandle<Vec<String>> = warnings.clone();
+
+        Callback::from(move |(file_name, content): (String, String)| {
+            let import: FoundryImport = import_foundry_json(&content, localization);
+            let mut all_cards: Vec<SpellCard> = (*cards).clone();
+            all_cards.extend(import.cards);
+            let mut all_warnings: Vec<String> = (*warnings).clone();
+            all_warnings.extend(import.warnings.into_iter().map(|w| format!("{}: {}", file_name, w)));
+            cards.set(all_cards);
+            warnings.set(all_warnings)
+        })
+    };
+    let confirm_import: Callback<MouseEvent> = {
+        let cards: UseStateHandle<Vec<SpellCard>> = cards.clone();
+        let warnings: UseStateHandle<Vec<String>> = warnings.clone();
+        let on_import: Callback<Vec<SpellCard>> = on_import.clone();
+
+        Callback::from(move |_| {
+            on_import.emit((*cards).clone());
+            cards.set(Vec::new());
+            warnings.set(Vec::new())
+        })
+    };
+
+    html! {
+        <div class="importer">
+            <h2>{"Foundry VTT"}</h2>
+            <FileUpload
+                label={localization.t("Spell items or compendium packs (.json, .db):")}
+                accept={".json,.db"}
+                on_load={file_load}
+            />
+            if !warnings.is_empty() {
+                <p>{localization.t("Fields that could not be mapped:")}</p>
+                <ul class="import-warnings">
+                    { warnings.iter().map(|warning| {
+                        html!{<li>{warning}</li>}
+                    }).collect::<Html>() }
+                </ul>
+            }
+            if !cards.is_empty() {
+                <button onclick={confirm_import}>
+                    {localization.tf("Add {} card(s) to the spellbook", &[&cards.len()])}
+                </button>
+                <div class="spell-book">
+                    { cards.iter().map(|card| card.to_html()).collect::<Html>() }
+                </div>
+            }
+        </div>
+    }
+}
+
+#[derive(PartialEq, Properties)]
+pub struct FoundryExporterProps {
+    pub spells: Vec<SpellCard>,
+}
+
+#[function_component]
+pub fn FoundryExporter(props: &FoundryExporterProps) -> Html {
+    let FoundryExporterProps {
+        spells
+    } = props;
+
+    let localization: Localization = use_context::<Localization>().unwrap_or_default();
+
+    // # Callback functions
+    let export_bundle: Callback<MouseEvent> = {
+        let spells: Vec<SpellCard> = spells.clone();
+
+        Callback::from(move |_| {
+            _ = download_file("spellbook-foundry.json", "application/json", &export_foundry_json(&spells));
+        })
+    };
+    let export_separately: Callback<MouseEvent> = {
+        let spells: Vec<SpellCard> = spells.clone();
+
+        Callback::from(move |_| {
+            for card in spells.iter() {
+                let item: String = serde_json::to_string_pretty(&card_to_foundry_item(card)).unwrap_or_default();
+                _ = download_file(&file_name_for(&card.spell_name, "json"), "application/json", &item);
+            }
+        })
+    };
+
+    html! {
+        <div class="exporter">
+            <button onclick={export_bundle}>{localization.t("Export to Foundry VTT (one file)")}</button>
+            <button onclick={export_separately}>{localization.t("Export to Foundry VTT (file per card)")}</button>
+        </div>
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use super::super::spell_card::RollResult;
+
+    fn round_trip(cards: &[SpellCard]) -> FoundryImport {
+        import_foundry_json(&export_foundry_json(cards), Localization::default())
+    }
+
+    #[test]
+    fn round_trip_keeps_the_basic_save_and_line_width() {
+        let lightning_bolt: SpellCard = SpellCard {
+            spell_name: "Lightning Bolt".to_string(),
+            spell_level: 3,
+            traditions: vec!["Arcane".to_string(), "Primal".to_string()],
+            overview: vec![SpellOverview::Area(Area::Line(120, Some(10))), SpellOverview::Defence(Defence::Reflex)],
+            basic_save: true,
+            spell_effect: "A bolt of lightning strikes outward from your hand.".to_string(),
+            ..SpellCard::empty()
+        };
+        let fear: SpellCard = SpellCard {
+            spell_name: "Fear".to_string(),
+            overview: vec![SpellOverview::Defence(Defence::Will)],
+            spell_effect: "You plant fear in the target, which is not a basic effect.".to_string(),
+            roll_effect: vec![RollResult::Success("The target is frightened 1.".to_string())],
+            ..SpellCard::empty()
+        };
+
+        let FoundryImport { cards, warnings } = round_trip(&[lightning_bolt.clone(), fear.clone()]);
+        assert!(warnings.is_empty());
+        assert_eq!(cards.len(), 2);
+        assert!(cards[0].overview == lightning_bolt.overview);
+        assert!(cards[0].basic_save);
+        assert_eq!(cards[0].spell_effect, lightning_bolt.spell_effect);
+        assert!(cards[1].overview == fear.overview);
+        assert!(!cards[1].basic_save);
+        assert!(cards[1].roll_effect == fear.roll_effect);
+    }
+
+    #[test]
+    fn lines_without_a_width_have_no_details() {
+        let card: SpellCard = SpellCard {
+            spell_name: "Line".to_string(),
+            overview: vec![SpellOverview::Area(Area::Line(30, None))],
+            ..SpellCard::empty()
+        };
+        let item: Value = card_to_foundry_item(&card);
+        assert!(item["system"]["area"]["details"].is_null());
+        assert!(round_trip(std::slice::from_ref(&card)).cards[0].overview == card.overview);
+    }
+}
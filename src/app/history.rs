@@ -0,0 +1,177 @@
+use gloo_events::{EventListener, EventListenerOptions};
+use gloo_storage::errors::StorageError;
+use gloo_storage::{SessionStorage, Storage};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use wasm_bindgen::JsCast;
+use web_sys::{window, Element, KeyboardEvent};
+use yew::prelude::*;
+
+use super::localization::Localization;
+
+// Older states are dropped once this many are kept
+const MAX_HISTORY: usize = 30;
+// Name of the error browsers throw when a write would go over the storage quota
+const QUOTA_EXCEEDED: &str = "QuotaExceededError";
+
+#[derive(PartialEq, Clone, Serialize, Deserialize)]
+pub struct History<T> {
+    past: Vec<T>,
+    future: Vec<T>,
+    // The last save failed, so the history will not survive a reload
+    #[serde(skip)]
+    unsaved: bool,
+}
+
+impl<T> Default for History<T> {
+    fn default() -> History<T> {
+        History { past: Vec::new(), future: Vec::new(), unsaved: false }
+    }
+}
+
+impl<T: Clone + Serialize + DeserializeOwned> History<T> {
+    // Histories are kept in session storage so they survive a reload but not closing the tab
+    pub fn load(key: &str) -> History<T> {
+        SessionStorage::get::<History<T>>(key).unwrap_or_default()
+    }
+
+    // Drops the oldest states until the history fits in session storage
+    pub fn save(&mut self, key: &str) {
+        loop {
+            match SessionStorage::set(key, &*self) {
+                Ok(()) => {
+                    self.unsaved = false;
+                    return;
+                },
+                Err(StorageError::JsError(err)) if err.name == QUOTA_EXCEEDED && self.drop_oldest() => (),
+                Err(_) => {
+                    // An older saved history would not match the current state after a reload
+                    SessionStorage::delete(key);
+                    self.unsaved = true;
+                    return;
+                },
+            }
+        }
+    }
+
+    pub fn is_unsaved(&self) -> bool {
+        self.unsaved
+    }
+
+    // The furthest undo goes first, then the furthest redo
+    fn drop_oldest(&mut self) -> bool {
+        if !self.past.is_empty() {
+            self.past.remove(0);
+            true
+        } else if !self.future.is_empty() {
+            self.future.remove(0);
+            true
+        } else {
+            false
+        }
+    }
+
+    pub fn clear(key: &str) {
+        SessionStorage::delete(key);
+    }
+
+    pub fn can_undo(&self) -> bool {
+        !self.past.is_empty()
+    }
+
+    pub fn can_redo(&self) -> bool {
+        !self.future.is_empty()
+    }
+
+    // Remembers the state that is about to be replaced, a new change can no longer be redone past
+    pub fn record(&mut self, previous: T) {
+        self.past.push(previous);
+        if self.past.len() > MAX_HISTORY {
+            self.past.remove(0);
+        }
+        self.future.clear();
+    }
+
+    pub fn undo(&mut self, current: T) -> Option<T> {
+        let previous: T = self.past.pop()?;
+        self.future.push(current);
+        Some(previous)
+    }
+
+    pub fn redo(&mut self, current: T) -> Option<T> {
+        let next: T = self.future.pop()?;
+        self.past.push(current);
+        Some(next)
+    }
+}
+
+// Text fields keep the browser's own undo while typing
+fn is_text_field(element: Option<Element>) -> bool {
+    element.is_some_and(|element| {
+        matches!(element.tag_name().as_str(), "INPUT" | "TEXTAREA") || element.has_attribute("contenteditable")
+    })
+}
+
+#[derive(PartialEq, Properties)]
+pub struct UndoRedoProps {
+    pub can_undo: bool,
+    pub can_redo: bool,
+    #[prop_or_default]
+    pub unsaved: bool,
+    pub on_undo: Callback<()>,
+    pub on_redo: Callback<()>,
+}
+
+#[function_component]
+pub fn UndoRedo(props: &UndoRedoProps) -> Html {
+    let UndoRedoProps {
+        can_undo,
+        can_redo,
+        unsaved,
+        on_undo,
+        on_redo
+    } = props;
+
+    let localization: Localization = use_context::<Localization>().unwrap_or_default();
+
+    // Ctrl+Z undoes, Ctrl+Shift+Z and Ctrl+Y redo, Cmd takes the place of Ctrl on macOS
+    {
+        let on_undo: Callback<()> = on_undo.clone();
+        let on_redo: Callback<()> = on_redo.clone();
+        use_effect_with((on_undo, on_redo), move |(on_undo, on_redo)| {
+            let on_undo: Callback<()> = on_undo.clone();
+            let on_redo: Callback<()> = on_redo.clone();
+            let listener: Option<EventListener> = window().and_then(|w| w.document()).map(|document| {
+                EventListener::new_with_options(&document, "keydown", EventListenerOptions::enable_prevent_default(), move |e: &Event| {
+                    let event: Option<&KeyboardEvent> = e.dyn_ref::<KeyboardEvent>();
+                    if let Some(event) = event {
+                        let active: Option<Element> = window().and_then(|w| w.document()).and_then(|d| d.active_element());
+                        if !(event.ctrl_key() || event.meta_key()) || is_text_field(active) {
+                            return;
+                        }
+                        match (event.key().to_lowercase().as_str(), event.shift_key()) {
+                            ("z", false) => on_undo.emit(()),
+                            ("z", true) | ("y", false) => on_redo.emit(()),
+                            _ => return,
+                        }
+                        event.prevent_default();
+                    }
+                })
+            });
+            move || drop(listener)
+        });
+    }
+
+    let undo: Callback<MouseEvent> = on_undo.reform(|_| ());
+    let redo: Callback<MouseEvent> = on_redo.reform(|_| ());
+
+    html! {
+        <div class="undo-redo">
+            <button type="button" onclick={undo} disabled={!can_undo} title={localization.t("Undo (Ctrl+Z)")}>{localization.t("Undo")}</button>
+            <button type="button" onclick={redo} disabled={!can_redo} title={localization.t("Redo (Ctrl+Shift+Z)")}>{localization.t("Redo")}</button>
+            if *unsaved {
+                <span class="import-warnings">{localization.t("The undo history is too large to keep and will be lost on reload")}</span>
+            }
+        </div>
+    }
+}
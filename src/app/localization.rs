@@ -0,0 +1,989 @@
+use std::fmt::Display;
+use gloo_storage::{LocalStorage, Storage};
+use serde::{Deserialize, Serialize};
+use wasm_bindgen::JsCast;
+use web_sys::{EventTarget, HtmlSelectElement};
+use yew::prelude::*;
+
+use super::area_grid::SQUARE_FT;
+use super::spell_card::{Area, Defence, Heightened, SpellOverview, SpellSource};
+use super::terminology::Terminology;
+
+const LOCALIZATION_KEY: &str = "Localization";
+
+#[derive(PartialEq, Eq, Clone, Copy, Default, Serialize, Deserialize)]
+pub enum Locale {
+    #[default]
+    English,
+    German,
+    French,
+}
+
+impl Locale {
+    pub const ALL: [Locale; 3] = [Locale::English, Locale::German, Locale::French];
+
+    pub fn code(&self) -> &'static str {
+        match self {
+            Locale::English => "en",
+            Locale::German  => "de",
+            Locale::French  => "fr",
+        }
+    }
+
+    // Each language is offered under its own name
+    pub fn native_name(&self) -> &'static str {
+        match self {
+            Locale::English => "English",
+            Locale::German  => "Deutsch",
+            Locale::French  => "Français",
+        }
+    }
+
+    fn from_code(code: &str) -> Locale {
+        Locale::ALL.into_iter().find(|locale| locale.code() == code).unwrap_or_default()
+    }
+
+    // Translations of the English texts, which are their own keys
+    fn messages(&self) -> &'static [(&'static str, &'static str)] {
+        match self {
+            Locale::English => &[],
+            Locale::German  => GERMAN,
+            Locale::French  => FRENCH,
+        }
+    }
+
+    pub fn ordinal(&self, n: u8) -> String {
+        match self {
+            Locale::English => {
+                let suffix: &str = match (n % 10, n % 100) {
+                    (_, 11..=13) => "th",
+                    (1, _)       => "st",
+                    (2, _)       => "nd",
+                    (3, _)       => "rd",
+                    _            => "th",
+                };
+                format!("{}{}", n, suffix)
+            },
+            Locale::German  => format!("{}.", n),
+            Locale::French  => if n == 1 { "1er".to_string() } else { format!("{}e", n) },
+        }
+    }
+
+    fn decimal_separator(&self) -> char {
+        match self {
+            Locale::English => '.',
+            Locale::German | Locale::French => ',',
+        }
+    }
+}
+
+// How distances are shown, cards always store them in feet
+#[derive(PartialEq, Eq, Clone, Copy, Default, Serialize, Deserialize)]
+pub enum Units {
+    #[default]
+    Feet,
+    // 1.5 m for every 5 ft square
+    Metres,
+    Squares,
+}
+
+impl Units {
+    pub const ALL: [Units; 3] = [Units::Feet, Units::Metres, Units::Squares];
+
+    fn code(&self) -> &'static str {
+        match self {
+            Units::Feet    => "ft",
+            Units::Metres  => "m",
+            Units::Squares => "squares",
+        }
+    }
+
+    fn from_code(code: &str) -> Units {
+        Units::ALL.into_iter().find(|units| units.code() == code).unwrap_or_default()
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Units::Feet    => "Feet",
+            Units::Metres  => "Metres (1.5 m per square)",
+            Units::Squares => "Grid squares",
+        }
+    }
+}
+
+// Language and units every text is shown in, provided to all components as a context
+#[derive(PartialEq, Eq, Clone, Copy, Default, Serialize, Deserialize)]
+pub struct Localization {
+    pub locale: Locale,
+    #[serde(default)]
+    pub units: Units,
+}
+
+impl Localization {
+    pub fn load() -> Localization {
+        LocalStorage::get::<Localization>(LOCALIZATION_KEY).unwrap_or_default()
+    }
+
+    pub fn save(&self) {
+        _ = LocalStorage::set(LOCALIZATION_KEY, self);
+    }
+
+    // The text in the chosen language, English when there is no translation
+    pub fn t(&self, text: &str) -> String {
+        self.locale.messages().iter()
+            .find(|(english, _)| *english == text)
+            .map(|(_, translated)| translated.to_string())
+            .unwrap_or_else(|| text.to_string())
+    }
+
+    // Translates a template and fills its "{}" in order
+    pub fn tf(&self, template: &str, args: &[&dyn Display]) -> String {
+        let translated: String = self.t(template);
+        let mut parts = translated.split("{}");
+        let mut text: String = parts.next().unwrap_or_default().to_string();
+        for (index, part) in parts.enumerate() {
+            if let Some(arg) = args.get(index) {
+                text.push_str(&arg.to_string());
+            }
+            text.push_str(part);
+        }
+        text
+    }
+
+    // A number given in tenths, with one decimal only when needed
+    fn decimal(&self, tenths: u32) -> String {
+        if tenths.is_multiple_of(10) {
+            (tenths / 10).to_string()
+        } else {
+            format!("{}{}{}", tenths / 10, self.locale.decimal_separator(), tenths % 10)
+        }
+    }
+
+    pub fn distance(&self, feet: u8) -> String {
+        match self.units {
+            Units::Feet    => self.tf("{}ft", &[&feet]),
+            Units::Metres  => self.tf("{} m", &[&self.decimal(feet as u32 * 3)]),
+            Units::Squares if feet == SQUARE_FT => self.tf("{} square", &[&1]),
+            Units::Squares => self.tf("{} squares", &[&self.decimal(feet as u32 * 10 / SQUARE_FT as u32)]),
+        }
+    }
+
+    pub fn area(&self, area: &Area) -> String {
+        match area {
+            Area::Burst(aoe)                => self.tf("{} burst", &[&self.distance(*aoe)]),
+            Area::Cone(aoe)                 => self.tf("{} cone", &[&self.distance(*aoe)]),
+            Area::Emanation(aoe)            => self.tf("{} emanation", &[&self.distance(*aoe)]),
+            Area::Line(length, None)        => self.tf("{} line", &[&self.distance(*length)]),
+            Area::Line(length, Some(width)) => self.tf("{} long and {} wide line", &[&self.distance(*length), &self.distance(*width)]),
+        }
+    }
+
+    pub fn overview_label(&self, terminology: Terminology, elem: &SpellOverview) -> String {
+        self.t(&terminology.overview_label(elem))
+    }
+
+    pub fn overview_value(&self, terminology: Terminology, elem: &SpellOverview, basic_save: bool) -> String {
+        match elem {
+            SpellOverview::Defence(defence) if basic_save && *defence != Defence::ArmourClass => self.tf("basic {}", &[&self.t(&terminology.defence_name(defence))]),
+            SpellOverview::Defence(defence) => self.t(&terminology.defence_name(defence)),
+            _ => elem.value_string(*self),
+        }
+    }
+
+    pub fn heightened(&self, heightened: &Heightened) -> String {
+        match heightened {
+            Heightened::Repeat(lvl, _) => self.tf("Heightened (+{})", &[lvl]),
+            Heightened::Single(lvl, _) => self.tf("Heightened ({})", &[&self.locale.ordinal(*lvl)]),
+        }
+    }
+
+    pub fn source(&self, source: &SpellSource) -> String {
+        match source {
+            SpellSource::Unknown => String::new(),
+            SpellSource::Published { book, page, remaster } => {
+                let mut text: String = book.clone();
+                if let Some(page) = page {
+                    text.push_str(&self.tf(" p. {}", &[page]));
+                }
+                text.push_str(&format!(" ({})", self.t(if *remaster { "remaster" } else { "legacy" })));
+                text
+            },
+            SpellSource::Homebrew { author } if author.is_empty() => self.t("Homebrew"),
+            SpellSource::Homebrew { author } => self.tf("Homebrew by {}", &[author]),
+        }
+    }
+}
+
+#[derive(PartialEq, Properties)]
+pub struct LocalizationSettingProps {
+    pub localization: Localization,
+    pub on_change: Callback<Localization>,
+}
+
+#[function_component]
+pub fn LocalizationSetting(props: &LocalizationSettingProps) -> Html {
+    let LocalizationSettingProps {
+        localization,
+        on_change
+    } = props;
+
+    // # Callback functions
+    let locale_change: Callback<Event> = {
+        let localization: Localization = *localization;
+        let on_change: Callback<Localization> = on_change.clone();
+
+        Callback::from(move |e: Event| {
+            let target: Option<EventTarget> = e.target();
+
+            let select: Option<HtmlSelectElement> = target.and_then(|t: EventTarget| t.dyn_into::<HtmlSelectElement>().ok());
+            if let Some(select) = select {
+                let new_localization: Localization = Localization { locale: Locale::from_code(&select.value()), ..localization };
+                new_localization.save();
+                on_change.emit(new_localization)
+            }
+        })
+    };
+    let units_change: Callback<Event> = {
+        let localization: Localization = *localization;
+        let on_change: Callback<Localization> = on_change.clone();
+
+        Callback::from(move |e: Event| {
+            let target: Option<EventTarget> = e.target();
+
+            let select: Option<HtmlSelectElement> = target.and_then(|t: EventTarget| t.dyn_into::<HtmlSelectElement>().ok());
+            if let Some(select) = select {
+                let new_localization: Localization = Localization { units: Units::from_code(&select.value()), ..localization };
+                new_localization.save();
+                on_change.emit(new_localization)
+            }
+        })
+    };
+
+    html! {
+        <>
+            <label>
+                {localization.t("Language:")}
+                <select onchange={locale_change}>
+                    { Locale::ALL.iter().map(|locale| html!{
+                        <option value={locale.code()} selected={*locale == localization.locale}>{locale.native_name()}</option>
+                    }).collect::<Html>() }
+                </select>
+            </label>
+            <label>
+                {localization.t("Distances:")}
+                <select onchange={units_change}>
+                    { Units::ALL.iter().map(|units| html!{
+                        <option value={units.code()} selected={*units == localization.units}>{localization.t(units.label())}</option>
+                    }).collect::<Html>() }
+                </select>
+            </label>
+        </>
+    }
+}
+
+// # German
+const GERMAN: &[(&str, &str)] = &[
+    ("Loading spellbooks…", "Zauberbücher werden geladen…"),
+    ("Your saved spellbooks could not be loaded ({}). Nothing was changed, try reloading the page.", "Deine gespeicherten Zauberbücher konnten nicht geladen werden ({}). Es wurde nichts verändert, versuche die Seite neu zu laden."),
+    ("Reload", "Neu laden"),
+    ("Browser storage is nearly full ({} of about {} MB used). Export your spellbooks to a file, older backups are removed when space runs out.", "Der Browserspeicher ist fast voll ({} von etwa {} MB belegt). Exportiere deine Zauberbücher in eine Datei, ältere Sicherungen werden entfernt, wenn der Platz ausgeht."),
+    ("Spellbook overview", "Zauberbuch-Übersicht"),
+    ("See backups", "Sicherungen ansehen"),
+    ("Share spellbook link", "Link zum Zauberbuch teilen"),
+    ("Add new card", "Neue Karte hinzufügen"),
+    ("Start encounter", "Begegnung starten"),
+    ("Import spells", "Zauber importieren"),
+    ("Browse spell catalog", "Zauberkatalog durchsuchen"),
+    ("Print cards", "Karten drucken"),
+    ("Settings", "Einstellungen"),
+    ("Add a New Card", "Neue Karte hinzufügen"),
+    ("Edit {}", "{} bearbeiten"),
+    ("Card not found", "Karte nicht gefunden"),
+    ("Back to overview", "Zurück zur Übersicht"),
+    ("Edit card", "Karte bearbeiten"),
+    ("Spellbook name:", "Name des Zauberbuchs:"),
+    ("My spellbook", "Mein Zauberbuch"),
+    ("Restore a backup", "Sicherung wiederherstellen"),
+    ("Backups", "Sicherungen"),
+    ("Back to settings", "Zurück zu den Einstellungen"),
+    ("Merge cards", "Karten zusammenführen"),
+    ("There is nothing to merge.", "Es gibt nichts zusammenzuführen."),
+    ("Encounter", "Begegnung"),
+    ("Spell catalog", "Zauberkatalog"),
+    ("Adding to {}", "Hinzufügen zu {}"),
+    ("Shared spells", "Geteilte Zauber"),
+    ("Go to my spellbook", "Zu meinem Zauberbuch"),
+    ("Your saved spellbooks could not be read and were replaced by the backup from {}. The unreadable data was kept under \"{}\".", "Deine gespeicherten Zauberbücher konnten nicht gelesen werden und wurden durch die Sicherung vom {} ersetzt. Die unlesbaren Daten wurden unter \"{}\" aufbewahrt."),
+    ("Direction:", "Richtung:"),
+    ("Creature size:", "Kreaturengröße:"),
+    ("Medium or smaller", "Mittelgroß oder kleiner"),
+    ("Large", "Groß"),
+    ("Huge", "Riesig"),
+    ("Gargantuan", "Gigantisch"),
+    ("{} ({} squares, click to move the origin)", "{} ({} Felder, klicken, um den Ursprung zu verschieben)"),
+    ("Loading backups…", "Sicherungen werden geladen…"),
+    ("No backups have been made yet.", "Es wurden noch keine Sicherungen erstellt."),
+    ("The latest backup could not be saved: {}", "Die letzte Sicherung konnte nicht gespeichert werden: {}"),
+    ("{}: {} cards", "{}: {} Karten"),
+    (" (currently {})", " (derzeit {})"),
+    (" (not in your current spellbooks)", " (nicht in deinen aktuellen Zauberbüchern)"),
+    ("Same as now", "Wie jetzt"),
+    ("Merge into this book", "In dieses Buch zusammenführen"),
+    ("Restore this backup", "Diese Sicherung wiederherstellen"),
+    ("or", "oder"),
+    ("Focus spells are granted by class features", "Fokuszauber werden durch Klassenmerkmale gewährt"),
+    ("Not on the {} spell list", "Nicht auf der Zauberliste ({})"),
+    ("Rank {} is above rank {}, the highest at level {}", "Rang {} liegt über Rang {}, dem höchsten auf Stufe {}"),
+    ("Uncommon, and uncommon spells are not unlocked", "Ungewöhnlich, und ungewöhnliche Zauber sind nicht freigeschaltet"),
+    ("{}, and rare spells are not unlocked", "{}, und seltene Zauber sind nicht freigeschaltet"),
+    ("Showing what {} can learn: the {} spell list up to rank {}.", "Zeigt, was {} lernen kann: Zauberliste ({}) bis Rang {}."),
+    ("GM unlocked uncommon spells", "SL hat ungewöhnliche Zauber freigeschaltet"),
+    ("GM unlocked rare spells", "SL hat seltene Zauber freigeschaltet"),
+    ("Show all spells", "Alle Zauber anzeigen"),
+    ("Loading the catalog…", "Katalog wird geladen…"),
+    ("Core catalog", "Grundkatalog"),
+    ("The catalog could not be loaded (status {})", "Der Katalog konnte nicht geladen werden (Status {})"),
+    ("{} spells", "{} Zauber"),
+    (", {} this character cannot learn are hidden", ", {}, die dieser Charakter nicht lernen kann, sind ausgeblendet"),
+    ("Also known as {}", "Auch bekannt als {}"),
+    (" Already in this spellbook", " Bereits in diesem Zauberbuch"),
+    ("Spells adapted from Pathfinder Player Core, © Paizo Inc., used under the ORC License.", "Zauber nach Pathfinder Player Core, © Paizo Inc., verwendet unter der ORC-Lizenz."),
+    ("Damage:", "Schaden:"),
+    ("e.g. 6d6", "z. B. 6W6"),
+    ("Expected damage: ", "Erwarteter Schaden: "),
+    ("Expected damage is only worked out for attacks and basic saves.", "Erwarteter Schaden wird nur für Angriffe und einfache Rettungswürfe berechnet."),
+    (" (average roll {})", " (durchschnittlicher Wurf {})"),
+    ("Unsaved drafts", "Ungespeicherte Entwürfe"),
+    (" ({}, saved {}) ", " ({}, gespeichert {}) "),
+    ("Discard", "Verwerfen"),
+    ("Round {}", "Runde {}"),
+    ("Next round", "Nächste Runde"),
+    ("End encounter", "Begegnung beenden"),
+    ("Active spells", "Aktive Zauber"),
+    ("No active spells", "Keine aktiven Zauber"),
+    (" (cast in round {})", " (gewirkt in Runde {})"),
+    (", {} round(s) left", ", noch {} Runde(n)"),
+    ("Sustain", "Aufrechterhalten"),
+    (" (sustained this round)", " (in dieser Runde aufrechterhalten)"),
+    ("Dismiss", "Beenden"),
+    ("Expired", "Abgelaufen"),
+    ("Cast a spell", "Zauber wirken"),
+    ("Cast", "Wirken"),
+    ("Search", "Suche"),
+    ("Any type", "Jede Art"),
+    ("Trait", "Merkmal"),
+    ("Tradition", "Tradition"),
+    ("Source or homebrew", "Quelle oder Eigenkreation"),
+    ("Clear filters", "Filter zurücksetzen"),
+    ("Spell items or compendium packs (.json, .db):", "Zaubergegenstände oder Kompendium-Pakete (.json, .db):"),
+    ("Fields that could not be mapped:", "Felder, die nicht übernommen werden konnten:"),
+    ("The file does not contain Foundry items", "Die Datei enthält keine Foundry-Gegenstände"),
+    ("Skipped a line that is not valid JSON: {}", "Eine Zeile ohne gültiges JSON wurde übersprungen: {}"),
+    ("{}: skipped item of type \"{}\"", "{}: Gegenstand vom Typ \"{}\" übersprungen"),
+    ("could not map cast time \"{}\"", "Zauberdauer \"{}\" konnte nicht übernommen werden"),
+    ("could not map range \"{}\"", "Reichweite \"{}\" konnte nicht übernommen werden"),
+    ("cylinder area mapped to a burst, its height is not kept", "Zylinder als Explosion übernommen, seine Höhe geht verloren"),
+    ("could not map area type \"{}\"", "Bereichstyp \"{}\" konnte nicht übernommen werden"),
+    ("structured damage is only kept as written in the description", "strukturierter Schaden bleibt nur als Text in der Beschreibung erhalten"),
+    ("cost was not mapped", "Kosten wurden nicht übernommen"),
+    ("requirements were not mapped", "Voraussetzungen wurden nicht übernommen"),
+    ("ritual casters and checks were not mapped", "Ritualwirker und Proben wurden nicht übernommen"),
+    ("interval heightening without damage was not mapped", "Intervall-Verstärkung ohne Schaden wurde nicht übernommen"),
+    ("fixed heightening was only mapped to its ranks, fill in the effects", "feste Verstärkung wurde nur mit ihren Graden übernommen, ergänze die Effekte"),
+    ("Add {} card(s) to the spellbook", "{} Karte(n) zum Zauberbuch hinzufügen"),
+    ("Export to Foundry VTT (one file)", "Nach Foundry VTT exportieren (eine Datei)"),
+    ("Export to Foundry VTT (file per card)", "Nach Foundry VTT exportieren (Datei pro Karte)"),
+    ("Undo (Ctrl+Z)", "Rückgängig (Strg+Z)"),
+    ("Undo", "Rückgängig"),
+    ("Redo (Ctrl+Shift+Z)", "Wiederholen (Strg+Umschalt+Z)"),
+    ("Redo", "Wiederholen"),
+    ("The undo history is too large to keep and will be lost on reload", "Der Verlauf ist zu groß zum Speichern und geht beim Neuladen verloren"),
+    ("{}ft", "{} Fuß"),
+    ("{} burst", "Explosion von {}"),
+    ("{} cone", "Kegel von {}"),
+    ("{} emanation", "Ausstrahlung von {}"),
+    ("{} line", "Linie von {}"),
+    ("{} long and {} wide line", "Linie, {} lang und {} breit"),
+    ("Heightened (+{})", "Verstärkt (+{})"),
+    ("Heightened ({})", "Verstärkt ({})"),
+    (" p. {}", " S. {}"),
+    ("Homebrew", "Eigenkreation"),
+    ("Homebrew by {}", "Eigenkreation von {}"),
+    ("Language:", "Sprache:"),
+    ("Distances:", "Entfernungen:"),
+    ("Feet", "Fuß"),
+    ("Metres (1.5 m per square)", "Meter (1,5 m pro Feld)"),
+    ("Grid squares", "Rasterfelder"),
+    ("{} square", "{} Feld"),
+    ("{} squares", "{} Felder"),
+    ("One square is {}", "Ein Feld entspricht {}"),
+    ("In feet, 5 ft per square", "In Fuß, 5 Fuß pro Feld"),
+    ("{} brings {} new card(s) and {} card(s) that differ from yours. Pick which version of each field to keep.", "{} bringt {} neue Karte(n) und {} Karte(n), die sich von deinen unterscheiden. Wähle für jedes Feld, welche Fassung bleibt."),
+    ("Keep all of mine", "Alle meine behalten"),
+    ("Take all incoming", "Alle neuen übernehmen"),
+    ("Field", "Feld"),
+    ("Before", "Vorher"),
+    ("Mine", "Meine"),
+    ("Incoming", "Neu"),
+    ("Added as new cards: {}", "Als neue Karten hinzugefügt: {}"),
+    ("Left out, as an earlier incoming card already matches the same card of yours: {}", "Ausgelassen, da schon eine frühere eingehende Karte zu derselben deiner Karten passt: {}"),
+    ("Merge", "Zusammenführen"),
+    ("Cancel", "Abbrechen"),
+    ("Character export (.json):", "Charakterexport (.json):"),
+    ("{} spell(s) matched your cards or the catalog", "{} Zauber passten zu deinen Karten oder zum Katalog"),
+    ("Not valid JSON: {}", "Kein gültiges JSON: {}"),
+    ("No spellcasting found, is this a Pathbuilder 2e export?", "Kein Zauberwirken gefunden, ist das ein Pathbuilder-2e-Export?"),
+    ("Focus spells", "Fokuszauber"),
+    ("No card found for these spells, add them by hand:", "Keine Karte für diese Zauber gefunden, füge sie von Hand hinzu:"),
+    ("Create spellbook \"{}\"", "Zauberbuch \"{}\" anlegen"),
+    ("Paper:", "Papier:"),
+    ("Card size:", "Kartengröße:"),
+    ("Print", "Drucken"),
+    ("No document", "Kein Dokument"),
+    ("Could not serialise the card", "Die Karte konnte nicht serialisiert werden"),
+    ("The download could not be started", "Der Download konnte nicht gestartet werden"),
+    ("Copied to the clipboard", "In die Zwischenablage kopiert"),
+    ("Share link", "Link teilen"),
+    ("The link is damaged", "Der Link ist beschädigt"),
+    ("The link does not contain spell cards", "Der Link enthält keine Zauberkarten"),
+    ("This link is {} characters long, links over {} characters may be cut off when shared. Consider sharing fewer cards or exporting a file instead.", "Dieser Link ist {} Zeichen lang, Links über {} Zeichen können beim Teilen abgeschnitten werden. Teile weniger Karten oder exportiere stattdessen eine Datei."),
+    ("Add to my spellbook", "Zu meinem Zauberbuch hinzufügen"),
+    ("{}, level {} {}", "{}, Stufe {} {}"),
+    ("No spells match the current filters.", "Keine Zauber passen zu den aktuellen Filtern."),
+    ("Details", "Details"),
+    ("Edit", "Bearbeiten"),
+    ("Traditions", "Traditionen"),
+    ("Tada!", "Tada!"),
+    ("You have an unsaved draft of \"{}\" from {}. Editing the card replaces it.", "Du hast einen ungespeicherten Entwurf von \"{}\" vom {}. Wenn du die Karte bearbeitest, wird er ersetzt."),
+    ("Resume draft", "Entwurf fortsetzen"),
+    ("Discard draft", "Entwurf verwerfen"),
+    ("Paste stat block:", "Werteblock einfügen:"),
+    ("Copy a spell from Archives of Nethys and paste it here", "Kopiere einen Zauber aus den Archives of Nethys und füge ihn hier ein"),
+    ("Fill in from stat block", "Aus Werteblock ausfüllen"),
+    ("Could not find a line like \"<name> Spell <rank>\"", "Keine Zeile wie \"<Name> Spell <Grad>\" gefunden"),
+    ("Unrecognised cast time \"{}\"", "Unbekannte Zauberdauer \"{}\""),
+    ("Range \"{}\" does not fit the range field, added to the effect instead", "Reichweite \"{}\" passt nicht in das Reichweitenfeld und wurde stattdessen zur Wirkung hinzugefügt"),
+    ("Unrecognised area \"{}\"", "Unbekannter Bereich \"{}\""),
+    ("Unrecognised defense \"{}\"", "Unbekannte Verteidigung \"{}\""),
+    ("Spell name:", "Name des Zaubers:"),
+    ("Link:", "Link:"),
+    ("Source:", "Quelle:"),
+    ("Not given", "Nicht angegeben"),
+    ("Published book", "Veröffentlichtes Buch"),
+    ("Book", "Buch"),
+    ("Page", "Seite"),
+    ("Remaster", "Remaster"),
+    ("Author", "Autor"),
+    ("Cast time:", "Zauberzeit:"),
+    ("Free action", "Freie Aktion"),
+    ("Reaction", "Reaktion"),
+    ("Single action", "Eine Aktion"),
+    ("Two actions", "Zwei Aktionen"),
+    ("Three actions", "Drei Aktionen"),
+    ("Range", "Reichweite"),
+    ("Longer, specify", "Länger, angeben"),
+    ("Spell type:", "Zauberart:"),
+    ("Cantrip", "Zaubertrick"),
+    ("Spell", "Zauber"),
+    ("Focus", "Fokus"),
+    ("Ritual", "Ritual"),
+    ("Traits:", "Merkmale:"),
+    ("Traditions:", "Traditionen:"),
+    ("Range:", "Reichweite:"),
+    ("Area:", "Bereich:"),
+    ("Burst", "Explosion"),
+    ("Cone", "Kegel"),
+    ("Emanation", "Ausstrahlung"),
+    ("Line", "Linie"),
+    ("Targets:", "Ziele:"),
+    ("None", "Keine"),
+    ("Fortitude", "Zähigkeit"),
+    ("Reflex", "Reflex"),
+    ("basic {}", "einfacher {}"),
+    ("Basic save", "Einfacher Rettungswurf"),
+    ("Will", "Willen"),
+    ("Duration:", "Wirkungsdauer:"),
+    ("Spell effect:", "Zauberwirkung:"),
+    ("Roll effect", "Wurfergebnis"),
+    ("Critical success:", "Kritischer Erfolg:"),
+    ("Success:", "Erfolg:"),
+    ("Failure:", "Fehlschlag:"),
+    ("Critical failure:", "Kritischer Fehlschlag:"),
+    ("Heightened", "Verstärkt"),
+    ("Repeat", "Wiederholt"),
+    ("Once", "Einmalig"),
+    ("save", "speichern"),
+    ("cancel", "abbrechen"),
+    ("Sync", "Synchronisation"),
+    ("Server:", "Server:"),
+    ("Library id:", "Bibliotheks-ID:"),
+    ("Same on every device", "Auf jedem Gerät gleich"),
+    ("Sync now", "Jetzt synchronisieren"),
+    ("Syncing…", "Synchronisiere…"),
+    ("Up to date", "Aktuell"),
+    ("Edited on this device and another one since the last sync:", "Seit der letzten Synchronisation auf diesem und einem anderen Gerät bearbeitet:"),
+    ("Review field by field", "Feld für Feld prüfen"),
+    ("Spell rank {}", "Zauberrang {}"),
+    ("Spell level {}", "Zaubergrad {}"),
+    ("For every rank above the spell's own", "Für jeden Rang über dem des Zaubers"),
+    ("For every {} ranks above the spell's own", "Für je {} Ränge über dem des Zaubers"),
+    ("When cast at rank {} or higher", "Wenn auf Rang {} oder höher gewirkt"),
+    ("For every spell level above the spell's own", "Für jeden Zaubergrad über dem des Zaubers"),
+    ("For every {} spell levels above the spell's own", "Für je {} Zaubergrade über dem des Zaubers"),
+    ("When cast at spell level {} or higher", "Wenn auf Zaubergrad {} oder höher gewirkt"),
+    ("Terminology:", "Begriffe:"),
+    ("Remaster (Player Core)", "Remaster (Player Core)"),
+    ("Legacy (Core Rulebook)", "Alt (Grundregelwerk)"),
+    ("Source", "Quelle"),
+    ("Traits", "Merkmale"),
+    ("Copy Markdown", "Markdown kopieren"),
+    ("Copy text", "Text kopieren"),
+    ("Export as Markdown", "Als Markdown exportieren"),
+    ("Export as text", "Als Text exportieren"),
+    ("Critical Success", "Kritischer Erfolg"),
+    ("Success", "Erfolg"),
+    ("Failure", "Fehlschlag"),
+    ("Critical Failure", "Kritischer Fehlschlag"),
+    ("East", "Osten"),
+    ("South-east", "Südosten"),
+    ("South", "Süden"),
+    ("South-west", "Südwesten"),
+    ("West", "Westen"),
+    ("North-west", "Nordwesten"),
+    ("North", "Norden"),
+    ("North-east", "Nordosten"),
+    ("Letter", "Letter"),
+    ("Poker (63 x 88 mm)", "Poker (63 x 88 mm)"),
+    ("Tarot (70 x 120 mm)", "Tarot (70 x 120 mm)"),
+    ("Name", "Name"),
+    ("Cast time", "Zauberzeit"),
+    ("Type", "Art"),
+    ("Level", "Grad"),
+    ("Link", "Link"),
+    ("Overview", "Übersicht"),
+    ("Effect", "Wirkung"),
+    ("Degrees of success", "Erfolgsgrade"),
+    ("Rank", "Rang"),
+    ("Defense", "Verteidigung"),
+    ("Saving throw", "Rettungswurf"),
+    ("Saving Throw", "Rettungswurf"),
+    ("Attack", "Angriff"),
+    ("Spell attack vs. AC", "Zauberangriff gegen RK"),
+    ("AC", "RK"),
+    ("Area", "Bereich"),
+    ("Targets", "Ziele"),
+    ("Duration", "Wirkungsdauer"),
+    ("remaster", "Remaster"),
+    ("legacy", "alt"),
+    ("Uncommon", "Ungewöhnlich"),
+    ("Rare", "Selten"),
+    ("Unique", "Einzigartig"),
+    ("arcane", "arkan"),
+    ("divine", "göttlich"),
+    ("occult", "okkult"),
+    ("primal", "primal"),
+    ("new card", "neue Karte"),
+    ("edited card", "bearbeitete Karte"),
+    ("kept this device's version", "Fassung dieses Geräts behalten"),
+    ("took the other device's version", "Fassung des anderen Geräts übernommen"),
+    ("{} ({}): {}", "{} ({}): {}"),
+    ("The server answered with status {}", "Der Server antwortete mit Status {}"),
+    ("Other devices kept syncing at the same time, please try again", "Andere Geräte haben gleichzeitig synchronisiert, bitte versuche es erneut"),
+    ("The import", "Der Import"),
+    ("The shared link", "Der geteilte Link"),
+    ("The backup", "Die Sicherung"),
+    ("The versions the sync set aside", "Die bei der Synchronisation zurückgestellten Fassungen"),
+    ("Would bring back", "Würde zurückbringen"),
+    ("Would remove", "Würde entfernen"),
+    ("Would change", "Würde ändern"),
+    ("Spell attack modifier:", "Zauberangriffsmodifikator:"),
+    ("Target AC:", "RK des Ziels:"),
+    ("Target save modifier:", "Rettungswurfmodifikator des Ziels:"),
+    ("Spell DC:", "Zauber-SG:"),
+    ("Add another copy", "Weitere Kopie hinzufügen"),
+];
+
+// # French
+const FRENCH: &[(&str, &str)] = &[
+    ("Loading spellbooks…", "Chargement des grimoires…"),
+    ("Your saved spellbooks could not be loaded ({}). Nothing was changed, try reloading the page.", "Vos grimoires enregistrés n'ont pas pu être chargés ({}). Rien n'a été modifié, essayez de recharger la page."),
+    ("Reload", "Recharger"),
+    ("Browser storage is nearly full ({} of about {} MB used). Export your spellbooks to a file, older backups are removed when space runs out.", "Le stockage du navigateur est presque plein ({} sur environ {} Mo utilisés). Exportez vos grimoires dans un fichier, les anciennes sauvegardes sont supprimées lorsque l'espace manque."),
+    ("Spellbook overview", "Aperçu du grimoire"),
+    ("See backups", "Voir les sauvegardes"),
+    ("Share spellbook link", "Partager le lien du grimoire"),
+    ("Add new card", "Ajouter une carte"),
+    ("Start encounter", "Commencer une rencontre"),
+    ("Import spells", "Importer des sorts"),
+    ("Browse spell catalog", "Parcourir le catalogue de sorts"),
+    ("Print cards", "Imprimer les cartes"),
+    ("Settings", "Paramètres"),
+    ("Add a New Card", "Ajouter une nouvelle carte"),
+    ("Edit {}", "Modifier {}"),
+    ("Card not found", "Carte introuvable"),
+    ("Back to overview", "Retour à l'aperçu"),
+    ("Edit card", "Modifier la carte"),
+    ("Spellbook name:", "Nom du grimoire :"),
+    ("My spellbook", "Mon grimoire"),
+    ("Restore a backup", "Restaurer une sauvegarde"),
+    ("Backups", "Sauvegardes"),
+    ("Back to settings", "Retour aux paramètres"),
+    ("Merge cards", "Fusionner les cartes"),
+    ("There is nothing to merge.", "Il n'y a rien à fusionner."),
+    ("Encounter", "Rencontre"),
+    ("Spell catalog", "Catalogue de sorts"),
+    ("Adding to {}", "Ajout à {}"),
+    ("Shared spells", "Sorts partagés"),
+    ("Go to my spellbook", "Aller à mon grimoire"),
+    ("Your saved spellbooks could not be read and were replaced by the backup from {}. The unreadable data was kept under \"{}\".", "Vos grimoires enregistrés n'ont pas pu être lus et ont été remplacés par la sauvegarde du {}. Les données illisibles ont été conservées sous \"{}\"."),
+    ("Direction:", "Direction :"),
+    ("Creature size:", "Taille de la créature :"),
+    ("Medium or smaller", "Moyenne ou plus petite"),
+    ("Large", "Grande"),
+    ("Huge", "Très grande"),
+    ("Gargantuan", "Gigantesque"),
+    ("{} ({} squares, click to move the origin)", "{} ({} cases, cliquez pour déplacer l'origine)"),
+    ("Loading backups…", "Chargement des sauvegardes…"),
+    ("No backups have been made yet.", "Aucune sauvegarde n'a encore été faite."),
+    ("The latest backup could not be saved: {}", "La dernière sauvegarde n'a pas pu être enregistrée : {}"),
+    ("{}: {} cards", "{} : {} cartes"),
+    (" (currently {})", " (actuellement {})"),
+    (" (not in your current spellbooks)", " (absent de vos grimoires actuels)"),
+    ("Same as now", "Identique à maintenant"),
+    ("Merge into this book", "Fusionner dans ce grimoire"),
+    ("Restore this backup", "Restaurer cette sauvegarde"),
+    ("or", "ou"),
+    ("Focus spells are granted by class features", "Les sorts focalisés sont accordés par des capacités de classe"),
+    ("Not on the {} spell list", "Absent de la liste de sorts {}"),
+    ("Rank {} is above rank {}, the highest at level {}", "Le rang {} dépasse le rang {}, le plus élevé au niveau {}"),
+    ("Uncommon, and uncommon spells are not unlocked", "Peu courant, et les sorts peu courants ne sont pas débloqués"),
+    ("{}, and rare spells are not unlocked", "{}, et les sorts rares ne sont pas débloqués"),
+    ("Showing what {} can learn: the {} spell list up to rank {}.", "Sorts que {} peut apprendre : la liste {} jusqu'au rang {}."),
+    ("GM unlocked uncommon spells", "Le MJ a débloqué les sorts peu courants"),
+    ("GM unlocked rare spells", "Le MJ a débloqué les sorts rares"),
+    ("Show all spells", "Afficher tous les sorts"),
+    ("Loading the catalog…", "Chargement du catalogue…"),
+    ("Core catalog", "Catalogue de base"),
+    ("The catalog could not be loaded (status {})", "Le catalogue n'a pas pu être chargé (statut {})"),
+    ("{} spells", "{} sorts"),
+    (", {} this character cannot learn are hidden", ", {} que ce personnage ne peut pas apprendre sont masqués"),
+    ("Also known as {}", "Aussi appelé {}"),
+    (" Already in this spellbook", " Déjà dans ce grimoire"),
+    ("Spells adapted from Pathfinder Player Core, © Paizo Inc., used under the ORC License.", "Sorts adaptés de Pathfinder Player Core, © Paizo Inc., utilisés sous la licence ORC."),
+    ("Damage:", "Dégâts :"),
+    ("e.g. 6d6", "ex. 6d6"),
+    ("Expected damage: ", "Dégâts attendus : "),
+    ("Expected damage is only worked out for attacks and basic saves.", "Les dégâts attendus ne sont calculés que pour les attaques et les jets de sauvegarde basiques."),
+    (" (average roll {})", " (jet moyen {})"),
+    ("Unsaved drafts", "Brouillons non enregistrés"),
+    (" ({}, saved {}) ", " ({}, enregistré {}) "),
+    ("Discard", "Supprimer"),
+    ("Round {}", "Round {}"),
+    ("Next round", "Round suivant"),
+    ("End encounter", "Terminer la rencontre"),
+    ("Active spells", "Sorts actifs"),
+    ("No active spells", "Aucun sort actif"),
+    (" (cast in round {})", " (lancé au round {})"),
+    (", {} round(s) left", ", encore {} round(s)"),
+    ("Sustain", "Maintenir"),
+    (" (sustained this round)", " (maintenu ce round)"),
+    ("Dismiss", "Révoquer"),
+    ("Expired", "Expirés"),
+    ("Cast a spell", "Lancer un sort"),
+    ("Cast", "Lancer"),
+    ("Search", "Rechercher"),
+    ("Any type", "Tout type"),
+    ("Trait", "Trait"),
+    ("Tradition", "Tradition"),
+    ("Source or homebrew", "Source ou création maison"),
+    ("Clear filters", "Effacer les filtres"),
+    ("Spell items or compendium packs (.json, .db):", "Objets de sort ou compendiums (.json, .db) :"),
+    ("Fields that could not be mapped:", "Champs qui n'ont pas pu être repris :"),
+    ("The file does not contain Foundry items", "Le fichier ne contient pas d'objets Foundry"),
+    ("Skipped a line that is not valid JSON: {}", "Ligne ignorée, JSON invalide : {}"),
+    ("{}: skipped item of type \"{}\"", "{} : objet de type \"{}\" ignoré"),
+    ("could not map cast time \"{}\"", "le temps d'incantation \"{}\" n'a pas pu être repris"),
+    ("could not map range \"{}\"", "la portée \"{}\" n'a pas pu être reprise"),
+    ("cylinder area mapped to a burst, its height is not kept", "cylindre repris comme une explosion, sa hauteur est perdue"),
+    ("could not map area type \"{}\"", "le type de zone \"{}\" n'a pas pu être repris"),
+    ("structured damage is only kept as written in the description", "les dégâts structurés ne sont conservés que dans le texte de la description"),
+    ("cost was not mapped", "le coût n'a pas été repris"),
+    ("requirements were not mapped", "les conditions n'ont pas été reprises"),
+    ("ritual casters and checks were not mapped", "les incantateurs et tests de rituel n'ont pas été repris"),
+    ("interval heightening without damage was not mapped", "l'intensification par intervalle sans dégâts n'a pas été reprise"),
+    ("fixed heightening was only mapped to its ranks, fill in the effects", "l'intensification fixe n'a été reprise que pour ses rangs, complétez les effets"),
+    ("Add {} card(s) to the spellbook", "Ajouter {} carte(s) au grimoire"),
+    ("Export to Foundry VTT (one file)", "Exporter vers Foundry VTT (un fichier)"),
+    ("Export to Foundry VTT (file per card)", "Exporter vers Foundry VTT (un fichier par carte)"),
+    ("Undo (Ctrl+Z)", "Annuler (Ctrl+Z)"),
+    ("Undo", "Annuler"),
+    ("Redo (Ctrl+Shift+Z)", "Rétablir (Ctrl+Maj+Z)"),
+    ("Redo", "Rétablir"),
+    ("The undo history is too large to keep and will be lost on reload", "L'historique est trop volumineux pour être conservé et sera perdu au rechargement"),
+    ("{}ft", "{} pieds"),
+    ("{} burst", "explosion de {}"),
+    ("{} cone", "cône de {}"),
+    ("{} emanation", "émanation de {}"),
+    ("{} line", "ligne de {}"),
+    ("{} long and {} wide line", "ligne de {} de long et {} de large"),
+    ("Heightened (+{})", "Intensifié (+{})"),
+    ("Heightened ({})", "Intensifié ({})"),
+    (" p. {}", " p. {}"),
+    ("Homebrew", "Création maison"),
+    ("Homebrew by {}", "Création maison de {}"),
+    ("Language:", "Langue :"),
+    ("Distances:", "Distances :"),
+    ("Feet", "Pieds"),
+    ("Metres (1.5 m per square)", "Mètres (1,5 m par case)"),
+    ("Grid squares", "Cases de la grille"),
+    ("{} square", "{} case"),
+    ("{} squares", "{} cases"),
+    ("One square is {}", "Une case fait {}"),
+    ("In feet, 5 ft per square", "En pieds, 5 pieds par case"),
+    ("{} brings {} new card(s) and {} card(s) that differ from yours. Pick which version of each field to keep.", "{} apporte {} nouvelle(s) carte(s) et {} carte(s) différente(s) des vôtres. Choisissez la version à garder pour chaque champ."),
+    ("Keep all of mine", "Garder toutes les miennes"),
+    ("Take all incoming", "Prendre toutes les nouvelles"),
+    ("Field", "Champ"),
+    ("Before", "Avant"),
+    ("Mine", "La mienne"),
+    ("Incoming", "Nouvelle"),
+    ("Added as new cards: {}", "Ajoutées comme nouvelles cartes : {}"),
+    ("Left out, as an earlier incoming card already matches the same card of yours: {}", "Laissées de côté, car une carte entrante précédente correspond déjà à la même de vos cartes : {}"),
+    ("Merge", "Fusionner"),
+    ("Cancel", "Annuler"),
+    ("Character export (.json):", "Export du personnage (.json) :"),
+    ("{} spell(s) matched your cards or the catalog", "{} sort(s) correspondent à vos cartes ou au catalogue"),
+    ("Not valid JSON: {}", "JSON non valide : {}"),
+    ("No spellcasting found, is this a Pathbuilder 2e export?", "Aucune incantation trouvée, est-ce un export Pathbuilder 2e ?"),
+    ("Focus spells", "Sorts focalisés"),
+    ("No card found for these spells, add them by hand:", "Aucune carte trouvée pour ces sorts, ajoutez-les à la main :"),
+    ("Create spellbook \"{}\"", "Créer le grimoire \"{}\""),
+    ("Paper:", "Papier :"),
+    ("Card size:", "Taille des cartes :"),
+    ("Print", "Imprimer"),
+    ("No document", "Aucun document"),
+    ("Could not serialise the card", "La carte n'a pas pu être sérialisée"),
+    ("The download could not be started", "Le téléchargement n'a pas pu démarrer"),
+    ("Copied to the clipboard", "Copié dans le presse-papiers"),
+    ("Share link", "Partager un lien"),
+    ("The link is damaged", "Le lien est endommagé"),
+    ("The link does not contain spell cards", "Le lien ne contient pas de cartes de sort"),
+    ("This link is {} characters long, links over {} characters may be cut off when shared. Consider sharing fewer cards or exporting a file instead.", "Ce lien fait {} caractères, les liens de plus de {} caractères peuvent être tronqués lors du partage. Partagez moins de cartes ou exportez plutôt un fichier."),
+    ("Add to my spellbook", "Ajouter à mon grimoire"),
+    ("{}, level {} {}", "{}, niveau {} {}"),
+    ("No spells match the current filters.", "Aucun sort ne correspond aux filtres actuels."),
+    ("Details", "Détails"),
+    ("Edit", "Modifier"),
+    ("Traditions", "Traditions"),
+    ("Tada!", "Tada !"),
+    ("You have an unsaved draft of \"{}\" from {}. Editing the card replaces it.", "Vous avez un brouillon non enregistré de \"{}\" du {}. Modifier la carte le remplace."),
+    ("Resume draft", "Reprendre le brouillon"),
+    ("Discard draft", "Supprimer le brouillon"),
+    ("Paste stat block:", "Coller le bloc de statistiques :"),
+    ("Copy a spell from Archives of Nethys and paste it here", "Copiez un sort depuis Archives of Nethys et collez-le ici"),
+    ("Fill in from stat block", "Remplir depuis le bloc"),
+    ("Could not find a line like \"<name> Spell <rank>\"", "Aucune ligne comme \"<nom> Spell <rang>\" n'a été trouvée"),
+    ("Unrecognised cast time \"{}\"", "Temps d'incantation inconnu \"{}\""),
+    ("Range \"{}\" does not fit the range field, added to the effect instead", "La portée \"{}\" ne tient pas dans le champ de portée, elle a été ajoutée à l'effet"),
+    ("Unrecognised area \"{}\"", "Zone inconnue \"{}\""),
+    ("Unrecognised defense \"{}\"", "Défense inconnue \"{}\""),
+    ("Spell name:", "Nom du sort :"),
+    ("Link:", "Lien :"),
+    ("Source:", "Source :"),
+    ("Not given", "Non précisée"),
+    ("Published book", "Livre publié"),
+    ("Book", "Livre"),
+    ("Page", "Page"),
+    ("Remaster", "Remaster"),
+    ("Author", "Auteur"),
+    ("Cast time:", "Temps d'incantation :"),
+    ("Free action", "Action gratuite"),
+    ("Reaction", "Réaction"),
+    ("Single action", "Une action"),
+    ("Two actions", "Deux actions"),
+    ("Three actions", "Trois actions"),
+    ("Range", "Portée"),
+    ("Longer, specify", "Plus long, préciser"),
+    ("Spell type:", "Type de sort :"),
+    ("Cantrip", "Tour de magie"),
+    ("Spell", "Sort"),
+    ("Focus", "Focalisé"),
+    ("Ritual", "Rituel"),
+    ("Traits:", "Traits :"),
+    ("Traditions:", "Traditions :"),
+    ("Range:", "Portée :"),
+    ("Area:", "Zone :"),
+    ("Burst", "Explosion"),
+    ("Cone", "Cône"),
+    ("Emanation", "Émanation"),
+    ("Line", "Ligne"),
+    ("Targets:", "Cibles :"),
+    ("None", "Aucune"),
+    ("Fortitude", "Vigueur"),
+    ("Reflex", "Réflexes"),
+    ("basic {}", "{} basique"),
+    ("Basic save", "Sauvegarde basique"),
+    ("Will", "Volonté"),
+    ("Duration:", "Durée :"),
+    ("Spell effect:", "Effet du sort :"),
+    ("Roll effect", "Effet du jet"),
+    ("Critical success:", "Succès critique :"),
+    ("Success:", "Succès :"),
+    ("Failure:", "Échec :"),
+    ("Critical failure:", "Échec critique :"),
+    ("Heightened", "Intensifié"),
+    ("Repeat", "Répété"),
+    ("Once", "Une fois"),
+    ("save", "enregistrer"),
+    ("cancel", "annuler"),
+    ("Sync", "Synchronisation"),
+    ("Server:", "Serveur :"),
+    ("Library id:", "Identifiant de bibliothèque :"),
+    ("Same on every device", "Identique sur chaque appareil"),
+    ("Sync now", "Synchroniser"),
+    ("Syncing…", "Synchronisation…"),
+    ("Up to date", "À jour"),
+    ("Edited on this device and another one since the last sync:", "Modifié sur cet appareil et sur un autre depuis la dernière synchronisation :"),
+    ("Review field by field", "Revoir champ par champ"),
+    ("Spell rank {}", "Sort de rang {}"),
+    ("Spell level {}", "Sort de niveau {}"),
+    ("For every rank above the spell's own", "Pour chaque rang au-dessus de celui du sort"),
+    ("For every {} ranks above the spell's own", "Pour chaque tranche de {} rangs au-dessus de celui du sort"),
+    ("When cast at rank {} or higher", "Lancé au rang {} ou plus"),
+    ("For every spell level above the spell's own", "Pour chaque niveau de sort au-dessus de celui du sort"),
+    ("For every {} spell levels above the spell's own", "Pour chaque tranche de {} niveaux de sort au-dessus de celui du sort"),
+    ("When cast at spell level {} or higher", "Lancé au niveau de sort {} ou plus"),
+    ("Terminology:", "Terminologie :"),
+    ("Remaster (Player Core)", "Remaster (Player Core)"),
+    ("Legacy (Core Rulebook)", "Ancienne (Livre de base)"),
+    ("Source", "Source"),
+    ("Traits", "Traits"),
+    ("Copy Markdown", "Copier en Markdown"),
+    ("Copy text", "Copier le texte"),
+    ("Export as Markdown", "Exporter en Markdown"),
+    ("Export as text", "Exporter en texte"),
+    ("Critical Success", "Succès critique"),
+    ("Success", "Succès"),
+    ("Failure", "Échec"),
+    ("Critical Failure", "Échec critique"),
+    ("East", "Est"),
+    ("South-east", "Sud-est"),
+    ("South", "Sud"),
+    ("South-west", "Sud-ouest"),
+    ("West", "Ouest"),
+    ("North-west", "Nord-ouest"),
+    ("North", "Nord"),
+    ("North-east", "Nord-est"),
+    ("Letter", "Lettre US"),
+    ("Poker (63 x 88 mm)", "Poker (63 x 88 mm)"),
+    ("Tarot (70 x 120 mm)", "Tarot (70 x 120 mm)"),
+    ("Name", "Nom"),
+    ("Cast time", "Temps d'incantation"),
+    ("Type", "Type"),
+    ("Level", "Niveau"),
+    ("Link", "Lien"),
+    ("Overview", "Aperçu"),
+    ("Effect", "Effet"),
+    ("Degrees of success", "Degrés de réussite"),
+    ("Rank", "Rang"),
+    ("Defense", "Défense"),
+    ("Saving throw", "Jet de sauvegarde"),
+    ("Saving Throw", "Jet de sauvegarde"),
+    ("Attack", "Attaque"),
+    ("Spell attack vs. AC", "Attaque de sort contre CA"),
+    ("AC", "CA"),
+    ("Area", "Zone"),
+    ("Targets", "Cibles"),
+    ("Duration", "Durée"),
+    ("remaster", "remaster"),
+    ("legacy", "ancienne"),
+    ("Uncommon", "Peu courant"),
+    ("Rare", "Rare"),
+    ("Unique", "Unique"),
+    ("arcane", "arcanique"),
+    ("divine", "divine"),
+    ("occult", "occulte"),
+    ("primal", "primordiale"),
+    ("new card", "nouvelle carte"),
+    ("edited card", "carte modifiée"),
+    ("kept this device's version", "version de cet appareil gardée"),
+    ("took the other device's version", "version de l'autre appareil reprise"),
+    ("{} ({}): {}", "{} ({}) : {}"),
+    ("The server answered with status {}", "Le serveur a répondu avec le statut {}"),
+    ("Other devices kept syncing at the same time, please try again", "D'autres appareils synchronisaient en même temps, veuillez réessayer"),
+    ("The import", "L'import"),
+    ("The shared link", "Le lien partagé"),
+    ("The backup", "La sauvegarde"),
+    ("The versions the sync set aside", "Les versions mises de côté par la synchronisation"),
+    ("Would bring back", "Rétablirait"),
+    ("Would remove", "Supprimerait"),
+    ("Would change", "Modifierait"),
+    ("Spell attack modifier:", "Modificateur d'attaque de sort :"),
+    ("Target AC:", "CA de la cible :"),
+    ("Target save modifier:", "Modificateur de sauvegarde de la cible :"),
+    ("Spell DC:", "DD des sorts :"),
+    ("Add another copy", "Ajouter une autre copie"),
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn localization(locale: Locale, units: Units) -> Localization {
+        Localization { locale, units }
+    }
+
+    #[test]
+    fn one_square_is_five_feet_or_one_and_a_half_metres() {
+        assert_eq!(localization(Locale::English, Units::Feet).distance(5), "5ft");
+        assert_eq!(localization(Locale::English, Units::Metres).distance(5), "1.5 m");
+        assert_eq!(localization(Locale::English, Units::Squares).distance(5), "1 square");
+        assert_eq!(localization(Locale::English, Units::Metres).distance(30), "9 m");
+        assert_eq!(localization(Locale::English, Units::Squares).distance(30), "6 squares");
+    }
+
+    #[test]
+    fn distances_between_squares_keep_one_decimal() {
+        assert_eq!(localization(Locale::English, Units::Metres).distance(12), "3.6 m");
+        assert_eq!(localization(Locale::English, Units::Squares).distance(12), "2.4 squares");
+        assert_eq!(localization(Locale::English, Units::Squares).distance(2), "0.4 squares");
+        assert_eq!(localization(Locale::English, Units::Metres).distance(255), "76.5 m");
+        assert_eq!(localization(Locale::English, Units::Squares).distance(0), "0 squares");
+    }
+
+    #[test]
+    fn distances_use_the_locale_decimal_separator() {
+        assert_eq!(localization(Locale::German, Units::Metres).distance(25), "7,5 m");
+        assert_eq!(localization(Locale::French, Units::Squares).distance(7), "1,4 cases");
+        assert_eq!(localization(Locale::German, Units::Feet).distance(30), "30 Fuß");
+    }
+
+    #[test]
+    fn areas_show_every_distance_in_the_chosen_units() {
+        assert_eq!(localization(Locale::English, Units::Feet).area(&Area::Burst(20)), "20ft burst");
+        assert_eq!(localization(Locale::English, Units::Metres).area(&Area::Cone(15)), "4.5 m cone");
+        assert_eq!(localization(Locale::English, Units::Squares).area(&Area::Emanation(5)), "1 square emanation");
+        assert_eq!(localization(Locale::English, Units::Squares).area(&Area::Line(60, Some(5))), "12 squares long and 1 square wide line");
+        assert_eq!(localization(Locale::German, Units::Squares).area(&Area::Line(60, Some(5))), "Linie, 12 Felder lang und 1 Feld breit");
+        assert_eq!(localization(Locale::French, Units::Metres).area(&Area::Line(30, None)), "ligne de 9 m");
+    }
+
+    #[test]
+    fn templates_are_filled_in_order() {
+        let english: Localization = Localization::default();
+        assert_eq!(english.tf("{} of {}", &[&1, &"two"]), "1 of two");
+        assert_eq!(english.tf("no placeholders", &[&1]), "no placeholders");
+        assert_eq!(localization(Locale::French, Units::Feet).t("an untranslated text"), "an untranslated text");
+    }
+
+    #[test]
+    fn ordinals_follow_the_locale() {
+        assert_eq!(Locale::English.ordinal(1), "1st");
+        assert_eq!(Locale::English.ordinal(2), "2nd");
+        assert_eq!(Locale::English.ordinal(3), "3rd");
+        assert_eq!(Locale::English.ordinal(11), "11th");
+        assert_eq!(Locale::German.ordinal(4), "4.");
+        assert_eq!(Locale::French.ordinal(1), "1er");
+        assert_eq!(Locale::French.ordinal(2), "2e");
+    }
+}
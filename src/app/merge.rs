@@ -0,0 +1,394 @@
+use std::fmt::Display;
+use yew::prelude::*;
+
+use super::localization::Localization;
+use super::spell_card::{Defence, SpellCard, SpellOverview};
+use super::text_export::cast_time_text;
+
+#[derive(PartialEq, Eq, Clone, Copy)]
+pub enum CardField {
+    Name,
+    CastTime,
+    Type,
+    Level,
+    Link,
+    Source,
+    Traits,
+    Traditions,
+    Overview,
+    Effect,
+    RollEffect,
+    Heightened,
+}
+
+impl Display for CardField {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let field_str: &str = match self {
+            CardField::Name       => "Name",
+            CardField::CastTime   => "Cast time",
+            CardField::Type       => "Type",
+            CardField::Level      => "Level",
+            CardField::Link       => "Link",
+            CardField::Source     => "Source",
+            CardField::Traits     => "Traits",
+            CardField::Traditions => "Traditions",
+            CardField::Overview   => "Overview",
+            CardField::Effect     => "Effect",
+            CardField::RollEffect => "Degrees of success",
+            CardField::Heightened => "Heightened",
+        };
+        write!(f, "{}", field_str)
+    }
+}
+
+impl CardField {
+    pub const ALL: [CardField; 12] = [
+        CardField::Name,
+        CardField::CastTime,
+        CardField::Type,
+        CardField::Level,
+        CardField::Link,
+        CardField::Source,
+        CardField::Traits,
+        CardField::Traditions,
+        CardField::Overview,
+        CardField::Effect,
+        CardField::RollEffect,
+        CardField::Heightened,
+    ];
+
+    // The field as text, which is both what is compared and what is shown side by side
+    pub fn text(&self, card: &SpellCard, localization: Localization) -> String {
+        match self {
+            CardField::Name       => card.spell_name.clone(),
+            CardField::CastTime   => cast_time_text(&card.cast_time),
+            CardField::Type       => localization.t(&card.spell_type.to_string()),
+            CardField::Level      => card.spell_level.to_string(),
+            CardField::Link       => card.link.clone(),
+            CardField::Source     => localization.source(&card.source),
+            CardField::Traits     => card.traits.join(", "),
+            CardField::Traditions => card.traditions.join(", "),
+            CardField::Overview   => card.overview.iter().map(|elem| format!("{}: {}", localization.t(&elem.to_string()), overview_text(elem, card.basic_save, localization))).collect::<Vec<String>>().join("\n"),
+            CardField::Effect     => card.spell_effect.clone(),
+            CardField::RollEffect => card.roll_effect.iter().map(|rr| format!("{}: {}", localization.t(&rr.to_string()), rr.effect())).collect::<Vec<String>>().join("\n"),
+            CardField::Heightened => card.heightened.iter().map(|h| format!("{}: {}", localization.heightened(h), h.effect())).collect::<Vec<String>>().join("\n"),
+        }
+    }
+
+    fn copy(&self, from: &SpellCard, to: &mut SpellCard) {
+        match self {
+            CardField::Name       => to.spell_name = from.spell_name.clone(),
+            CardField::CastTime   => to.cast_time = from.cast_time.clone(),
+            CardField::Type       => to.spell_type = from.spell_type,
+            CardField::Level      => to.spell_level = from.spell_level,
+            CardField::Link       => to.link = from.link.clone(),
+            CardField::Source     => to.source = from.source.clone(),
+            CardField::Traits     => to.traits = from.traits.clone(),
+            CardField::Traditions => to.traditions = from.traditions.clone(),
+            CardField::Overview   => {
+                to.overview = from.overview.clone();
+                to.basic_save = from.basic_save;
+            },
+            CardField::Effect     => to.spell_effect = from.spell_effect.clone(),
+            CardField::RollEffect => to.roll_effect = from.roll_effect.clone(),
+            CardField::Heightened => to.heightened = from.heightened.clone(),
+        }
+    }
+}
+
+// The basic save flag is shown with the defence, so it is merged along with the overview
+fn overview_text(elem: &SpellOverview, basic_save: bool, localization: Localization) -> String {
+    match elem {
+        SpellOverview::Defence(defence) if basic_save && *defence != Defence::ArmourClass => localization.tf("basic {}", &[&elem.value_string(localization)]),
+        _ => elem.value_string(localization),
+    }
+}
+
+#[derive(PartialEq, Eq, Clone, Copy)]
+pub enum Side {
+    Local,
+    Incoming,
+}
+
+#[derive(PartialEq, Clone)]
+pub struct FieldDifference {
+    pub field: CardField,
+    pub base: Option<String>,
+    pub local: String,
+    pub incoming: String,
+    pub suggested: Side,
+    // Changed on both sides since the base, or no base to tell which side changed
+    pub conflicting: bool,
+}
+
+pub fn field_differences(base: Option<&SpellCard>, local: &SpellCard, incoming: &SpellCard, localization: Localization) -> Vec<FieldDifference> {
+    CardField::ALL.iter().filter_map(|field| {
+        let local_text: String = field.text(local, localization);
+        let incoming_text: String = field.text(incoming, localization);
+        if local_text == incoming_text {
+            return None;
+        }
+        let base_text: Option<String> = base.map(|card| field.text(card, localization));
+        // A field only changed on the incoming side is taken from there
+        let suggested: Side = if base_text.as_ref() == Some(&local_text) { Side::Incoming } else { Side::Local };
+        let conflicting: bool = base_text.as_ref().is_none_or(|b| *b != local_text && *b != incoming_text);
+        Some(FieldDifference { field: *field, base: base_text, local: local_text, incoming: incoming_text, suggested, conflicting })
+    }).collect()
+}
+
+// The local card with the fields chosen from the incoming one, keeping the local id
+pub fn merge_fields(local: &SpellCard, incoming: &SpellCard, choices: &[(CardField, Side)]) -> SpellCard {
+    let mut merged: SpellCard = local.clone();
+    for (field, side) in choices {
+        if *side == Side::Incoming {
+            field.copy(incoming, &mut merged);
+        }
+    }
+    merged
+}
+
+#[derive(PartialEq, Clone)]
+pub struct Collision {
+    pub local_index: usize,
+    pub base: Option<SpellCard>,
+    pub incoming: SpellCard,
+}
+
+// Cards are the same by id, or by name when either was saved before cards had ids
+pub fn same_card(a: &SpellCard, b: &SpellCard) -> bool {
+    if !a.id.is_empty() && !b.id.is_empty() {
+        a.id == b.id
+    } else {
+        a.spell_name.trim().eq_ignore_ascii_case(b.spell_name.trim())
+    }
+}
+
+// Incoming cards matching a local card collide with it. Cards identical to their local match are dropped, the rest are new.
+// Only the first incoming card matching a local card is merged into it, later ones are returned as duplicates
+pub fn match_incoming(local: &[SpellCard], incoming: &[SpellCard], base: &[SpellCard], localization: Localization) -> (Vec<Collision>, Vec<SpellCard>, Vec<SpellCard>) {
+    let mut collisions: Vec<Collision> = Vec::new();
+    let mut new_cards: Vec<SpellCard> = Vec::new();
+    let mut duplicates: Vec<SpellCard> = Vec::new();
+    let mut matched: Vec<usize> = Vec::new();
+    for card in incoming {
+        match local.iter().position(|l| same_card(l, card)) {
+            Some(local_index) if matched.contains(&local_index) => duplicates.push(card.clone()),
+            Some(local_index) => {
+                matched.push(local_index);
+                if !field_differences(None, &local[local_index], card, localization).is_empty() {
+                    collisions.push(Collision {
+                        local_index,
+                        base: base.iter().find(|b| same_card(b, card)).cloned(),
+                        incoming: card.clone(),
+                    });
+                }
+            },
+            None => new_cards.push(card.clone()),
+        }
+    }
+    (collisions, new_cards, duplicates)
+}
+
+#[derive(PartialEq, Properties)]
+pub struct MergeViewProps {
+    pub local: Vec<SpellCard>,
+    pub incoming: Vec<SpellCard>,
+    // Versions both sides started from, when known
+    #[prop_or_default]
+    pub base: Vec<SpellCard>,
+    pub source: AttrValue,
+    pub on_merge: Callback<Vec<SpellCard>>,
+    pub on_cancel: Callback<MouseEvent>,
+}
+
+#[function_component]
+pub fn MergeView(props: &MergeViewProps) -> Html {
+    let MergeViewProps {
+        local,
+        incoming,
+        base,
+        source,
+        on_merge,
+        on_cancel
+    } = props;
+
+    let localization: Localization = use_context::<Localization>().unwrap_or_default();
+
+    let (collisions, new_cards, duplicates) = match_incoming(local, incoming, base, localization);
+    let differences: Vec<Vec<FieldDifference>> = collisions.iter()
+        .map(|collision| field_differences(collision.base.as_ref(), &local[collision.local_index], &collision.incoming, localization))
+        .collect();
+    let suggested: Vec<Vec<Side>> = differences.iter().map(|diffs| diffs.iter().map(|diff| diff.suggested).collect()).collect();
+    let choices: UseStateHandle<Vec<Vec<Side>>> = use_state(|| suggested.clone());
+    // Other cards to merge start over from the suggested sides
+    {
+        let choices: UseStateHandle<Vec<Vec<Side>>> = choices.clone();
+        use_effect_with((local.clone(), incoming.clone(), base.clone()), move |_| {
+            if *choices != suggested {
+                choices.set(suggested)
+            }
+        });
+    }
+    let has_base: bool = collisions.iter().any(|collision| collision.base.is_some());
+
+    // # Callback functions
+    let choose = |card_index: usize, field_index: usize, side: Side| {
+        let choices: UseStateHandle<Vec<Vec<Side>>> = choices.clone();
+
+        Callback::from(move |_: Event| {
+            let mut new_choices: Vec<Vec<Side>> = (*choices).clone();
+            if let Some(choice) = new_choices.get_mut(card_index).and_then(|card_choices| card_choices.get_mut(field_index)) {
+                *choice = side;
+                choices.set(new_choices)
+            }
+        })
+    };
+    let choose_all = |card_index: usize, side: Side| {
+        let choices: UseStateHandle<Vec<Vec<Side>>> = choices.clone();
+
+        Callback::from(move |_: MouseEvent| {
+            let mut new_choices: Vec<Vec<Side>> = (*choices).clone();
+            if let Some(card_choices) = new_choices.get_mut(card_index) {
+                card_choices.iter_mut().for_each(|choice| *choice = side);
+                choices.set(new_choices)
+            }
+        })
+    };
+    let merge: Callback<MouseEvent> = {
+        let choices: UseStateHandle<Vec<Vec<Side>>> = choices.clone();
+        let local: Vec<SpellCard> = local.clone();
+        let collisions: Vec<Collision> = collisions.clone();
+        let differences: Vec<Vec<FieldDifference>> = differences.clone();
+        let new_cards: Vec<SpellCard> = new_cards.clone();
+        let on_merge: Callback<Vec<SpellCard>> = on_merge.clone();
+
+        Callback::from(move |_| {
+            let mut merged: Vec<SpellCard> = local.clone();
+            for ((collision, diffs), card_choices) in collisions.iter().zip(differences.iter()).zip(choices.iter()) {
+                let field_choices: Vec<(CardField, Side)> = diffs.iter().zip(card_choices.iter()).map(|(diff, side)| (diff.field, *side)).collect();
+                merged[collision.local_index] = merge_fields(&local[collision.local_index], &collision.incoming, &field_choices);
+            }
+            merged.extend(new_cards.iter().cloned().map(SpellCard::with_id));
+            on_merge.emit(merged)
+        })
+    };
+
+    html! {
+        <div class="merge-view">
+            <p>
+                {localization.tf(
+                    "{} brings {} new card(s) and {} card(s) that differ from yours. Pick which version of each field to keep.",
+                    &[&localization.t(source), &new_cards.len(), &collisions.len()]
+                )}
+            </p>
+            { collisions.iter().zip(differences.iter()).enumerate().map(|(card_index, (collision, diffs))| {
+                let card_choices: Vec<Side> = choices.get(card_index).cloned().unwrap_or_default();
+                html!{
+                    <div class="merge-card" key={card_index}>
+                        <h3>{local[collision.local_index].spell_name.clone()}</h3>
+                        <button type="button" onclick={choose_all(card_index, Side::Local)}>{localization.t("Keep all of mine")}</button>
+                        <button type="button" onclick={choose_all(card_index, Side::Incoming)}>{localization.t("Take all incoming")}</button>
+                        <table>
+                            <tr>
+                                <th>{localization.t("Field")}</th>
+                                if has_base {
+                                    <th>{localization.t("Before")}</th>
+                                }
+                                <th>{localization.t("Mine")}</th>
+                                <th>{localization.t("Incoming")}</th>
+                            </tr>
+                            { diffs.iter().enumerate().map(|(field_index, diff)| {
+                                let chosen: Side = card_choices.get(field_index).copied().unwrap_or(diff.suggested);
+                                let group: String = format!("merge-{}-{}", card_index, field_index);
+                                html!{
+                                    <tr key={field_index} class={classes!(diff.conflicting.then_some("merge-conflict"))}>
+                                        <td>{localization.t(&diff.field.to_string())}</td>
+                                        if has_base {
+                                            <td class="merge-text">{diff.base.clone().unwrap_or_default()}</td>
+                                        }
+                                        <td class="merge-text">
+                                            <label>
+                                                <input
+                                                    type="radio"
+                                                    name={group.clone()}
+                                                    checked={chosen == Side::Local}
+                                                    onchange={choose(card_index, field_index, Side::Local)}
+                                                />
+                                                {diff.local.clone()}
+                                            </label>
+                                        </td>
+                                        <td class="merge-text">
+                                            <label>
+                                                <input
+                                                    type="radio"
+                                                    name={group}
+                                                    checked={chosen == Side::Incoming}
+                                                    onchange={choose(card_index, field_index, Side::Incoming)}
+                                                />
+                                                {diff.incoming.clone()}
+                                            </label>
+                                        </td>
+                                    </tr>
+                                }
+                            }).collect::<Html>() }
+                        </table>
+                    </div>
+                }
+            }).collect::<Html>() }
+            if !new_cards.is_empty() {
+                <p>{localization.tf("Added as new cards: {}", &[&new_cards.iter().map(|c| c.spell_name.clone()).collect::<Vec<String>>().join(", ")])}</p>
+            }
+            if !duplicates.is_empty() {
+                <p class="import-warnings">{localization.tf(
+                    "Left out, as an earlier incoming card already matches the same card of yours: {}",
+                    &[&duplicates.iter().map(|c| c.spell_name.clone()).collect::<Vec<String>>().join(", ")]
+                )}</p>
+            }
+            <button onclick={merge}>{localization.t("Merge")}</button>
+            <button onclick={on_cancel}>{localization.t("Cancel")}</button>
+        </div>
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app::localization::Units;
+    use crate::app::spell_card::SpellOverview;
+
+    #[test]
+    fn overview_text_follows_the_chosen_units() {
+        let card: SpellCard = SpellCard { overview: vec![SpellOverview::Range(30)], ..SpellCard::empty() };
+        let metres: Localization = Localization { units: Units::Metres, ..Default::default() };
+        assert_eq!(CardField::Overview.text(&card, Localization::default()), "Range: 30ft");
+        assert_eq!(CardField::Overview.text(&card, metres), "Range: 9 m");
+    }
+
+    fn card(id: &str, spell_name: &str, spell_effect: &str) -> SpellCard {
+        SpellCard { id: id.to_string(), spell_name: spell_name.to_string(), spell_effect: spell_effect.to_string(), ..SpellCard::empty() }
+    }
+
+    #[test]
+    fn incoming_cards_collide_with_their_local_match() {
+        let local: Vec<SpellCard> = vec![card("a", "Fireball", "Boom"), card("b", "Fear", "Boo")];
+        let base: Vec<SpellCard> = vec![card("a", "Fireball", "Small boom")];
+        let incoming: Vec<SpellCard> = vec![card("a", "Fireball", "Big boom"), card("b", "Fear", "Boo"), card("", "Heal", "Better")];
+        let (collisions, new_cards, duplicates) = match_incoming(&local, &incoming, &base, Localization::default());
+        assert_eq!(collisions.len(), 1);
+        assert_eq!(collisions[0].local_index, 0);
+        assert!(collisions[0].base == Some(base[0].clone()));
+        assert!(new_cards == vec![incoming[2].clone()]);
+        assert!(duplicates.is_empty());
+    }
+
+    #[test]
+    fn a_second_match_for_the_same_card_is_a_duplicate() {
+        let local: Vec<SpellCard> = vec![card("a", "Fireball", "Boom")];
+        let incoming: Vec<SpellCard> = vec![card("", "Fireball", "Big boom"), card("", "fireball ", "Bigger boom")];
+        let (collisions, new_cards, duplicates) = match_incoming(&local, &incoming, &[], Localization::default());
+        assert_eq!(collisions.len(), 1);
+        assert!(collisions[0].incoming == incoming[0]);
+        assert!(new_cards.is_empty());
+        assert!(duplicates == vec![incoming[1].clone()]);
+    }
+}
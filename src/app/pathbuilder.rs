@@ -0,0 +1,191 @@
+use serde_json::Value;
+use yew::platform::spawn_local;
+use yew::prelude::*;
+
+use super::catalog::{copy_from_catalog, fetch_catalog};
+use super::file_io::FileUpload;
+use super::localization::Localization;
+use super::spell_book::{CharacterProfile, SpellBookProps, SpellcastingEntry};
+use super::spell_card::SpellCard;
+use super::terminology::renamed_spell;
+
+pub struct PathbuilderImport {
+    pub book: SpellBookProps,
+    pub unmatched: Vec<String>,
+}
+
+// Reads a Pathbuilder 2e JSON export into a book named after the character, using the known cards for its spells
+// and copies from the catalog for the spells no book has yet
+pub fn import_pathbuilder_json(text: &str, known_spells: &[SpellCard], catalog: &[SpellCard], localization: Localization) -> Result<PathbuilderImport, String> {
+    let root: Value = serde_json::from_str::<Value>(text).map_err(|err| localization.tf("Not valid JSON: {}", &[&err]))?;
+    // The export wraps the character in {"success": true, "build": {...}}
+    let build: &Value = if root["build"].is_object() { &root["build"] } else { &root };
+    if !build["spellCasters"].is_array() && !build["focus"].is_object() {
+        return Err(localization.t("No spellcasting found, is this a Pathbuilder 2e export?"));
+    }
+
+    let mut spellcasting: Vec<SpellcastingEntry> = Vec::new();
+    for caster in build["spellCasters"].as_array().into_iter().flatten() {
+        let mut entry: SpellcastingEntry = SpellcastingEntry {
+            name: caster["name"].as_str().unwrap_or("Spellcasting").to_string(),
+            tradition: caster["magicTradition"].as_str().unwrap_or_default().to_string(),
+            casting_type: caster["spellcastingType"].as_str().unwrap_or_default().to_string(),
+            spells: Vec::new(),
+        };
+        for rank in caster["spells"].as_array().into_iter().flatten() {
+            let level: u8 = rank["spellLevel"].as_u64().unwrap_or(0) as u8;
+            add_spell_names(&mut entry, level, &rank["list"]);
+        }
+        spellcasting.push(entry);
+    }
+    // Focus spells are grouped by tradition and then by key ability
+    if let Some(focus) = build["focus"].as_object() {
+        for (tradition, abilities) in focus.iter() {
+            for (_, pool) in abilities.as_object().into_iter().flatten() {
+                let mut entry: SpellcastingEntry = SpellcastingEntry {
+                    name: localization.t("Focus spells"),
+                    tradition: tradition.clone(),
+                    casting_type: "focus".to_string(),
+                    spells: Vec::new(),
+                };
+                add_spell_names(&mut entry, 0, &pool["focusCantrips"]);
+                add_spell_names(&mut entry, 1, &pool["focusSpells"]);
+                if !entry.spells.is_empty() {
+                    spellcasting.push(entry);
+                }
+            }
+        }
+    }
+
+    let mut spells: Vec<SpellCard> = Vec::new();
+    let mut unmatched: Vec<String> = Vec::new();
+    for name in spellcasting.iter().flat_map(|entry| entry.spells.iter().flat_map(|(_, names)| names.iter())) {
+        let card: Option<SpellCard> = find_spell(name, known_spells).cloned()
+            .or_else(|| find_spell(name, catalog).map(copy_from_catalog));
+        match card {
+            Some(card) if !spells.iter().any(|s| s.spell_name == card.spell_name) => spells.push(card),
+            Some(_) => (),
+            None if !unmatched.contains(name) => unmatched.push(name.clone()),
+            None => (),
+        }
+    }
+
+    let character: CharacterProfile = CharacterProfile {
+        name: build["name"].as_str().unwrap_or("Unnamed character").to_string(),
+        class: build["class"].as_str().unwrap_or_default().to_string(),
+        level: build["level"].as_u64().unwrap_or(1) as u8,
+        spellcasting,
+        uncommon_unlocked: false,
+        rare_unlocked: false,
+    };
+    Ok(PathbuilderImport {
+        book: SpellBookProps { spells, name: character.name.clone(), character: Some(character) },
+        unmatched,
+    })
+}
+
+fn add_spell_names(entry: &mut SpellcastingEntry, level: u8, list: &Value) {
+    let names: Vec<String> = list.as_array().into_iter().flatten()
+        .filter_map(|name| name.as_str().map(|n| n.to_string()))
+        .collect();
+    if !names.is_empty() {
+        entry.spells.push((level, names));
+    }
+}
+
+// Compares names ignoring case, punctuation and spacing, so "Force Bolt" matches "force-bolt"
+pub fn normalise_spell_name(name: &str) -> String {
+    name.chars().filter(|c| c.is_alphanumeric()).flat_map(|c| c.to_lowercase()).collect()
+}
+
+pub fn find_spell<'a>(name: &str, known_spells: &'a [SpellCard]) -> Option<&'a SpellCard> {
+    let normalised: String = normalise_spell_name(name);
+    known_spells.iter().find(|card| normalise_spell_name(&card.spell_name) == normalised)
+        // Builds from before the remaster may still use the old names
+        .or_else(|| known_spells.iter().find(|card| renamed_spell(&card.spell_name).is_some_and(|renamed| normalise_spell_name(renamed) == normalised)))
+}
+
+#[derive(PartialEq, Properties)]
+pub struct PathbuilderImporterProps {
+    pub known_spells: Vec<SpellCard>,
+    pub on_import: Callback<SpellBookProps>,
+}
+
+#[function_component]
+pub fn PathbuilderImporter(props: &PathbuilderImporterProps) -> Html {
+    let PathbuilderImporterProps {
+        known_spells,
+        on_import
+    } = props;
+
+    let localization: Localization = use_context::<Localization>().unwrap_or_default();
+
+    let import: UseStateHandle<Option<Result<PathbuilderImport, String>>> = use_state(|| None);
+    // Spells no book has yet are looked up in the catalog, an import without it only matches the books
+    let catalog: UseStateHandle<Vec<SpellCard>> = use_state(Vec::new);
+    {
+        let catalog: UseStateHandle<Vec<SpellCard>> = catalog.clone();
+        use_effect_with((), move |_| {
+            spawn_local(async move {
+                if let Ok(cards) = fetch_catalog(localization).await {
+                    catalog.set(cards)
+                }
+            });
+        });
+    }
+
+    // # Callback functions
+    let file_load: Callback<(String, String)> = {
+        let import: UseStateHandle<Option<Result<PathbuilderImport, String>>> = import.clone();
+        let known_spells: Vec<SpellCard> = known_spells.clone();
+        let catalog: UseStateHandle<Vec<SpellCard>> = catalog.clone();
+
+        Callback::from(move |(_, content): (String, String)| {
+            import.set(Some(import_pathbuilder_json(&content, &known_spells, &catalog, localization)))
+        })
+    };
+    let confirm_import: Callback<MouseEvent> = {
+        let import: UseStateHandle<Option<Result<PathbuilderImport, String>>> = import.clone();
+        let on_import: Callback<SpellBookProps> = on_import.clone();
+
+        Callback::from(move |_| {
+            if let Some(Ok(result)) = &*import {
+                on_import.emit(result.book.clone());
+            }
+            import.set(None)
+        })
+    };
+
+    html! {
+        <div class="importer">
+            <h2>{"Pathbuilder 2e"}</h2>
+            <FileUpload
+                label={localization.t("Character export (.json):")}
+                on_load={file_load}
+            />
+            { match &*import {
+                None => html!{},
+                Some(Err(err)) => html!{<p class="import-warnings">{err}</p>},
+                Some(Ok(result)) => html!{
+                    <>
+                        if let Some(character) = &result.book.character {
+                            {character.to_html(localization)}
+                        }
+                        <p>{localization.tf("{} spell(s) matched your cards or the catalog", &[&result.book.spells.len()])}</p>
+                        if !result.unmatched.is_empty() {
+                            <p>{localization.t("No card found for these spells, add them by hand:")}</p>
+                            <ul class="import-warnings">
+                                { result.unmatched.iter().map(|name| {
+                                    html!{<li key={name.clone()}>{name}</li>}
+                                }).collect::<Html>() }
+                            </ul>
+                        }
+                        <button onclick={confirm_import}>
+                            {localization.tf("Create spellbook \"{}\"", &[&result.book.display_name()])}
+                        </button>
+                    </>
+                },
+            } }
+        </div>
+    }
+}
@@ -0,0 +1,300 @@
+use std::fmt::Display;
+use wasm_bindgen::JsCast;
+use web_sys::{window, Document, Element, EventTarget, HtmlInputElement, HtmlSelectElement};
+use yew::prelude::*;
+
+use super::localization::Localization;
+use super::spell_card::SpellCard;
+
+// Smallest font scale tried before an overflowing card is split onto a continuation back
+const MIN_SCALE: f64 = 0.75;
+const SCALE_STEP: f64 = 0.05;
+const PAGE_MARGIN_MM: f64 = 8.0;
+const CARD_GAP_MM: f64 = 6.0;
+
+#[derive(PartialEq, Eq, Clone, Copy)]
+pub enum Paper {
+    A4,
+    Letter,
+}
+
+impl Display for Paper {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Paper::A4     => write!(f, "A4"),
+            Paper::Letter => write!(f, "Letter"),
+        }
+    }
+}
+
+impl Paper {
+    pub fn size_mm(&self) -> (f64, f64) {
+        match self {
+            Paper::A4     => (210.0, 297.0),
+            Paper::Letter => (215.9, 279.4),
+        }
+    }
+}
+
+#[derive(PartialEq, Eq, Clone, Copy)]
+pub enum CardSize {
+    Poker,
+    Tarot,
+}
+
+impl Display for CardSize {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CardSize::Poker => write!(f, "Poker (63 x 88 mm)"),
+            CardSize::Tarot => write!(f, "Tarot (70 x 120 mm)"),
+        }
+    }
+}
+
+impl CardSize {
+    pub fn size_mm(&self) -> (f64, f64) {
+        match self {
+            CardSize::Poker => (63.0, 88.0),
+            CardSize::Tarot => (70.0, 120.0),
+        }
+    }
+}
+
+// Number of columns and rows of cards fitting on one page
+pub fn grid_for(paper: Paper, card_size: CardSize) -> (usize, usize) {
+    let (paper_width, paper_height) = paper.size_mm();
+    let (card_width, card_height) = card_size.size_mm();
+    let fit = |available: f64, card: f64| -> usize {
+        (((available - 2.0 * PAGE_MARGIN_MM + CARD_GAP_MM) / (card + CARD_GAP_MM)).floor() as usize).max(1)
+    };
+    (fit(paper_width, card_width), fit(paper_height, card_height))
+}
+
+#[derive(PartialEq, Clone, Copy)]
+struct CardFit {
+    scale: f64,
+    split: bool,
+}
+
+const FITS_AS_IS: CardFit = CardFit { scale: 1.0, split: false };
+
+// Splits a card too long for one side: the degrees of success and heightening go on the back,
+// or the second half of the effect when there are none
+pub fn split_card(card: &SpellCard) -> (SpellCard, SpellCard) {
+    let mut front: SpellCard = card.clone();
+    let mut back: SpellCard = SpellCard::empty();
+    back.spell_name = format!("{} (cont.)", card.spell_name);
+    back.cast_time = card.cast_time.clone();
+    back.spell_type = card.spell_type;
+    back.spell_level = card.spell_level;
+    back.link = card.link.clone();
+    back.source = card.source.clone();
+    if card.roll_effect.is_empty() && card.heightened.is_empty() {
+        let paragraphs: Vec<&str> = card.spell_effect.split('\n').collect();
+        let half: usize = paragraphs.len().div_ceil(2);
+        front.spell_effect = paragraphs[..half].join("\n");
+        back.spell_effect = paragraphs[half..].join("\n");
+    } else {
+        back.roll_effect = std::mem::take(&mut front.roll_effect);
+        back.heightened = std::mem::take(&mut front.heightened);
+    }
+    (front, back)
+}
+
+#[derive(PartialEq, Properties)]
+pub struct PrintSheetProps {
+    pub spells: Vec<SpellCard>,
+}
+
+#[function_component]
+pub fn PrintSheet(props: &PrintSheetProps) -> Html {
+    let PrintSheetProps {
+        spells
+    } = props;
+
+    let localization: Localization = use_context::<Localization>().unwrap_or_default();
+
+    let selected: UseStateHandle<Vec<bool>> = use_state(|| vec![true; spells.len()]);
+    let paper: UseStateHandle<Paper> = use_state(|| Paper::A4);
+    let card_size: UseStateHandle<CardSize> = use_state(|| CardSize::Poker);
+    let fits: UseStateHandle<Vec<CardFit>> = use_state(Vec::new);
+
+    let printed: Vec<SpellCard> = spells.iter().zip(selected.iter())
+        .filter(|(_, is_selected)| **is_selected)
+        .map(|(card, _)| card.clone())
+        .collect();
+
+    // Start measuring again whenever the cards or their size change
+    {
+        let fits: UseStateHandle<Vec<CardFit>> = fits.clone();
+        let printed_count: usize = printed.len();
+        use_effect_with(((*selected).clone(), *card_size), move |_| {
+            fits.set(vec![FITS_AS_IS; printed_count]);
+        });
+    }
+    // Shrink the font of each overflowing card step by step, then split it
+    {
+        let fits: UseStateHandle<Vec<CardFit>> = fits.clone();
+        use_effect_with((*fits).clone(), move |current: &Vec<CardFit>| {
+            let document: Option<Document> = window().and_then(|w| w.document());
+            let mut next: Vec<CardFit> = current.clone();
+            for (index, fit) in next.iter_mut().enumerate().filter(|(_, fit)| !fit.split) {
+                let element: Option<Element> = document.as_ref()
+                    .and_then(|d| d.get_element_by_id(&format!("print-card-{}", index)))
+                    .and_then(|slot| slot.first_element_child());
+                if let Some(element) = element {
+                    if element.scroll_height() > element.client_height() {
+                        if fit.scale - SCALE_STEP >= MIN_SCALE {
+                            fit.scale -= SCALE_STEP;
+                        } else {
+                            *fit = CardFit { scale: 1.0, split: true };
+                        }
+                    }
+                }
+            }
+            if next != *current {
+                fits.set(next);
+            }
+        });
+    }
+
+    // # Callback functions
+    let selection_change = |index: usize| {
+        let selected: UseStateHandle<Vec<bool>> = selected.clone();
+
+        Callback::from(move |e: Event| {
+            let target: Option<EventTarget> = e.target();
+
+            let input: Option<HtmlInputElement> = target.and_then(|t: EventTarget| t.dyn_into::<HtmlInputElement>().ok());
+            if let Some(input) = input {
+                let mut new_selected: Vec<bool> = (*selected).clone();
+                new_selected[index] = input.checked();
+                selected.set(new_selected)
+            }
+        })
+    };
+    let paper_change: Callback<Event> = {
+        let paper: UseStateHandle<Paper> = paper.clone();
+
+        Callback::from(move |e: Event| {
+            let target: Option<EventTarget> = e.target();
+
+            let input: Option<HtmlSelectElement> = target.and_then(|t: EventTarget| t.dyn_into::<HtmlSelectElement>().ok());
+            if let Some(input) = input {
+                paper.set(if input.value() == "letter" { Paper::Letter } else { Paper::A4 })
+            }
+        })
+    };
+    let card_size_change: Callback<Event> = {
+        let card_size: UseStateHandle<CardSize> = card_size.clone();
+
+        Callback::from(move |e: Event| {
+            let target: Option<EventTarget> = e.target();
+
+            let input: Option<HtmlSelectElement> = target.and_then(|t: EventTarget| t.dyn_into::<HtmlSelectElement>().ok());
+            if let Some(input) = input {
+                card_size.set(if input.value() == "tarot" { CardSize::Tarot } else { CardSize::Poker })
+            }
+        })
+    };
+    let print: Callback<MouseEvent> = Callback::from(|_| {
+        if let Some(window) = window() {
+            _ = window.print();
+        }
+    });
+
+    // # Layout
+    let (columns, rows) = grid_for(*paper, *card_size);
+    let per_page: usize = columns * rows;
+    let (paper_width, paper_height) = paper.size_mm();
+    let (card_width, card_height) = card_size.size_mm();
+    let page_style: String = format!(
+        "width: {}mm; height: {}mm; padding: {}mm; grid-template-columns: repeat({}, {}mm); grid-auto-rows: {}mm; gap: {}mm;",
+        paper_width, paper_height, PAGE_MARGIN_MM, columns, card_width, card_height, CARD_GAP_MM
+    );
+    let page_rule: String = format!("@page {{ size: {}mm {}mm; margin: 0; }}", paper_width, paper_height);
+    let card_style = |scale: f64| format!(
+        "--card-width: {}mm; --card-height: {}mm; --card-scale: {};", card_width, card_height, scale
+    );
+    let slot = |id: Option<String>, card: Option<&SpellCard>, scale: f64| html!{
+        <div class="print-slot" id={id} style={card_style(scale)}>
+            if let Some(card) = card {
+                {card.to_html()}
+                <span class="cut-mark cut-mark-tl" />
+                <span class="cut-mark cut-mark-tr" />
+                <span class="cut-mark cut-mark-bl" />
+                <span class="cut-mark cut-mark-br" />
+            }
+        </div>
+    };
+
+    let pages: Vec<Html> = printed.chunks(per_page).enumerate().flat_map(|(page_index, page_cards)| {
+        let mut continuations: Vec<Option<SpellCard>> = vec![None; per_page];
+        let fronts: Html = page_cards.iter().enumerate().map(|(slot_index, card)| {
+            let index: usize = page_index * per_page + slot_index;
+            let fit: CardFit = fits.get(index).copied().unwrap_or(FITS_AS_IS);
+            if fit.split {
+                let (front, back) = split_card(card);
+                // Mirror the columns so the back lines up with its front when printed double-sided
+                let row: usize = slot_index / columns;
+                let column: usize = slot_index % columns;
+                continuations[row * columns + (columns - 1 - column)] = Some(back);
+                slot(Some(format!("print-card-{}", index)), Some(&front), MIN_SCALE)
+            } else {
+                slot(Some(format!("print-card-{}", index)), Some(card), fit.scale)
+            }
+        }).collect::<Html>();
+        let mut page: Vec<Html> = vec![html!{
+            <div class="print-page" style={page_style.clone()}>{fronts}</div>
+        }];
+        if continuations.iter().any(|c| c.is_some()) {
+            let used_rows: usize = page_cards.len().div_ceil(columns);
+            page.push(html!{
+                <div class="print-page" style={page_style.clone()}>
+                    { continuations.iter().take(used_rows * columns).map(|back| slot(None, back.as_ref(), MIN_SCALE)).collect::<Html>() }
+                </div>
+            });
+        }
+        page
+    }).collect();
+
+    html! {
+        <div class="print-sheet">
+            <style>{page_rule}</style>
+            <div class="print-controls no-print">
+                <label>
+                    {localization.t("Paper:")}
+                    <select onchange={paper_change}>
+                        <option value={"a4"} selected={*paper == Paper::A4}>{Paper::A4.to_string()}</option>
+                        <option value={"letter"} selected={*paper == Paper::Letter}>{localization.t(&Paper::Letter.to_string())}</option>
+                    </select>
+                </label>
+                <label>
+                    {localization.t("Card size:")}
+                    <select onchange={card_size_change}>
+                        <option value={"poker"} selected={*card_size == CardSize::Poker}>{localization.t(&CardSize::Poker.to_string())}</option>
+                        <option value={"tarot"} selected={*card_size == CardSize::Tarot}>{localization.t(&CardSize::Tarot.to_string())}</option>
+                    </select>
+                </label>
+                <button onclick={print}>{localization.t("Print")}</button>
+                <ul class="print-selection">
+                    { spells.iter().enumerate().map(|(index, card)| {
+                        html!{
+                            <li key={index}>
+                                <label>
+                                    <input
+                                        type="checkbox"
+                                        checked={selected.get(index).copied().unwrap_or(false)}
+                                        onchange={selection_change(index)}
+                                    />
+                                    {card.spell_name.clone()}
+                                </label>
+                            </li>
+                        }
+                    }).collect::<Html>() }
+                </ul>
+            </div>
+            { pages.into_iter().collect::<Html>() }
+        </div>
+    }
+}
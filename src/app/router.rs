@@ -0,0 +1,85 @@
+use web_sys::window;
+
+use super::filters::SpellFilter;
+use super::share_link::SHARE_PREFIX;
+
+// Routes live in the URL fragment so the app works from a static host such as GitHub Pages
+#[derive(PartialEq, Clone)]
+pub enum Route {
+    Overview(SpellFilter),
+    NewCard,
+    // Cards are addressed by their id, which survives reordering and renames
+    CardDetail(String),
+    EditCard(String),
+    Settings,
+    Backups,
+    Merge,
+    Encounter,
+    Import,
+    Catalog(SpellFilter),
+    Print,
+    Share(String),
+}
+
+impl Route {
+    pub fn from_hash(hash: &str) -> Route {
+        if let Some(payload) = hash.strip_prefix(SHARE_PREFIX) {
+            return Route::Share(payload.to_string());
+        }
+        let path: &str = hash.trim_start_matches('#');
+        let (path, query) = path.split_once('?').unwrap_or((path, ""));
+        let segments: Vec<&str> = path.split('/').filter(|s| !s.is_empty()).collect();
+        match segments.as_slice() {
+            ["new"]                 => Route::NewCard,
+            ["card", id]            => Route::CardDetail(id.to_string()),
+            ["card", id, "edit"]    => Route::EditCard(id.to_string()),
+            ["settings"]            => Route::Settings,
+            ["backups"]             => Route::Backups,
+            ["merge"]               => Route::Merge,
+            ["encounter"]           => Route::Encounter,
+            ["import"]              => Route::Import,
+            ["catalog"]             => Route::Catalog(SpellFilter::from_query(query)),
+            ["print"]               => Route::Print,
+            _                       => Route::Overview(SpellFilter::from_query(query)),
+        }
+    }
+
+    pub fn to_hash(&self) -> String {
+        match self {
+            Route::Overview(filter) if filter.is_empty() => "#/".to_string(),
+            Route::Overview(filter)  => format!("#/?{}", filter.to_query()),
+            Route::NewCard           => "#/new".to_string(),
+            Route::CardDetail(id)    => format!("#/card/{}", id),
+            Route::EditCard(id)      => format!("#/card/{}/edit", id),
+            Route::Settings          => "#/settings".to_string(),
+            Route::Backups           => "#/backups".to_string(),
+            Route::Merge             => "#/merge".to_string(),
+            Route::Encounter         => "#/encounter".to_string(),
+            Route::Import            => "#/import".to_string(),
+            Route::Catalog(filter) if filter.is_empty() => "#/catalog".to_string(),
+            Route::Catalog(filter)   => format!("#/catalog?{}", filter.to_query()),
+            Route::Print             => "#/print".to_string(),
+            Route::Share(payload)    => format!("{}{}", SHARE_PREFIX, payload),
+        }
+    }
+
+    pub fn current() -> Route {
+        let hash: String = window()
+            .and_then(|w| w.location().hash().ok())
+            .unwrap_or_default();
+        Route::from_hash(&hash)
+    }
+}
+
+impl Default for Route {
+    fn default() -> Route {
+        Route::Overview(SpellFilter::default())
+    }
+}
+
+// Changing the fragment fires `hashchange`, which the app listens to in order to render the new route
+pub fn navigate(route: &Route) {
+    if let Some(window) = window() {
+        _ = window.location().set_hash(&route.to_hash());
+    }
+}
@@ -0,0 +1,116 @@
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+use miniz_oxide::deflate::compress_to_vec;
+use miniz_oxide::inflate::decompress_to_vec_with_limit;
+use web_sys::window;
+use yew::prelude::*;
+
+use super::localization::Localization;
+use super::spell_card::SpellCard;
+use super::text_export::copy_to_clipboard;
+
+pub const SHARE_PREFIX: &str = "#/share/";
+// Links longer than this get cut off by some browsers, chat apps and URL shorteners
+pub const SAFE_LINK_LENGTH: usize = 2000;
+// Guards against links that inflate to something unreasonably large
+const MAX_SHARED_BYTES: usize = 4 * 1024 * 1024;
+
+// Cards are stored as deflated JSON in URL-safe base64
+pub fn encode_cards(cards: &[SpellCard]) -> String {
+    let json: String = serde_json::to_string(cards).unwrap_or_default();
+    URL_SAFE_NO_PAD.encode(compress_to_vec(json.as_bytes(), 9))
+}
+
+pub fn decode_cards(payload: &str, localization: Localization) -> Result<Vec<SpellCard>, String> {
+    let compressed: Vec<u8> = URL_SAFE_NO_PAD.decode(payload.trim()).map_err(|_| localization.t("The link is damaged"))?;
+    let json: Vec<u8> = decompress_to_vec_with_limit(&compressed, MAX_SHARED_BYTES).map_err(|_| localization.t("The link is damaged"))?;
+    serde_json::from_slice::<Vec<SpellCard>>(&json).map_err(|_| localization.t("The link does not contain spell cards"))
+}
+
+pub fn share_url(cards: &[SpellCard]) -> String {
+    let base: String = window()
+        .and_then(|w| w.location().href().ok())
+        .map(|href| href.split('#').next().unwrap_or_default().to_string())
+        .unwrap_or_default();
+    format!("{}{}{}", base, SHARE_PREFIX, encode_cards(cards))
+}
+
+#[derive(PartialEq, Properties)]
+pub struct ShareLinkProps {
+    pub cards: Vec<SpellCard>,
+    // "Share link" in the chosen language when not given
+    #[prop_or_default]
+    pub label: Option<AttrValue>,
+}
+
+#[function_component]
+pub fn ShareLink(props: &ShareLinkProps) -> Html {
+    let ShareLinkProps {
+        cards,
+        label
+    } = props;
+
+    let localization: Localization = use_context::<Localization>().unwrap_or_default();
+
+    let link: UseStateHandle<Option<String>> = use_state(|| None);
+
+    let create_link: Callback<MouseEvent> = {
+        let link: UseStateHandle<Option<String>> = link.clone();
+        let cards: Vec<SpellCard> = cards.clone();
+
+        Callback::from(move |_| {
+            let url: String = share_url(&cards);
+            copy_to_clipboard(&url);
+            link.set(Some(url))
+        })
+    };
+
+    html! {
+        <div class="share-link">
+            <button onclick={create_link}>{label.as_ref().map(|label| label.to_string()).unwrap_or_else(|| localization.t("Share link"))}</button>
+            if let Some(url) = &*link {
+                <input type="text" readonly={true} value={url.clone()} />
+                <span>{localization.t("Copied to the clipboard")}</span>
+                if url.len() > SAFE_LINK_LENGTH {
+                    <p class="import-warnings">
+                        {localization.tf(
+                            "This link is {} characters long, links over {} characters may be cut off when shared. Consider sharing fewer cards or exporting a file instead.",
+                            &[&url.len(), &SAFE_LINK_LENGTH]
+                        )}
+                    </p>
+                }
+            }
+        </div>
+    }
+}
+
+#[derive(PartialEq, Properties)]
+pub struct SharedCardsProps {
+    pub cards: Vec<SpellCard>,
+    pub on_add: Callback<Vec<SpellCard>>,
+}
+
+#[function_component]
+pub fn SharedCards(props: &SharedCardsProps) -> Html {
+    let SharedCardsProps {
+        cards,
+        on_add
+    } = props;
+
+    let localization: Localization = use_context::<Localization>().unwrap_or_default();
+
+    let add_cards: Callback<MouseEvent> = {
+        let cards: Vec<SpellCard> = cards.clone();
+        let on_add: Callback<Vec<SpellCard>> = on_add.clone();
+        Callback::from(move |_| on_add.emit(cards.clone()))
+    };
+
+    html! {
+        <div class="shared-cards">
+            <div class="spell-book">
+                { cards.iter().map(|card| card.to_html()).collect::<Html>() }
+            </div>
+            <button onclick={add_cards}>{localization.t("Add to my spellbook")}</button>
+        </div>
+    }
+}
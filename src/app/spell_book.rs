@@ -1,22 +1,174 @@
-use super::spell_card::SpellCard;
+use std::collections::HashSet;
+use super::area_grid::AreaGrid;
+use super::card_image::CardImageExport;
+use super::degree_of_success::DegreeOfSuccessCalculator;
+use super::filters::SpellFilter;
+use super::localization::Localization;
+use super::router::Route;
+use super::share_link::ShareLink;
+use super::text_export::CopyCardText;
+use super::spell_card::{generate_id, SpellCard, SpellOverview};
 use serde::{Deserialize, Serialize};
 use yew::prelude::*;
 
-#[derive(PartialEq, Properties, Clone, Serialize, Deserialize)]
+#[derive(PartialEq, Eq, Clone, Serialize, Deserialize)]
+pub struct SpellcastingEntry {
+    pub name: String,
+    pub tradition: String,
+    pub casting_type: String,
+    // Spell names by rank, cantrips at rank 0
+    pub spells: Vec<(u8, Vec<String>)>,
+}
+
+#[derive(PartialEq, Eq, Clone, Serialize, Deserialize)]
+pub struct CharacterProfile {
+    pub name: String,
+    pub class: String,
+    pub level: u8,
+    pub spellcasting: Vec<SpellcastingEntry>,
+    // Rarities the GM has made available to this character
+    #[serde(default)]
+    pub uncommon_unlocked: bool,
+    #[serde(default)]
+    pub rare_unlocked: bool,
+}
+
+impl CharacterProfile {
+    pub fn traditions(&self) -> Vec<String> {
+        let mut traditions: Vec<String> = Vec::new();
+        for entry in self.spellcasting.iter().filter(|entry| !entry.tradition.is_empty()) {
+            if !traditions.iter().any(|t| t.eq_ignore_ascii_case(&entry.tradition)) {
+                traditions.push(entry.tradition.clone());
+            }
+        }
+        traditions
+    }
+
+    // Spell ranks open up every other level: rank 1 at level 1, rank 2 at level 3 and so on
+    pub fn max_spell_rank(&self) -> u8 {
+        self.level.div_ceil(2).max(1)
+    }
+
+    pub fn to_html(&self, localization: Localization) -> Html {
+        html!{
+            <div class="character-profile">
+                <b>{localization.tf("{}, level {} {}", &[&self.name, &self.level, &self.class])}</b>
+                { self.spellcasting.iter().map(|entry| {
+                    html!{
+                        <div key={entry.name.clone()}>
+                            {format!("{}: {} {}", entry.name, entry.tradition, entry.casting_type)}
+                        </div>
+                    }
+                }).collect::<Html>() }
+            </div>
+        }
+    }
+}
+
+#[derive(PartialEq, Properties, Clone, Default, Serialize, Deserialize)]
 pub struct SpellBookProps {
-    pub spells: Vec<SpellCard>
+    pub spells: Vec<SpellCard>,
+    #[serde(default)]
+    #[prop_or_default]
+    pub name: String,
+    #[serde(default)]
+    #[prop_or_default]
+    pub character: Option<CharacterProfile>,
+}
+
+impl SpellBookProps {
+    pub fn display_name(&self) -> String {
+        if self.name.is_empty() { "My spellbook".to_string() } else { self.name.clone() }
+    }
+}
+
+// Gives an id to every card without one, or sharing one with an earlier card, and tells whether any changed
+pub fn assign_card_ids(mut books: Vec<SpellBookProps>) -> (Vec<SpellBookProps>, bool) {
+    let mut seen: HashSet<String> = HashSet::new();
+    let mut changed: bool = false;
+    for card in books.iter_mut().flat_map(|book| book.spells.iter_mut()) {
+        if card.id.is_empty() || seen.contains(&card.id) {
+            card.id = generate_id();
+            changed = true;
+        }
+        seen.insert(card.id.clone());
+    }
+    (books, changed)
+}
+
+#[derive(PartialEq, Properties)]
+pub struct SpellBookViewProps {
+    pub spells: Vec<SpellCard>,
+    #[prop_or_default]
+    pub filter: SpellFilter,
 }
 
 #[function_component]
-pub fn SpellBook(props: &SpellBookProps) -> Html {
-    let SpellBookProps {
-        spells
+pub fn SpellBook(props: &SpellBookViewProps) -> Html {
+    let SpellBookViewProps {
+        spells,
+        filter
     } = props;
+
+    let localization: Localization = use_context::<Localization>().unwrap_or_default();
+
+    let shown: Vec<&SpellCard> = spells.iter().filter(|card| filter.matches(card)).collect();
+
     html! {
         <div class="spell-book">
-            {spells.iter().map(|spell_card| {
-                spell_card.to_html()
+            if shown.is_empty() {
+                <p>{localization.t("No spells match the current filters.")}</p>
+            }
+            {shown.into_iter().map(|spell_card| {
+                html!{<SpellBookEntry key={spell_card.id.clone()} card={spell_card.clone()} links={true} />}
             }).collect::<Html>()}
         </div>
     }
 }
+
+#[derive(PartialEq, Properties)]
+pub struct SpellBookEntryProps {
+    pub card: SpellCard,
+    // Shows links to the card's own pages
+    #[prop_or_default]
+    pub links: bool,
+}
+
+#[function_component]
+pub fn SpellBookEntry(props: &SpellBookEntryProps) -> Html {
+    let SpellBookEntryProps {
+        card,
+        links
+    } = props;
+
+    let localization: Localization = use_context::<Localization>().unwrap_or_default();
+
+    let card_ref: NodeRef = use_node_ref();
+
+    html! {
+        <div class="spell-book-entry">
+            <div ref={card_ref.clone()}>
+                {card.to_html()}
+            </div>
+            if *links {
+                <div class="card-links">
+                    <a href={Route::CardDetail(card.id.clone()).to_hash()}>{localization.t("Details")}</a>
+                    <a href={Route::EditCard(card.id.clone()).to_hash()}>{localization.t("Edit")}</a>
+                </div>
+            }
+            <CardImageExport card_ref={card_ref} spell_name={card.spell_name.clone()} />
+            <CopyCardText card={card.clone()} />
+            <ShareLink cards={vec![card.clone()]} />
+            if let Some(SpellOverview::Defence(defence)) = card.get_overview_element("Defence") {
+                <DegreeOfSuccessCalculator
+                    defence={defence}
+                    basic_save={card.basic_save}
+                    roll_effect={card.roll_effect.clone()}
+                />
+            }
+            if let Some(SpellOverview::Area(area)) = card.get_overview_element("Area") {
+                <AreaGrid area={area} />
+            }
+        </div>
+    }
+}
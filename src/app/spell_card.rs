@@ -1,7 +1,10 @@
 use std::fmt::Display;
 use serde::{Deserialize, Serialize};
 
-use yew::{function_component, html, Html, Properties};
+use yew::{function_component, html, use_context, Html, Properties};
+
+use super::localization::{Locale, Localization};
+use super::terminology::Terminology;
 
 #[derive(PartialEq, Eq, Clone, Serialize, Deserialize)]
 pub enum CastTime {
@@ -80,15 +83,20 @@ impl Display for SpellOverview {
 }
 
 impl SpellOverview {
-    pub fn to_html(&self) -> Html {
+    // Distances follow the reader's units
+    pub fn value_string(&self, localization: Localization) -> String {
+        match self {
+            SpellOverview::Range(range)       => localization.distance(*range),
+            SpellOverview::Area(area)         => localization.area(area),
+            SpellOverview::Targets(targets)   => targets.clone(),
+            SpellOverview::Defence(defence)   => defence.to_string(),
+            SpellOverview::Duration(duration) => duration.clone(),
+        }
+    }
+
+    pub fn to_html(&self, terminology: Terminology, localization: Localization, basic_save: bool) -> Html {
         html!{<>
-            <b>{self.to_string()}{": "}</b>{match self {
-                SpellOverview::Range(range)       => format!("{}ft",range),
-                SpellOverview::Area(area)         => area.to_string(),
-                SpellOverview::Targets(targets)   => targets.clone(),
-                SpellOverview::Defence(defence)   => defence.to_string(),
-                SpellOverview::Duration(duration) => duration.clone(),
-            }}
+            <b>{localization.overview_label(terminology, self)}{": "}</b>{localization.overview_value(terminology, self, basic_save)}
         </>}
     }
 }
@@ -101,18 +109,6 @@ pub enum Area {
     Line(u8, Option<u8>),
 }
 
-impl Display for Area {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        match self {
-            Area::Burst(aoe)                     => write!(f, "{}ft burst", aoe),
-            Area::Cone(aoe)                      => write!(f, "{}ft cone", aoe),
-            Area::Emanation(aoe)                 => write!(f, "{}ft emanation", aoe),
-            Area::Line(length, None)             => write!(f, "{}ft line", length),
-            Area::Line(length, Some(width)) => write!(f, "{}ft long and {}ft wide line", length, width),
-        }
-    }
-}
-
 impl Area {
     pub fn get_aoe_val(&self) -> (u8, Option<u8>) {
         match self {
@@ -163,13 +159,17 @@ impl Display for RollResult {
 }
 
 impl RollResult {
-    pub fn to_html(&self) -> Html {
-        html!{<><b>{self.to_string()}{": "}</b>{match self {
+    pub fn effect(&self) -> &str {
+        match self {
             RollResult::CriticalSuccess(eff) |
             RollResult::Success(eff) |
             RollResult::Failure(eff) |
             RollResult::CriticalFailure(eff) => eff,
-        }}</>}
+        }
+    }
+
+    pub fn to_html(&self, localization: Localization) -> Html {
+        html!{<><b>{localization.t(&self.to_string())}{": "}</b>{self.effect().to_string()}</>}
     }
 }
 
@@ -183,35 +183,105 @@ impl Display for Heightened {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
             Heightened::Repeat(lvl, _) => write!(f, "Heightened (+{})", lvl),
-            Heightened::Single(lvl, _) => write!(f, "Heightened ({})", match lvl {
-                1 => "1st".to_string(),
-                2 => "2nd".to_string(),
-                3 => "3rd".to_string(),
-                n => format!("{}th", n)
-            }),
+            Heightened::Single(lvl, _) => write!(f, "Heightened ({})", Locale::English.ordinal(*lvl)),
         }
     }
 }
 
 impl Heightened {
-    pub fn to_html(&self) -> Html {
-        html!{<><b>{self.to_string()}{": "}</b>{match self {
+    pub fn effect(&self) -> &str {
+        match self {
             Heightened::Repeat(_, eff) | Heightened::Single(_, eff) => eff,
-        }}</>}
+        }
+    }
+
+    pub fn to_html(&self, terminology: Terminology, localization: Localization) -> Html {
+        html!{<><b title={terminology.heightened_title(localization, self)}>{localization.heightened(self)}{": "}</b>{self.effect().to_string()}</>}
+    }
+}
+
+// Books whose spells use the remastered rules
+const REMASTER_BOOKS: [&str; 6] = ["Player Core", "GM Core", "Monster Core", "Rage of Elements", "Howl of the Wild", "War of Immortals"];
+
+#[derive(PartialEq, Eq, Clone, Default, Serialize, Deserialize)]
+pub enum SpellSource {
+    #[default]
+    Unknown,
+    Published { book: String, page: Option<u16>, remaster: bool },
+    Homebrew { author: String },
+}
+
+impl Display for SpellSource {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SpellSource::Unknown => write!(f, ""),
+            SpellSource::Published { book, page, remaster } => {
+                write!(f, "{}", book)?;
+                if let Some(page) = page {
+                    write!(f, " p. {}", page)?;
+                }
+                write!(f, " ({})", if *remaster { "remaster" } else { "legacy" })
+            },
+            SpellSource::Homebrew { author } if author.is_empty() => write!(f, "Homebrew"),
+            SpellSource::Homebrew { author } => write!(f, "Homebrew by {}", author),
+        }
+    }
+}
+
+// Byte index of the last `marker` in `text` ignoring ASCII case, lowercasing first could move the index
+fn rfind_ignore_ascii_case(text: &str, marker: &str) -> Option<usize> {
+    text.char_indices()
+        .rev()
+        .map(|(index, _)| index)
+        .find(|index| text[*index..].get(..marker.len()).is_some_and(|part| part.eq_ignore_ascii_case(marker)))
+}
+
+impl SpellSource {
+    // Reads references such as "Player Core pg. 316"
+    pub fn from_reference(reference: &str) -> SpellSource {
+        let reference: &str = reference.trim();
+        if reference.is_empty() {
+            return SpellSource::Unknown;
+        }
+        let (book, page): (&str, Option<u16>) = match rfind_ignore_ascii_case(reference, " pg.").or(rfind_ignore_ascii_case(reference, " p.")) {
+            Some(index) => {
+                let page: Option<u16> = reference[index..].split_whitespace()
+                    .nth(1)
+                    .and_then(|page| page.parse::<u16>().ok());
+                (reference[..index].trim(), page)
+            },
+            None => (reference, None),
+        };
+        let remaster: bool = REMASTER_BOOKS.iter().any(|remaster_book| book.trim_start_matches("Pathfinder ").starts_with(remaster_book));
+        SpellSource::Published { book: book.to_string(), page, remaster }
     }
 }
 
 #[derive(PartialEq, Properties, Clone, Serialize, Deserialize)]
 pub struct SpellCard {
+    // Identity, kept through edits and renames
+    #[serde(default)]
+    #[prop_or_default]
+    pub id: String,
     // Header
     pub spell_name: String,
     pub cast_time: CastTime,
     pub spell_type: SpellType,
     pub spell_level: u8,
     pub link: String,
+    #[serde(default)]
+    #[prop_or_default]
+    pub source: SpellSource,
     // Middle
     pub traits: Vec<String>,
+    #[serde(default)]
+    #[prop_or_default]
+    pub traditions: Vec<String>,
     pub overview: Vec<SpellOverview>,
+    // A basic save halves the damage on a success and doubles it on a critical failure
+    #[serde(default)]
+    #[prop_or_default]
+    pub basic_save: bool,
     // Bottom
     pub spell_effect: String,
     pub roll_effect: Vec<RollResult>,
@@ -219,50 +289,86 @@ pub struct SpellCard {
 }
 
 impl SpellCard {
+    pub fn empty() -> SpellCard {
+        SpellCard {
+            id: String::new(),
+            spell_name: String::new(),
+            cast_time: CastTime::Single,
+            spell_type: SpellType::Spell,
+            spell_level: 1,
+            link: String::new(),
+            source: SpellSource::Unknown,
+            traits: Vec::new(),
+            traditions: Vec::new(),
+            overview: Vec::new(),
+            basic_save: false,
+            spell_effect: String::new(),
+            roll_effect: Vec::new(),
+            heightened: Vec::new()
+        }
+    }
+
     pub fn to_html(&self) -> Html {
         html!{<SpellCardHtml
             spell_name={self.spell_name.clone()}
             cast_time={self.cast_time.clone()}
-            spell_type={self.spell_type.clone()}
-            spell_level={self.spell_level.clone()}
+            spell_type={self.spell_type}
+            spell_level={self.spell_level}
             link={self.link.clone()}
+            source={self.source.clone()}
             traits={self.traits.clone()}
+            traditions={self.traditions.clone()}
             overview={self.overview.clone()}
+            basic_save={self.basic_save}
             spell_effect={self.spell_effect.clone()}
             roll_effect={self.roll_effect.clone()}
             heightened={self.heightened.clone()}
         />}
     }
 
-    pub fn get_overview_element(&self, overview_type:&str) -> Option<SpellOverview> {
-        let overtype_string: String = overview_type.to_string();
-        for elem in self.overview.clone() {
-            if overtype_string == elem.to_string() {
-                return Some(elem);
-            }
+    // Gives the card an id unless it already has one
+    pub fn with_id(mut self) -> SpellCard {
+        if self.id.is_empty() {
+            self.id = generate_id();
         }
-        None
+        self
+    }
+
+    pub fn get_overview_element(&self, overview_type:&str) -> Option<SpellOverview> {
+        self.overview.iter().find(|elem| elem.to_string() == overview_type).cloned()
     }
 }
 
+// Time based with a random part, so cards made on different devices do not collide
+pub fn generate_id() -> String {
+    let random: u32 = (js_sys::Math::random() * u32::MAX as f64) as u32;
+    format!("{:x}{:08x}", js_sys::Date::now() as u64, random)
+}
+
 #[function_component]
 pub fn SpellCardHtml(props: &SpellCard) -> Html {
     let SpellCard {
+        id: _,
         // Header
         spell_name,
         cast_time,
         spell_type,
         spell_level,
         link,
+        source,
         // Middle
         traits,
+        traditions,
         overview,
+        basic_save,
         // Bottom
         spell_effect,
         roll_effect,
         heightened
     } = props;
 
+    let terminology: Terminology = use_context::<Terminology>().unwrap_or_default();
+    let localization: Localization = use_context::<Localization>().unwrap_or_default();
     let spell_effect_paragraphs: Vec<&str> = spell_effect.split("\n").collect();
 
     html! {
@@ -271,14 +377,14 @@ pub fn SpellCardHtml(props: &SpellCard) -> Html {
             <SpellHeader
                 spell_name={spell_name.clone()}
                 cast_time={cast_time.clone()}
-                spell_type={spell_type.clone()}
+                spell_type={*spell_type}
                 spell_level={spell_level}
                 link={link.clone()}
             />
             <hr/>
             // # Middle
             // Traits
-            if traits.len() > 0 {
+            if !traits.is_empty() {
                 <div class="trait-container">
                     { traits.iter().map(|val| {
                         html!{<SpellTrait value={val.clone()} />}
@@ -286,32 +392,40 @@ pub fn SpellCardHtml(props: &SpellCard) -> Html {
                 </div>
                 <hr/>
             }
+            // Traditions
+            if !traditions.is_empty() {
+                <div><b>{localization.t("Traditions")}{": "}</b>{traditions.join(", ")}</div>
+            }
             // overview
-            if overview.len() > 0 {
-                { overview.clone().iter().map(|elem| {
-                    html!{<div key={elem.to_string()}>{elem.to_html()}</div>}
+            if !overview.is_empty() {
+                { overview.iter().enumerate().map(|(index, elem)| {
+                    html!{<div key={index}>{elem.to_html(terminology, localization, *basic_save)}</div>}
                 }).collect::<Html>() }
                 <hr/>
             }
             // # Bottom
             // Effect
-            { spell_effect_paragraphs.iter().map(|par| {
-                html!(<div key={*par}>{par}</div>)
+            { spell_effect_paragraphs.iter().enumerate().map(|(index, par)| {
+                html!(<div key={index}>{par}</div>)
             }).collect::<Html>() }
             // Save/Attack results
-            if roll_effect.len() > 0 {
+            if !roll_effect.is_empty() {
                 <hr/>
-                { roll_effect.iter().map(|elem| {
-                    html!{<div key={elem.to_string()}>{elem.to_html()}</div>}
+                { roll_effect.iter().enumerate().map(|(index, elem)| {
+                    html!{<div key={index}>{elem.to_html(localization)}</div>}
                 }).collect::<Html>() }
             }
             // Heightened
-            if heightened.len() > 0 {
+            if !heightened.is_empty() {
                 <hr/>
-                { heightened.iter().map(|elem| {
-                    html!{<div key={elem.to_string()}>{elem.to_html()}</div>}
+                { heightened.iter().enumerate().map(|(index, elem)| {
+                    html!{<div key={index}>{elem.to_html(terminology, localization)}</div>}
                 }).collect::<Html>() }
             }
+            // # Footer
+            if *source != SpellSource::Unknown {
+                <div class="spell-source">{localization.source(source)}</div>
+            }
         </div>
     }
 }
@@ -358,6 +472,9 @@ pub fn SpellHeader(props: &SpellHeaderProps) -> Html {
         spell_level
     } = props;
 
+    let terminology: Terminology = use_context::<Terminology>().unwrap_or_default();
+    let localization: Localization = use_context::<Localization>().unwrap_or_default();
+
     html! {
         <div class="spell-header">
             <div>
@@ -367,9 +484,31 @@ pub fn SpellHeader(props: &SpellHeaderProps) -> Html {
                     <span class="action">{cast_time.to_string()}</span>
                 }
             </div>
-            <div>
-                {spell_type.to_string()}{" "}{spell_level}
+            <div title={terminology.rank_title(localization, *spell_level)}>
+                {localization.t(&spell_type.to_string())}{" "}{spell_level}
             </div>
         </div>
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_book_and_page_references() {
+        assert!(SpellSource::from_reference("Player Core pg. 316") == SpellSource::Published { book: "Player Core".to_string(), page: Some(316), remaster: true });
+        assert!(SpellSource::from_reference("Core Rulebook PG. 334") == SpellSource::Published { book: "Core Rulebook".to_string(), page: Some(334), remaster: false });
+        assert!(SpellSource::from_reference("Pathfinder GM Core p. 12") == SpellSource::Published { book: "Pathfinder GM Core".to_string(), page: Some(12), remaster: true });
+        assert!(SpellSource::from_reference("Secrets of Magic") == SpellSource::Published { book: "Secrets of Magic".to_string(), page: None, remaster: false });
+        assert!(SpellSource::from_reference("  ") == SpellSource::Unknown);
+    }
+
+    #[test]
+    fn reads_references_with_non_ascii_book_names() {
+        // Lowercasing "İ" takes more bytes, which used to shift the page marker
+        assert!(SpellSource::from_reference("İİİİ pg. 7") == SpellSource::Published { book: "İİİİ".to_string(), page: Some(7), remaster: false });
+        assert!(SpellSource::from_reference("Grimoire des Éléments p. 42") == SpellSource::Published { book: "Grimoire des Éléments".to_string(), page: Some(42), remaster: false });
+        assert!(SpellSource::from_reference("Ünheimliche Zauber") == SpellSource::Published { book: "Ünheimliche Zauber".to_string(), page: None, remaster: false });
+    }
+}
@@ -1,3 +1,5 @@
+use std::cell::RefCell;
+use std::rc::Rc;
 use gloo_console::log;
 use regex::Regex;
 use yew::prelude::*;
@@ -6,37 +8,54 @@ use web_sys::{window, Document, EventTarget, HtmlInputElement, HtmlSelectElement
 
 use crate::app::spell_card::{Area, CastTime, Heightened, RollResult, SpellOverview, SpellType};
 
-use super::spell_card::{Defence, SpellCard};
+use super::drafts::{discard_draft, find_draft, save_draft, Draft};
+use super::history::{History, UndoRedo};
+use super::localization::Localization;
+use super::spell_card::{Defence, SpellCard, SpellSource};
+use super::stat_block::{parse_stat_block, ParsedStatBlock};
+use super::terminology::Terminology;
 
 #[derive(PartialEq, Properties)]
 pub struct SpellCardCreatorProps {
-    // pub on_card_completion: Callback<SpellCard>,
+    // Card to edit, a new card is started when none is given
+    #[prop_or_default]
+    pub card: Option<SpellCard>,
+    // Identifies what is being worked on, so each card keeps its own draft and edit history
+    #[prop_or(AttrValue::from("new"))]
+    pub draft_key: AttrValue,
+    pub on_card_completion: Callback<SpellCard>,
     pub on_cancellation: Callback<MouseEvent>
 }
 
 #[function_component]
 pub fn SpellCardCreator(props: &SpellCardCreatorProps) -> Html {
     let SpellCardCreatorProps {
-        // on_card_completion,
+        card,
+        draft_key,
+        on_card_completion,
         on_cancellation
     } = props;
 
-    let state: UseStateHandle<SpellCard> = use_state(|| {
-        SpellCard { 
-            spell_name: String::new(),
-            cast_time: CastTime::Single,
-            spell_type: SpellType::Spell,
-            spell_level: 1,
-            link: String::new(),
-            traits: Vec::new(),
-            overview: Vec::new(),
-            spell_effect: String::new(),
-            roll_effect: Vec::new(),
-            heightened: Vec::new()
-        }
+    let localization: Localization = use_context::<Localization>().unwrap_or_default();
+
+    let history_key: String = format!("CreatorHistory/{}", draft_key);
+    let original_card: SpellCard = card.clone().unwrap_or_else(SpellCard::empty);
+    let state: UseStateHandle<SpellCard> = use_state(|| original_card.clone());
+    // A draft left from an earlier visit is offered until the card is edited, which replaces it
+    let resume_offer: UseStateHandle<Option<Draft>> = use_state(|| {
+        find_draft(draft_key).filter(|draft| draft.card != original_card)
     });
+    let autosaved_card: Rc<RefCell<SpellCard>> = use_mut_ref(|| (*state).clone());
+    let history: UseStateHandle<History<SpellCard>> = use_state(|| History::load(&history_key));
+    // Last card put in the history, so undoing and redoing are not recorded as edits themselves
+    let recorded_card: Rc<RefCell<SpellCard>> = use_mut_ref(|| (*state).clone());
+    let stat_block: UseStateHandle<String> = use_state(String::new);
+    let stat_block_warnings: UseStateHandle<Vec<String>> = use_state(Vec::new);
     let state_value: SpellCard = (*state).clone();
     let card_html: Html = state.to_html();
+    let area_element: Option<SpellOverview> = state_value.get_overview_element("Area");
+    let defence_element: Option<SpellOverview> = state_value.get_overview_element("Defence");
+    let terminology: Terminology = use_context::<Terminology>().unwrap_or_default();
     
     let mut heightened: Vec<Heightened> = state_value.heightened.clone();
     match heightened.last() {
@@ -53,7 +72,108 @@ pub fn SpellCardCreator(props: &SpellCardCreatorProps) -> Html {
         }
     }
 
+    // Every edit goes through `state.set`, so the history follows the card rather than each callback
+    {
+        let history: UseStateHandle<History<SpellCard>> = history.clone();
+        let recorded_card: Rc<RefCell<SpellCard>> = recorded_card.clone();
+        let history_key: String = history_key.clone();
+        use_effect_with(state_value.clone(), move |card: &SpellCard| {
+            let previous: SpellCard = recorded_card.replace(card.clone());
+            if previous != *card {
+                let mut new_history: History<SpellCard> = (*history).clone();
+                new_history.record(previous);
+                new_history.save(&history_key);
+                history.set(new_history)
+            }
+        });
+    }
+    // Drafts are saved on every change, and dropped again once the card is back to how it started
+    {
+        let autosaved_card: Rc<RefCell<SpellCard>> = autosaved_card.clone();
+        let resume_offer: UseStateHandle<Option<Draft>> = resume_offer.clone();
+        let draft_key: AttrValue = draft_key.clone();
+        let original_card: SpellCard = original_card.clone();
+        use_effect_with(state_value.clone(), move |card: &SpellCard| {
+            if autosaved_card.replace(card.clone()) != *card {
+                if *card == original_card {
+                    discard_draft(&draft_key);
+                } else {
+                    save_draft(&draft_key, card);
+                }
+                if resume_offer.is_some() {
+                    resume_offer.set(None)
+                }
+            }
+        });
+    }
+
     // # Callback functions
+    // ## History
+    let history_step = |redo: bool| {
+        let state: UseStateHandle<SpellCard> = state.clone();
+        let history: UseStateHandle<History<SpellCard>> = history.clone();
+        let recorded_card: Rc<RefCell<SpellCard>> = recorded_card.clone();
+        let history_key: String = history_key.clone();
+
+        Callback::from(move |_| {
+            let mut new_history: History<SpellCard> = (*history).clone();
+            let card: Option<SpellCard> = if redo {
+                new_history.redo((*state).clone())
+            } else {
+                new_history.undo((*state).clone())
+            };
+            if let Some(card) = card {
+                *recorded_card.borrow_mut() = card.clone();
+                new_history.save(&history_key);
+                history.set(new_history);
+                state.set(card)
+            }
+        })
+    };
+    // ## Drafts
+    let resume_draft: Callback<MouseEvent> = {
+        let state: UseStateHandle<SpellCard> = state.clone();
+        let resume_offer: UseStateHandle<Option<Draft>> = resume_offer.clone();
+
+        Callback::from(move |_| {
+            if let Some(draft) = &*resume_offer {
+                state.set(draft.card.clone())
+            }
+        })
+    };
+    let discard_offered_draft: Callback<MouseEvent> = {
+        let resume_offer: UseStateHandle<Option<Draft>> = resume_offer.clone();
+        let draft_key: AttrValue = draft_key.clone();
+
+        Callback::from(move |_| {
+            discard_draft(&draft_key);
+            resume_offer.set(None)
+        })
+    };
+    // ## Stat block
+    let stat_block_change: Callback<InputEvent> = {
+        let stat_block: UseStateHandle<String> = stat_block.clone();
+
+        Callback::from(move |e: InputEvent| {
+            let target: Option<EventTarget> = e.target();
+
+            let input: Option<HtmlTextAreaElement> = target.and_then(|t| t.dyn_into::<HtmlTextAreaElement>().ok());
+            if let Some(input) = input {
+                stat_block.set(input.value())
+            }
+        })
+    };
+    let stat_block_parse: Callback<MouseEvent> = {
+        let state: UseStateHandle<SpellCard> = state.clone();
+        let stat_block: UseStateHandle<String> = stat_block.clone();
+        let stat_block_warnings: UseStateHandle<Vec<String>> = stat_block_warnings.clone();
+
+        Callback::from(move |_| {
+            let ParsedStatBlock { card, warnings } = parse_stat_block(&stat_block, localization);
+            stat_block_warnings.set(warnings);
+            state.set(card)
+        })
+    };
     // ## Spell name
     let spell_name_change: Callback<Event> = {
         let state: UseStateHandle<SpellCard> = state.clone();
@@ -84,6 +204,39 @@ pub fn SpellCardCreator(props: &SpellCardCreatorProps) -> Html {
             }
         })
     };
+    // ## Source
+    let source_kind_change: Callback<Event> = {
+        let state: UseStateHandle<SpellCard> = state.clone();
+
+        Callback::from(move |e: Event| {
+            let target: Option<EventTarget> = e.target();
+
+            let input: Option<HtmlSelectElement> = target.and_then(|t: EventTarget| t.dyn_into::<HtmlSelectElement>().ok());
+            if let Some(input) = input {
+                let mut card: SpellCard = (*state).clone();
+                card.source = match input.value().as_str() {
+                    "published" => SpellSource::Published { book: String::new(), page: None, remaster: true },
+                    "homebrew" => SpellSource::Homebrew { author: String::new() },
+                    _ => SpellSource::Unknown,
+                };
+                state.set(card)
+            }
+        })
+    };
+    let source_field_change = |update: fn(&mut SpellSource, &HtmlInputElement)| {
+        let state: UseStateHandle<SpellCard> = state.clone();
+
+        Callback::from(move |e: Event| {
+            let target: Option<EventTarget> = e.target();
+
+            let input: Option<HtmlInputElement> = target.and_then(|t: EventTarget| t.dyn_into::<HtmlInputElement>().ok());
+            if let Some(input) = input {
+                let mut card: SpellCard = (*state).clone();
+                update(&mut card.source, &input);
+                state.set(card)
+            }
+        })
+    };
     // ## Cast time
     let cast_time_change: Callback<Event> = {
         let state: UseStateHandle<SpellCard> = state.clone();
@@ -206,6 +359,21 @@ pub fn SpellCardCreator(props: &SpellCardCreatorProps) -> Html {
             }
         })
     };
+    // ## Traditions
+    let traditions_change: Callback<Event> = {
+        let state: UseStateHandle<SpellCard> = state.clone();
+
+        Callback::from(move |e: Event| {
+            let target: Option<EventTarget> = e.target();
+            
+            let input: Option<HtmlInputElement> = target.and_then(|t: EventTarget| t.dyn_into::<HtmlInputElement>().ok());
+            if let Some(input) = input {
+                let mut card: SpellCard = (*state).clone();
+                card.traditions = input.value().split(',').map(|s| s.trim().to_string()).filter(|s| !s.is_empty()).collect();
+                state.set(card)
+            }
+        })
+    };
     // ## Spell overview
     let spell_overview_range_change: Callback<Event> = {
         let state: UseStateHandle<SpellCard> = state.clone();
@@ -358,6 +526,20 @@ pub fn SpellCardCreator(props: &SpellCardCreatorProps) -> Html {
             }
         })
     };
+    let basic_save_change: Callback<Event> = {
+        let state: UseStateHandle<SpellCard> = state.clone();
+
+        Callback::from(move |e: Event| {
+            let target: Option<EventTarget> = e.target();
+
+            let input: Option<HtmlInputElement> = target.and_then(|t: EventTarget| t.dyn_into::<HtmlInputElement>().ok());
+            if let Some(input) = input {
+                let mut card: SpellCard = (*state).clone();
+                card.basic_save = input.checked();
+                state.set(card)
+            }
+        })
+    };
     let spell_overview_duration_change: Callback<Event> = {
         let state: UseStateHandle<SpellCard> = state.clone();
 
@@ -405,7 +587,7 @@ pub fn SpellCardCreator(props: &SpellCardCreatorProps) -> Html {
             if let Some(input) = input {
                 let mut card: SpellCard = (*state).clone();
                 let mut roll_result: Vec<RollResult> = card.roll_effect.clone();
-                roll_result = roll_result.into_iter().filter(|rr| if let RollResult::CriticalSuccess(_) = rr {false} else {true}).collect();
+                roll_result.retain(|rr| !matches!(rr, RollResult::CriticalSuccess(_)));
                 let crit_succ_str: String = input.value();
                 if !crit_succ_str.is_empty() {
                     roll_result.push(RollResult::CriticalSuccess(crit_succ_str));
@@ -426,7 +608,7 @@ pub fn SpellCardCreator(props: &SpellCardCreatorProps) -> Html {
             if let Some(input) = input {
                 let mut card: SpellCard = (*state).clone();
                 let mut roll_result: Vec<RollResult> = card.roll_effect.clone();
-                roll_result = roll_result.into_iter().filter(|rr| if let RollResult::Success(_) = rr {false} else {true}).collect();
+                roll_result.retain(|rr| !matches!(rr, RollResult::Success(_)));
                 let succ_str: String = input.value();
                 if !succ_str.is_empty() {
                     roll_result.push(RollResult::Success(succ_str));
@@ -447,7 +629,7 @@ pub fn SpellCardCreator(props: &SpellCardCreatorProps) -> Html {
             if let Some(input) = input {
                 let mut card: SpellCard = (*state).clone();
                 let mut roll_result: Vec<RollResult> = card.roll_effect.clone();
-                roll_result = roll_result.into_iter().filter(|rr| if let RollResult::Failure(_) = rr {false} else {true}).collect();
+                roll_result.retain(|rr| !matches!(rr, RollResult::Failure(_)));
                 let fail_str: String = input.value();
                 if !fail_str.is_empty() {
                     roll_result.push(RollResult::Failure(fail_str));
@@ -468,7 +650,7 @@ pub fn SpellCardCreator(props: &SpellCardCreatorProps) -> Html {
             if let Some(input) = input {
                 let mut card: SpellCard = (*state).clone();
                 let mut roll_result: Vec<RollResult> = card.roll_effect.clone();
-                roll_result = roll_result.into_iter().filter(|rr| if let RollResult::CriticalFailure(_) = rr {false} else {true}).collect();
+                roll_result.retain(|rr| !matches!(rr, RollResult::CriticalFailure(_)));
                 let crit_fail_str: String = input.value();
                 if !crit_fail_str.is_empty() {
                     roll_result.push(RollResult::CriticalFailure(crit_fail_str));
@@ -519,13 +701,65 @@ pub fn SpellCardCreator(props: &SpellCardCreatorProps) -> Html {
             state.set(card)
         })
     };
+    // ## Completion
+    let save_card: Callback<MouseEvent> = {
+        let state: UseStateHandle<SpellCard> = state.clone();
+        let on_card_completion: Callback<SpellCard> = on_card_completion.clone();
+        let history_key: String = history_key.clone();
+        let draft_key: AttrValue = draft_key.clone();
+
+        Callback::from(move |_| {
+            History::<SpellCard>::clear(&history_key);
+            discard_draft(&draft_key);
+            let mut card: SpellCard = (*state).clone();
+            // Drop the trailing empty rows the form keeps for adding new entries
+            card.heightened.retain(|h| !h.effect().is_empty());
+            card.roll_effect.retain(|rr| !rr.effect().is_empty());
+            on_card_completion.emit(card)
+        })
+    };
 
     html! {
         <div>
-            <p>{"Tada!"}</p>
+            <p>{localization.t("Tada!")}</p>
+            if let Some(draft) = &*resume_offer {
+                <div class="draft-offer">
+                    {localization.tf("You have an unsaved draft of \"{}\" from {}. Editing the card replaces it.", &[&draft.name(), &draft.saved_at_string()])}
+                    <button type="button" onclick={resume_draft}>{localization.t("Resume draft")}</button>
+                    <button type="button" onclick={discard_offered_draft}>{localization.t("Discard draft")}</button>
+                </div>
+            }
+            <UndoRedo
+                can_undo={history.can_undo()}
+                can_redo={history.can_redo()}
+                unsaved={history.is_unsaved()}
+                on_undo={history_step(false)}
+                on_redo={history_step(true)}
+            />
+            <div class="stat-block-import">
+                <label>
+                    {localization.t("Paste stat block:")}
+                    <textarea
+                        oninput={stat_block_change}
+                        value={(*stat_block).clone()}
+                        placeholder={localization.t("Copy a spell from Archives of Nethys and paste it here")}
+                    />
+                </label>
+                <button type="button" onclick={stat_block_parse}>
+                    {localization.t("Fill in from stat block")}
+                </button>
+                if !stat_block_warnings.is_empty() {
+                    <ul class="import-warnings">
+                        { stat_block_warnings.iter().map(|warning| {
+                            html!{<li>{warning}</li>}
+                        }).collect::<Html>() }
+                    </ul>
+                }
+            </div>
+            <hr/>
             <form>
                 <label>
-                    {"Spell name:"}
+                    {localization.t("Spell name:")}
                     <input 
                         onchange={spell_name_change}
                         type="text"
@@ -534,7 +768,7 @@ pub fn SpellCardCreator(props: &SpellCardCreatorProps) -> Html {
                 </label>
                 <br/>
                 <label>
-                    {"Link:"}
+                    {localization.t("Link:")}
                     <input 
                         onchange={link_change}
                         type="url"
@@ -543,15 +777,54 @@ pub fn SpellCardCreator(props: &SpellCardCreatorProps) -> Html {
                 </label>
                 <br/>
                 <label>
-                    {"Cast time:"}
+                    {localization.t("Source:")}
+                    <select onchange={source_kind_change}>
+                        <option value={"unknown"} selected={state_value.source == SpellSource::Unknown}>{localization.t("Not given")}</option>
+                        <option value={"published"} selected={matches!(state_value.source, SpellSource::Published { .. })}>{localization.t("Published book")}</option>
+                        <option value={"homebrew"} selected={matches!(state_value.source, SpellSource::Homebrew { .. })}>{localization.t("Homebrew")}</option>
+                    </select>
+                    if let SpellSource::Published { book, page, remaster } = state_value.source.clone() {
+                        <input
+                            onchange={source_field_change(|source, input| if let SpellSource::Published { book, .. } = source { *book = input.value().trim().to_string() })}
+                            type="text"
+                            placeholder={localization.t("Book")}
+                            value={book}
+                        />
+                        <input
+                            onchange={source_field_change(|source, input| if let SpellSource::Published { page, .. } = source { *page = input.value().parse::<u16>().ok() })}
+                            type="number"
+                            placeholder={localization.t("Page")}
+                            min="1"
+                            value={page.map(|p| p.to_string()).unwrap_or_default()}
+                        />
+                        <span>
+                            <input
+                                onchange={source_field_change(|source, input| if let SpellSource::Published { remaster, .. } = source { *remaster = input.checked() })}
+                                type="checkbox"
+                                checked={remaster}
+                            />
+                            {localization.t("Remaster")}
+                        </span>
+                    } else if let SpellSource::Homebrew { author } = state_value.source.clone() {
+                        <input
+                            onchange={source_field_change(|source, input| if let SpellSource::Homebrew { author } = source { *author = input.value().trim().to_string() })}
+                            type="text"
+                            placeholder={localization.t("Author")}
+                            value={author}
+                        />
+                    }
+                </label>
+                <br/>
+                <label>
+                    {localization.t("Cast time:")}
                     <select onchange={cast_time_change}>
-                        <option value={"free"}>{"Free action"}</option>
-                        <option value={"reaction"}>{"Reaction"}</option>
-                        <option value={"single"} selected={true}>{"Single action"}</option>
-                        <option value={"double"}>{"Two actions"}</option>
-                        <option value={"triple"}>{"Three actions"}</option>
-                        <option value={"range"}>{"Range"}</option>
-                        <option value={"longer"}>{"Longer, specify"}</option>
+                        <option value={"free"} selected={state_value.cast_time == CastTime::Free}>{localization.t("Free action")}</option>
+                        <option value={"reaction"} selected={state_value.cast_time == CastTime::Reaction}>{localization.t("Reaction")}</option>
+                        <option value={"single"} selected={state_value.cast_time == CastTime::Single}>{localization.t("Single action")}</option>
+                        <option value={"double"} selected={state_value.cast_time == CastTime::Double}>{localization.t("Two actions")}</option>
+                        <option value={"triple"} selected={state_value.cast_time == CastTime::Triple}>{localization.t("Three actions")}</option>
+                        <option value={"range"} selected={matches!(state_value.cast_time, CastTime::Range(_, _))}>{localization.t("Range")}</option>
+                        <option value={"longer"} selected={matches!(state_value.cast_time, CastTime::Longer(_))}>{localization.t("Longer, specify")}</option>
                     </select>
                     if let CastTime::Longer(duration) = state_value.cast_time.clone() {
                         <input
@@ -578,13 +851,14 @@ pub fn SpellCardCreator(props: &SpellCardCreatorProps) -> Html {
                 </label>
                 <br/>
                 <label>
-                    {"Spell type:"}
+                    {localization.t("Spell type:")}
                     <select onchange={spell_type_change}>
-                        <option value={"cantrip"}>{"Cantrip"}</option>
-                        <option value={"spell"} selected={true}>{"Spell"}</option>
-                        <option value={"focus"}>{"Focus"}</option>
-                        <option value={"ritual"}>{"Ritual"}</option>
+                        <option value={"cantrip"} selected={state_value.spell_type == SpellType::Cantrip}>{localization.t("Cantrip")}</option>
+                        <option value={"spell"} selected={state_value.spell_type == SpellType::Spell}>{localization.t("Spell")}</option>
+                        <option value={"focus"} selected={state_value.spell_type == SpellType::Focus}>{localization.t("Focus")}</option>
+                        <option value={"ritual"} selected={state_value.spell_type == SpellType::Ritual}>{localization.t("Ritual")}</option>
                     </select>
+                    {format!(" {}:", localization.t(terminology.rank_label()))}
                     <input
                         onchange={spell_level_change}
                         type="number"
@@ -595,15 +869,24 @@ pub fn SpellCardCreator(props: &SpellCardCreatorProps) -> Html {
                 </label>
                 <hr/>
                 <label>
-                    {"Traits:"}
+                    {localization.t("Traits:")}
                     <textarea
                         oninput={traits_change}
                         value={state_value.traits.join("\n")}
                     />
                 </label>
+                <br/>
+                <label>
+                    {localization.t("Traditions:")}
+                    <input
+                        onchange={traditions_change}
+                        type="text"
+                        value={state_value.traditions.join(", ")}
+                    />
+                </label>
                 <hr/>
                 <label>
-                    {"Range:"}
+                    {localization.t("Range:")}
                     <input
                         onchange={spell_overview_range_change}
                         type="number"
@@ -616,16 +899,17 @@ pub fn SpellCardCreator(props: &SpellCardCreatorProps) -> Html {
                         }
                         min={"0"}
                         step={"5"}
+                        title={localization.t("In feet, 5 ft per square")}
                     />
                 </label>
                 <br/>
                 <label>
-                    {"Area:"}
+                    {localization.t("Area:")}
                     <select onchange={spell_overview_area_type_change} id={"spell_overview_area_selector"}>
-                        <option value={"burst"} selected={true}>{"Burst"}</option>
-                        <option value={"cone"}>{"Cone"}</option>
-                        <option value={"eman"}>{"Emanation"}</option>
-                        <option value={"line"}>{"Line"}</option>
+                        <option value={"burst"} selected={!matches!(area_element, Some(SpellOverview::Area(Area::Cone(_) | Area::Emanation(_) | Area::Line(_, _))))}>{localization.t("Burst")}</option>
+                        <option value={"cone"} selected={matches!(area_element, Some(SpellOverview::Area(Area::Cone(_))))}>{localization.t("Cone")}</option>
+                        <option value={"eman"} selected={matches!(area_element, Some(SpellOverview::Area(Area::Emanation(_))))}>{localization.t("Emanation")}</option>
+                        <option value={"line"} selected={matches!(area_element, Some(SpellOverview::Area(Area::Line(_, _))))}>{localization.t("Line")}</option>
                     </select>
                     <input
                         onchange={spell_overview_area_value_change}
@@ -639,6 +923,7 @@ pub fn SpellCardCreator(props: &SpellCardCreatorProps) -> Html {
                         }
                         min={"0"}
                         step={"5"}
+                        title={localization.t("In feet, 5 ft per square")}
                     />
                     if let Some(SpellOverview::Area(Area::Line(_, second_line_val))) = state_value.get_overview_element("Area") {
                         <input
@@ -652,12 +937,13 @@ pub fn SpellCardCreator(props: &SpellCardCreatorProps) -> Html {
                             }
                             min={"0"}
                             step={"5"}
+                            title={localization.t("In feet, 5 ft per square")}
                         />
                     }
                 </label>
                 <br/>
                 <label>
-                    {"Targets:"}
+                    {localization.t("Targets:")}
                     <input
                         onchange={spell_overview_targets_change}
                         type="text"
@@ -672,18 +958,28 @@ pub fn SpellCardCreator(props: &SpellCardCreatorProps) -> Html {
                 </label>
                 <br/>
                 <label>
-                    {"Defence:"}
+                    {format!("{}:", localization.t(terminology.defence_label()))}
                     <select onchange={spell_overview_defence_change}>
-                        <option value={"none"} selected={true}>{"None"}</option>
-                        <option value={"ac"}>{"AC"}</option>
-                        <option value={"fort"}>{"Fortitude"}</option>
-                        <option value={"refl"}>{"Reflex"}</option>
-                        <option value={"will"}>{"Will"}</option>
+                        <option value={"none"} selected={defence_element.is_none()}>{localization.t("None")}</option>
+                        <option value={"ac"} selected={defence_element == Some(SpellOverview::Defence(Defence::ArmourClass))}>{localization.t(&terminology.defence_name(&Defence::ArmourClass))}</option>
+                        <option value={"fort"} selected={defence_element == Some(SpellOverview::Defence(Defence::Fortitude))}>{localization.t("Fortitude")}</option>
+                        <option value={"refl"} selected={defence_element == Some(SpellOverview::Defence(Defence::Reflex))}>{localization.t("Reflex")}</option>
+                        <option value={"will"} selected={defence_element == Some(SpellOverview::Defence(Defence::Will))}>{localization.t("Will")}</option>
                     </select>
                 </label>
+                if defence_element.is_some() && defence_element != Some(SpellOverview::Defence(Defence::ArmourClass)) {
+                    <label>
+                        <input
+                            onchange={basic_save_change}
+                            type="checkbox"
+                            checked={state_value.basic_save}
+                        />
+                        {localization.t("Basic save")}
+                    </label>
+                }
                 <br/>
                 <label>
-                    {"Duration:"}
+                    {localization.t("Duration:")}
                     <input
                         onchange={spell_overview_duration_change}
                         type="text"
@@ -698,7 +994,7 @@ pub fn SpellCardCreator(props: &SpellCardCreatorProps) -> Html {
                 </label>
                 <hr/>
                 <label>
-                    {"Spell effect:"}
+                    {localization.t("Spell effect:")}
                     <textarea 
                         oninput={spell_effect_change}
                         value={state_value.spell_effect}
@@ -706,15 +1002,15 @@ pub fn SpellCardCreator(props: &SpellCardCreatorProps) -> Html {
                 </label>
                 <hr/>
                 <label>
-                    {"Roll effect"}
+                    {localization.t("Roll effect")}
                     <br/>
-                    {"Critical success:"}
+                    {localization.t("Critical success:")}
                     <input
                         onchange={crit_succ_change}
                         type="text"
                         value={
                             let roll_effect = state_value.roll_effect.to_owned();
-                            let maybe_rr = roll_effect.iter().find(|rr| if let RollResult::CriticalSuccess(_) = rr {true} else {false});
+                            let maybe_rr = roll_effect.iter().find(|rr| matches!(rr, RollResult::CriticalSuccess(_)));
                             match maybe_rr {
                                 Some(RollResult::CriticalSuccess(rr)) => rr.to_owned(),
                                 None => "".to_string(),
@@ -723,13 +1019,13 @@ pub fn SpellCardCreator(props: &SpellCardCreatorProps) -> Html {
                         }
                     />
                     <br/>
-                    {"Success:"}
+                    {localization.t("Success:")}
                     <input
                         onchange={succ_change}
                         type="text"
                         value={
                             let roll_effect = state_value.roll_effect.to_owned();
-                            let maybe_rr = roll_effect.iter().find(|rr| if let RollResult::Success(_) = rr {true} else {false});
+                            let maybe_rr = roll_effect.iter().find(|rr| matches!(rr, RollResult::Success(_)));
                             match maybe_rr {
                                 Some(RollResult::Success(rr)) => rr.to_owned(),
                                 None => "".to_string(),
@@ -738,13 +1034,13 @@ pub fn SpellCardCreator(props: &SpellCardCreatorProps) -> Html {
                         }
                     />
                     <br/>
-                    {"Failure:"}
+                    {localization.t("Failure:")}
                     <input
                         onchange={fail_change}
                         type="text"
                         value={
                             let roll_effect = state_value.roll_effect.to_owned();
-                            let maybe_rr = roll_effect.iter().find(|rr| if let RollResult::Failure(_) = rr {true} else {false});
+                            let maybe_rr = roll_effect.iter().find(|rr| matches!(rr, RollResult::Failure(_)));
                             match maybe_rr {
                                 Some(RollResult::Failure(rr)) => rr.to_owned(),
                                 None => "".to_string(),
@@ -753,13 +1049,13 @@ pub fn SpellCardCreator(props: &SpellCardCreatorProps) -> Html {
                         }
                     />
                     <br/>
-                    {"Critical failure:"}
+                    {localization.t("Critical failure:")}
                     <input
                         onchange={crit_fail_change}
                         type="text"
                         value={
                             let roll_effect = state_value.roll_effect.to_owned();
-                            let maybe_rr = roll_effect.iter().find(|rr| if let RollResult::CriticalFailure(_) = rr {true} else {false});
+                            let maybe_rr = roll_effect.iter().find(|rr| matches!(rr, RollResult::CriticalFailure(_)));
                             match maybe_rr {
                                 Some(RollResult::CriticalFailure(rr)) => rr.to_owned(),
                                 None => "".to_string(),
@@ -770,7 +1066,7 @@ pub fn SpellCardCreator(props: &SpellCardCreatorProps) -> Html {
                 </label>
                 <hr/>
                 <label>
-                    {"Heightened"}
+                    {localization.t("Heightened")}
                     { heightened.iter().map(|h| {
                         let (is_repeat, level, text) = match h {
                             Heightened::Repeat(lvl, txt) => (true, lvl, txt.to_owned()),
@@ -782,8 +1078,8 @@ pub fn SpellCardCreator(props: &SpellCardCreatorProps) -> Html {
                             <>
                                 <br/>
                                 <select>
-                                    <option value={"repeat"} selected={is_repeat}>{"Repeat"}</option>
-                                    <option value={"single"} selected={!is_repeat}>{"Once"}</option>
+                                    <option value={"repeat"} selected={is_repeat}>{localization.t("Repeat")}</option>
+                                    <option value={"single"} selected={!is_repeat}>{localization.t("Once")}</option>
                                 </select>
                                 <input
                                     type="number"
@@ -808,8 +1104,11 @@ pub fn SpellCardCreator(props: &SpellCardCreatorProps) -> Html {
             </form>
             <hr/>
             {card_html}
+            <button onclick={save_card} disabled={state.spell_name.trim().is_empty()}>
+                {localization.t("save")}
+            </button>
             <button onclick={on_cancellation}>
-                {"cancel"}
+                {localization.t("cancel")}
             </button>
         </div>
     }
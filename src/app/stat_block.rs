@@ -0,0 +1,356 @@
+use std::sync::LazyLock;
+use regex::Regex;
+
+use super::localization::Localization;
+use super::spell_card::{Area, CastTime, Defence, Heightened, RollResult, SpellCard, SpellOverview, SpellSource, SpellType};
+
+// Labels that start a stat-block field, longest first so "Saving Throw" wins over shorter prefixes
+const FIELD_LABELS: [&str; 22] = [
+    "Secondary Casters", "Primary Check", "Secondary Check", "Saving Throw", "Requirements",
+    "Traditions", "Tradition", "Duration", "Bloodline", "PFS Note", "Targets", "Trigger", "Mystery",
+    "Defense", "Defence", "Patron", "Source", "Lesson", "Domain", "Range", "Area", "Cast",
+];
+
+const DEGREE_LABELS: [&str; 4] = ["Critical Success", "Critical Failure", "Success", "Failure"];
+
+static TITLE_REGEX: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"^(.*?)\s*\b(Spell|Cantrip|Focus|Ritual)\s+(\d+)$").unwrap());
+static HEIGHTENED_REGEX: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"^Heightened\s*\((\+?)(\d+)(?:st|nd|rd|th)?\)\s*(.*)$").unwrap());
+static ACTION_REGEX: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"\[(one|two|three)-actions?\]|\b(single|one|two|three|1|2|3) actions?\b").unwrap());
+static FEET_REGEX: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"(\d+)[\s-]*(?:feet|foot|ft)(?:[\s-]*(long|wide))?").unwrap());
+
+pub struct ParsedStatBlock {
+    pub card: SpellCard,
+    pub warnings: Vec<String>,
+}
+
+// Parses text copied from a spell page on Archives of Nethys into a card, warnings are given in the chosen language
+pub fn parse_stat_block(text: &str, localization: Localization) -> ParsedStatBlock {
+    let mut card: SpellCard = SpellCard::empty();
+    let mut warnings: Vec<String> = Vec::new();
+
+    let lines: Vec<&str> = text.lines().map(|l| l.trim()).filter(|l| !l.is_empty()).collect();
+    let mut index: usize = 0;
+
+    // # Header
+    // The name is either on the same line as "Spell 3" or the line above it
+    while index < lines.len() {
+        if let Some(captures) = TITLE_REGEX.captures(lines[index]) {
+            let name: &str = captures[1].trim();
+            card.spell_name = if name.is_empty() && index > 0 {
+                lines[index - 1].to_string()
+            } else {
+                name.to_string()
+            };
+            card.spell_type = match &captures[2] {
+                "Cantrip" => SpellType::Cantrip,
+                "Focus"   => SpellType::Focus,
+                "Ritual"  => SpellType::Ritual,
+                _         => SpellType::Spell,
+            };
+            card.spell_level = captures[3].parse::<u8>().unwrap_or(1);
+            index += 1;
+            break;
+        }
+        index += 1;
+    }
+    if card.spell_name.is_empty() {
+        warnings.push(localization.t("Could not find a line like \"<name> Spell <rank>\""));
+        index = 0;
+    } else if index < lines.len() && field_label(lines[index]).is_none() {
+        // The line after the title holds the traits
+        card.traits = lines[index].split_whitespace().map(|t| t.to_string()).collect();
+        index += 1;
+    }
+
+    parse_body(&lines[index..], &mut card, &mut warnings, localization);
+    ParsedStatBlock { card, warnings }
+}
+
+// Parses the fields, effect, degrees of success and heightening below the header
+pub fn parse_body(lines: &[&str], card: &mut SpellCard, warnings: &mut Vec<String>, localization: Localization) {
+    let mut effect_paragraphs: Vec<String> = Vec::new();
+
+    for line in lines.iter() {
+        if let Some(captures) = HEIGHTENED_REGEX.captures(line) {
+            let level: u8 = captures[2].parse::<u8>().unwrap_or(1);
+            let effect: String = captures[3].to_string();
+            card.heightened.push(if captures[1].is_empty() {
+                Heightened::Single(level, effect)
+            } else {
+                Heightened::Repeat(level, effect)
+            });
+        } else if let Some(degree) = DEGREE_LABELS.iter().find(|d| starts_with_label(line, d)) {
+            let effect: String = line[degree.len()..].trim().to_string();
+            card.roll_effect.push(match *degree {
+                "Critical Success" => RollResult::CriticalSuccess(effect),
+                "Success"          => RollResult::Success(effect),
+                "Failure"          => RollResult::Failure(effect),
+                _                  => RollResult::CriticalFailure(effect),
+            });
+        } else if field_label(line).is_some() {
+            for segment in line.split(';').map(|s| s.trim()).filter(|s| !s.is_empty()) {
+                match field_label(segment) {
+                    Some(label) => parse_field(label, segment[label.len()..].trim(), card, &mut effect_paragraphs, warnings, localization),
+                    None => effect_paragraphs.push(segment.to_string()),
+                }
+            }
+        } else {
+            effect_paragraphs.push(line.to_string());
+        }
+    }
+
+    card.spell_effect = effect_paragraphs.join("\n");
+    card.overview.sort();
+    card.roll_effect.sort();
+}
+
+fn starts_with_label(line: &str, label: &str) -> bool {
+    line.starts_with(label) && line[label.len()..].chars().next().is_none_or(|c| c.is_whitespace())
+}
+
+fn field_label(line: &str) -> Option<&'static str> {
+    FIELD_LABELS.iter().find(|label| starts_with_label(line, label)).copied()
+}
+
+fn parse_field(label: &str, value: &str, card: &mut SpellCard, effect_paragraphs: &mut Vec<String>, warnings: &mut Vec<String>, localization: Localization) {
+    match label {
+        "Traditions" | "Tradition" => {
+            card.traditions = value.split(',').map(|t| t.trim().to_string()).filter(|t| !t.is_empty()).collect();
+        },
+        "Cast" => match parse_cast_time(value) {
+            Some(cast_time) => card.cast_time = cast_time,
+            None => warnings.push(localization.tf("Unrecognised cast time \"{}\"", &[&value])),
+        },
+        "Range" => match parse_feet(value) {
+            Some(range) => card.overview.push(SpellOverview::Range(range)),
+            None => {
+                warnings.push(localization.tf("Range \"{}\" does not fit the range field, added to the effect instead", &[&value]));
+                effect_paragraphs.insert(0, format!("Range {}", value));
+            },
+        },
+        "Area" => match parse_area(value) {
+            Some(area) => card.overview.push(SpellOverview::Area(area)),
+            None => warnings.push(localization.tf("Unrecognised area \"{}\"", &[&value])),
+        },
+        "Targets" => card.overview.push(SpellOverview::Targets(value.to_string())),
+        "Defense" | "Defence" | "Saving Throw" => match parse_defence(value) {
+            Some(defence) => {
+                card.overview.push(SpellOverview::Defence(defence));
+                card.basic_save = value.to_lowercase().contains("basic");
+            },
+            None => warnings.push(localization.tf("Unrecognised defense \"{}\"", &[&value])),
+        },
+        "Duration" => card.overview.push(SpellOverview::Duration(value.to_string())),
+        "Trigger" | "Requirements" => effect_paragraphs.push(format!("{} {}", label, value)),
+        "Source" => card.source = SpellSource::from_reference(value),
+        // PFS notes and class-specific labels are not shown on cards
+        _ => (),
+    }
+}
+
+pub fn parse_cast_time(value: &str) -> Option<CastTime> {
+    // Drop components such as "(concentrate, manipulate)"
+    let value: &str = value.split('(').next().unwrap_or(value).trim();
+    let lowered: String = value.to_lowercase();
+    let actions: Vec<u8> = ACTION_REGEX.captures_iter(&lowered).map(|c| {
+        match c.get(1).or(c.get(2)).map(|m| m.as_str()) {
+            Some("two") | Some("2") => 2,
+            Some("three") | Some("3") => 3,
+            _ => 1,
+        }
+    }).collect();
+    match actions.as_slice() {
+        [min, max, ..] if min != max => Some(CastTime::Range(*min.min(max), *min.max(max))),
+        [1, ..] => Some(CastTime::Single),
+        [2, ..] => Some(CastTime::Double),
+        [3, ..] => Some(CastTime::Triple),
+        _ if lowered.contains("reaction") => Some(CastTime::Reaction),
+        _ if lowered.contains("free") => Some(CastTime::Free),
+        _ if !value.is_empty() => Some(CastTime::Longer(value.to_string())),
+        _ => None,
+    }
+}
+
+// Reads the first distance such as "30 feet" or "20-foot" and keeps it if it fits the card's range
+pub fn parse_feet(value: &str) -> Option<u8> {
+    FEET_REGEX.captures(value).and_then(|c| c[1].parse::<u8>().ok())
+}
+
+pub fn parse_area(value: &str) -> Option<Area> {
+    let lowered: String = value.to_lowercase();
+    let distances: Vec<(u8, Option<String>)> = FEET_REGEX.captures_iter(&lowered)
+        .filter_map(|c| Some((c[1].parse::<u8>().ok()?, c.get(2).map(|m| m.as_str().to_string()))))
+        .collect();
+    let first: u8 = distances.first()?.0;
+    if lowered.contains("burst") {
+        Some(Area::Burst(first))
+    } else if lowered.contains("cone") {
+        Some(Area::Cone(first))
+    } else if lowered.contains("emanation") {
+        Some(Area::Emanation(first))
+    } else if lowered.contains("line") {
+        let length: u8 = distances.iter().find(|(_, kind)| kind.as_deref() != Some("wide")).map(|(d, _)| *d).unwrap_or(first);
+        let width: Option<u8> = distances.iter().find(|(_, kind)| kind.as_deref() == Some("wide")).map(|(d, _)| *d);
+        Some(Area::Line(length, width))
+    } else {
+        None
+    }
+}
+
+pub fn parse_defence(value: &str) -> Option<Defence> {
+    let lowered: String = value.to_lowercase();
+    if lowered.contains("fortitude") {
+        Some(Defence::Fortitude)
+    } else if lowered.contains("reflex") {
+        Some(Defence::Reflex)
+    } else if lowered.contains("will") {
+        Some(Defence::Will)
+    } else if lowered.split_whitespace().any(|w| w == "ac") || lowered.contains("armor class") || lowered.contains("armour class") {
+        Some(Defence::ArmourClass)
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app::localization::Locale;
+
+    const FIREBALL: &str = "Fireball Spell 3
+Concentrate Fire Manipulate
+Source Player Core pg. 332
+Traditions arcane, primal
+Range 500 feet; Area 20-foot burst
+Defense basic Reflex
+A roaring blast of fire detonates at a spot you designate, dealing 6d6 fire damage.
+Heightened (+1) The damage increases by 2d6.";
+
+    const ELECTRIC_ARC: &str = "Electric Arc
+Cantrip 1
+Cantrip Electricity Evocation
+Source Core Rulebook pg. 334
+Traditions arcane, primal
+Cast [two-actions] somatic, verbal
+Targets 1 or 2 creatures
+Saving Throw basic Reflex
+An arc of lightning leaps from one target to another. You deal 1d4 electricity damage plus your spellcasting ability modifier.
+Heightened (+1) The damage increases by 1d4.";
+
+    const FEAR: &str = "Fear Spell 1
+Concentrate Emotion Fear Manipulate Mental
+Source Player Core pg. 330
+Traditions arcane, divine, occult, primal
+Range 30 feet; Targets 1 creature
+Defense Will; Duration varies
+You plant fear in the target; it must attempt a Will save.
+Critical Success The target is unaffected.
+Success The target is frightened 1.
+Failure The target is frightened 2.
+Critical Failure The target is frightened 3 and fleeing for 1 round.
+Heightened (3rd) You can target up to five creatures.";
+
+    fn parse(text: &str) -> ParsedStatBlock {
+        parse_stat_block(text, Localization::default())
+    }
+
+    #[test]
+    fn parses_a_remaster_spell() {
+        let ParsedStatBlock { card, warnings } = parse(FIREBALL);
+        assert_eq!(card.spell_name, "Fireball");
+        assert!(card.spell_type == SpellType::Spell);
+        assert_eq!(card.spell_level, 3);
+        assert_eq!(card.traits, vec!["Concentrate", "Fire", "Manipulate"]);
+        assert_eq!(card.traditions, vec!["arcane", "primal"]);
+        assert!(card.source == SpellSource::Published { book: "Player Core".to_string(), page: Some(332), remaster: true });
+        assert!(card.overview == vec![SpellOverview::Area(Area::Burst(20)), SpellOverview::Defence(Defence::Reflex)]);
+        assert!(card.basic_save);
+        assert!(card.heightened == vec![Heightened::Repeat(1, "The damage increases by 2d6.".to_string())]);
+        // 500 feet does not fit the card's range, so it is kept in the text
+        assert_eq!(warnings, vec!["Range \"500 feet\" does not fit the range field, added to the effect instead"]);
+        assert!(card.spell_effect.starts_with("Range 500 feet\nA roaring blast of fire"));
+    }
+
+    #[test]
+    fn parses_a_legacy_cantrip_with_the_name_on_its_own_line() {
+        let ParsedStatBlock { card, warnings } = parse(ELECTRIC_ARC);
+        assert!(warnings.is_empty());
+        assert_eq!(card.spell_name, "Electric Arc");
+        assert!(card.spell_type == SpellType::Cantrip);
+        assert!(card.cast_time == CastTime::Double);
+        assert!(card.source == SpellSource::Published { book: "Core Rulebook".to_string(), page: Some(334), remaster: false });
+        assert!(card.overview == vec![SpellOverview::Targets("1 or 2 creatures".to_string()), SpellOverview::Defence(Defence::Reflex)]);
+    }
+
+    #[test]
+    fn parses_degrees_of_success_and_fixed_heightening() {
+        let ParsedStatBlock { card, warnings } = parse(FEAR);
+        assert!(warnings.is_empty());
+        assert!(card.roll_effect == vec![
+            RollResult::CriticalSuccess("The target is unaffected.".to_string()),
+            RollResult::Success("The target is frightened 1.".to_string()),
+            RollResult::Failure("The target is frightened 2.".to_string()),
+            RollResult::CriticalFailure("The target is frightened 3 and fleeing for 1 round.".to_string()),
+        ]);
+        assert!(card.heightened == vec![Heightened::Single(3, "You can target up to five creatures.".to_string())]);
+        assert!(card.overview.contains(&SpellOverview::Range(30)));
+        assert!(card.overview.contains(&SpellOverview::Duration("varies".to_string())));
+        assert!(!card.basic_save);
+        assert_eq!(card.spell_effect, "You plant fear in the target; it must attempt a Will save.");
+    }
+
+    #[test]
+    fn warns_about_malformed_input() {
+        let ParsedStatBlock { card, warnings } = parse("Just some notes\nArea a small room\nDefense Perception");
+        assert_eq!(warnings, vec![
+            "Could not find a line like \"<name> Spell <rank>\"",
+            "Unrecognised area \"a small room\"",
+            "Unrecognised defense \"Perception\"",
+        ]);
+        assert!(card.spell_name.is_empty());
+        assert_eq!(card.spell_effect, "Just some notes");
+
+        let ParsedStatBlock { card, warnings } = parse("");
+        assert_eq!(warnings.len(), 1);
+        assert!(card.overview.is_empty());
+    }
+
+    #[test]
+    fn warnings_follow_the_chosen_language() {
+        let german: Localization = Localization { locale: Locale::German, ..Default::default() };
+        let ParsedStatBlock { warnings, .. } = parse_stat_block("Spark Spell 1\nArea a small room", german);
+        assert_eq!(warnings, vec!["Unbekannter Bereich \"a small room\""]);
+    }
+
+    #[test]
+    fn parses_cast_times() {
+        assert!(parse_cast_time("[one-action] to [three-actions]") == Some(CastTime::Range(1, 3)));
+        assert!(parse_cast_time("[three-actions] (manipulate)") == Some(CastTime::Triple));
+        assert!(parse_cast_time("1 action") == Some(CastTime::Single));
+        assert!(parse_cast_time("[reaction] concentrate") == Some(CastTime::Reaction));
+        assert!(parse_cast_time("[free-action]") == Some(CastTime::Free));
+        assert!(parse_cast_time("10 minutes") == Some(CastTime::Longer("10 minutes".to_string())));
+        assert!(parse_cast_time("").is_none());
+    }
+
+    #[test]
+    fn parses_distances_and_areas() {
+        assert_eq!(parse_feet("120 feet"), Some(120));
+        assert_eq!(parse_feet("touch"), None);
+        assert_eq!(parse_feet("1 mile"), None);
+        assert!(parse_area("15-foot cone") == Some(Area::Cone(15)));
+        assert!(parse_area("10-foot emanation") == Some(Area::Emanation(10)));
+        assert!(parse_area("60-foot line") == Some(Area::Line(60, None)));
+        assert!(parse_area("30-foot long, 10-foot wide line") == Some(Area::Line(30, Some(10))));
+        assert!(parse_area("20-foot cube").is_none());
+    }
+
+    #[test]
+    fn parses_defences() {
+        assert!(parse_defence("basic Fortitude") == Some(Defence::Fortitude));
+        assert!(parse_defence("Will") == Some(Defence::Will));
+        assert!(parse_defence("AC") == Some(Defence::ArmourClass));
+        assert!(parse_defence("Armor Class") == Some(Defence::ArmourClass));
+        assert!(parse_defence("Perception").is_none());
+    }
+}
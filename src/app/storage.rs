@@ -0,0 +1,441 @@
+use std::cell::{RefCell, RefMut};
+use std::collections::HashMap;
+use std::rc::Rc;
+use js_sys::{Function, Promise};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use wasm_bindgen::closure::Closure;
+use wasm_bindgen::{JsCast, JsValue};
+use wasm_bindgen_futures::JsFuture;
+use web_sys::{window, DomException, IdbDatabase, IdbFactory, IdbObjectStore, IdbOpenDbRequest, IdbRequest, IdbTransaction, IdbTransactionMode, StorageEstimate};
+
+const DATABASE_NAME: &str = "pf2e-spell-book";
+const DATABASE_VERSION: u32 = 1;
+const OBJECT_STORE: &str = "documents";
+// Moved out of local storage the first time IndexedDB is used
+const MIGRATED_KEYS: [&str; 3] = ["SpellBooks", "SpellBook", "SpellBookBackups"];
+// Browsers allow around 5 MB of local storage per origin, stored as UTF-16
+const LOCAL_STORAGE_LIMIT_BYTES: usize = 5 * 1024 * 1024;
+const STORAGE_WARNING_RATIO: f64 = 0.8;
+// Name of the error browsers give when a write would go over the storage quota
+const QUOTA_EXCEEDED: &str = "QuotaExceededError";
+
+// Documents are stored as JSON strings under a key, whichever backend holds them
+#[allow(async_fn_in_trait)]
+pub trait KeyValueStore {
+    async fn get_raw(&self, key: &str) -> Result<Option<String>, String>;
+    async fn set_raw(&self, key: &str, value: &str) -> Result<(), String>;
+    async fn delete(&self, key: &str) -> Result<(), String>;
+
+    async fn get<T: DeserializeOwned>(&self, key: &str) -> Result<Option<T>, String> {
+        match self.get_raw(key).await? {
+            Some(raw) => serde_json::from_str::<T>(&raw).map(Some).map_err(|err| err.to_string()),
+            None => Ok(None),
+        }
+    }
+
+    async fn set<T: Serialize + ?Sized>(&self, key: &str, value: &T) -> Result<(), String> {
+        let raw: String = serde_json::to_string(value).map_err(|err| err.to_string())?;
+        self.set_raw(key, &raw).await
+    }
+}
+
+// Errors keep the name of the exception, so a full storage can be told apart from other failures
+fn js_error(value: JsValue) -> String {
+    if let Some(exception) = value.dyn_ref::<DomException>() {
+        return format!("{}: {}", exception.name(), exception.message());
+    }
+    if let Some(error) = value.dyn_ref::<js_sys::Error>() {
+        return format!("{}: {}", String::from(error.name()), String::from(error.message()));
+    }
+    value.as_string().unwrap_or_else(|| format!("{:?}", value))
+}
+
+// Whether a write failed because the storage is full, rather than for another reason
+pub fn is_quota_error(error: &str) -> bool {
+    error.starts_with(QUOTA_EXCEEDED)
+}
+
+fn local_storage() -> Option<web_sys::Storage> {
+    window().and_then(|w| w.local_storage().ok().flatten())
+}
+
+// # Local storage
+#[derive(PartialEq, Clone)]
+pub struct LocalStore;
+
+impl KeyValueStore for LocalStore {
+    async fn get_raw(&self, key: &str) -> Result<Option<String>, String> {
+        local_storage().ok_or("Local storage is not available")?.get_item(key).map_err(js_error)
+    }
+
+    async fn set_raw(&self, key: &str, value: &str) -> Result<(), String> {
+        local_storage().ok_or("Local storage is not available")?.set_item(key, value).map_err(js_error)
+    }
+
+    async fn delete(&self, key: &str) -> Result<(), String> {
+        local_storage().ok_or("Local storage is not available")?.remove_item(key).map_err(js_error)
+    }
+}
+
+// Bytes used by everything the app keeps in local storage
+pub fn local_storage_usage() -> usize {
+    let Some(storage) = local_storage() else {
+        return 0;
+    };
+    let length: u32 = storage.length().unwrap_or(0);
+    (0..length)
+        .filter_map(|index| storage.key(index).ok().flatten())
+        .map(|key| {
+            let value: String = storage.get_item(&key).ok().flatten().unwrap_or_default();
+            (key.encode_utf16().count() + value.encode_utf16().count()) * 2
+        })
+        .sum()
+}
+
+// Bytes used and available to the app, whichever backend holds its documents
+#[derive(PartialEq, Clone, Copy)]
+pub struct StorageUsage {
+    pub used: f64,
+    pub quota: f64,
+}
+
+impl StorageUsage {
+    pub fn nearly_full(&self) -> bool {
+        self.quota > 0.0 && self.used >= self.quota * STORAGE_WARNING_RATIO
+    }
+}
+
+// What the browser reports for IndexedDB and everything else of this origin
+async fn storage_estimate() -> Option<StorageUsage> {
+    let promise: Promise = window()?.navigator().storage().estimate().ok()?;
+    let estimate: StorageEstimate = JsFuture::from(promise).await.ok()?.unchecked_into::<StorageEstimate>();
+    Some(StorageUsage { used: estimate.get_usage()?, quota: estimate.get_quota()? })
+}
+
+// # IndexedDB
+#[derive(PartialEq, Clone)]
+pub struct IndexedDbStore {
+    database: IdbDatabase,
+}
+
+// Turns the callbacks of an IndexedDB request into a future of its result
+async fn request_result(request: &IdbRequest) -> Result<JsValue, String> {
+    let promise: Promise = Promise::new(&mut |resolve: Function, reject: Function| {
+        let succeeded: IdbRequest = request.clone();
+        let on_success: JsValue = Closure::once_into_js(move || {
+            _ = resolve.call1(&JsValue::NULL, &succeeded.result().unwrap_or(JsValue::UNDEFINED));
+        });
+        let failed: IdbRequest = request.clone();
+        let on_error: JsValue = Closure::once_into_js(move || {
+            let error: JsValue = failed.error().ok().flatten().map(JsValue::from).unwrap_or_else(|| JsValue::from_str("The IndexedDB request failed"));
+            _ = reject.call1(&JsValue::NULL, &error);
+        });
+        request.set_onsuccess(Some(on_success.unchecked_ref()));
+        request.set_onerror(Some(on_error.unchecked_ref()));
+    });
+    JsFuture::from(promise).await.map_err(js_error)
+}
+
+// Resolves once the transaction's writes are stored, a full quota only shows up here and not on the request.
+// Made before the request is awaited, so the completion cannot be missed
+fn transaction_committed(transaction: &IdbTransaction) -> JsFuture {
+    let promise: Promise = Promise::new(&mut |resolve: Function, reject: Function| {
+        let aborted: IdbTransaction = transaction.clone();
+        let on_complete: JsValue = Closure::once_into_js(move || {
+            _ = resolve.call0(&JsValue::NULL);
+        });
+        let on_abort: JsValue = Closure::once_into_js(move || {
+            let error: JsValue = aborted.error().map(JsValue::from).unwrap_or_else(|| JsValue::from_str("The IndexedDB transaction was aborted"));
+            _ = reject.call1(&JsValue::NULL, &error);
+        });
+        transaction.set_oncomplete(Some(on_complete.unchecked_ref()));
+        transaction.set_onabort(Some(on_abort.unchecked_ref()));
+    });
+    JsFuture::from(promise)
+}
+
+impl IndexedDbStore {
+    pub async fn open() -> Result<IndexedDbStore, String> {
+        let factory: IdbFactory = window()
+            .and_then(|w| w.indexed_db().ok().flatten())
+            .ok_or("IndexedDB is not available")?;
+        let request: IdbOpenDbRequest = factory.open_with_u32(DATABASE_NAME, DATABASE_VERSION).map_err(js_error)?;
+        let upgrading: IdbOpenDbRequest = request.clone();
+        let on_upgrade: JsValue = Closure::once_into_js(move || {
+            let database: Option<IdbDatabase> = upgrading.result().ok().and_then(|r| r.dyn_into::<IdbDatabase>().ok());
+            if let Some(database) = database {
+                if !database.object_store_names().contains(OBJECT_STORE) {
+                    _ = database.create_object_store(OBJECT_STORE);
+                }
+            }
+        });
+        request.set_onupgradeneeded(Some(on_upgrade.unchecked_ref()));
+        let database: IdbDatabase = request_result(&request).await?.dyn_into::<IdbDatabase>().map_err(js_error)?;
+        Ok(IndexedDbStore { database })
+    }
+
+    fn transaction(&self, mode: IdbTransactionMode) -> Result<IdbTransaction, String> {
+        self.database.transaction_with_str_and_mode(OBJECT_STORE, mode).map_err(js_error)
+    }
+
+    fn object_store(&self, mode: IdbTransactionMode) -> Result<IdbObjectStore, String> {
+        self.transaction(mode)?.object_store(OBJECT_STORE).map_err(js_error)
+    }
+
+}
+
+// Copies what older versions kept in `old` to `new`, a key is only cleared from `old` once its copy is written
+pub async fn migrate_keys(old: &impl KeyValueStore, new: &impl KeyValueStore) {
+    for key in MIGRATED_KEYS {
+        if let Ok(Some(raw)) = old.get_raw(key).await {
+            let already_migrated: bool = new.get_raw(key).await.ok().flatten().is_some();
+            if !already_migrated && new.set_raw(key, &raw).await.is_ok() {
+                _ = old.delete(key).await;
+            }
+        }
+    }
+}
+
+impl KeyValueStore for IndexedDbStore {
+    async fn get_raw(&self, key: &str) -> Result<Option<String>, String> {
+        let request: IdbRequest = self.object_store(IdbTransactionMode::Readonly)?.get(&JsValue::from_str(key)).map_err(js_error)?;
+        Ok(request_result(&request).await?.as_string())
+    }
+
+    async fn set_raw(&self, key: &str, value: &str) -> Result<(), String> {
+        let transaction: IdbTransaction = self.transaction(IdbTransactionMode::Readwrite)?;
+        let committed: JsFuture = transaction_committed(&transaction);
+        let request: IdbRequest = transaction.object_store(OBJECT_STORE)
+            .and_then(|object_store| object_store.put_with_key(&JsValue::from_str(value), &JsValue::from_str(key)))
+            .map_err(js_error)?;
+        request_result(&request).await?;
+        committed.await.map(|_| ()).map_err(js_error)
+    }
+
+    async fn delete(&self, key: &str) -> Result<(), String> {
+        let request: IdbRequest = self.object_store(IdbTransactionMode::Readwrite)?.delete(&JsValue::from_str(key)).map_err(js_error)?;
+        request_result(&request).await.map(|_| ())
+    }
+}
+
+// # Memory
+// Nothing outlives the page, used when the browser offers no storage and for testing
+#[derive(Clone, Default)]
+pub struct MemoryStore {
+    entries: Rc<RefCell<HashMap<String, String>>>,
+    // Bytes the entries may take up, unlimited unless set for a test
+    quota: Option<usize>,
+    read_error: Option<String>,
+    write_error: Option<String>,
+}
+
+#[cfg(test)]
+impl MemoryStore {
+    // The same entries, with writes that fail like a browser storage holding at most `quota` bytes
+    pub fn with_quota(&self, quota: usize) -> MemoryStore {
+        MemoryStore { quota: Some(quota), ..self.clone() }
+    }
+
+    // The same entries, with every read failing with `error`
+    pub fn with_read_error(&self, error: &str) -> MemoryStore {
+        MemoryStore { read_error: Some(error.to_string()), ..self.clone() }
+    }
+
+    // The same entries, with every write failing with `error`
+    pub fn with_write_error(&self, error: &str) -> MemoryStore {
+        MemoryStore { write_error: Some(error.to_string()), ..self.clone() }
+    }
+}
+
+impl PartialEq for MemoryStore {
+    fn eq(&self, other: &MemoryStore) -> bool {
+        Rc::ptr_eq(&self.entries, &other.entries)
+    }
+}
+
+impl KeyValueStore for MemoryStore {
+    async fn get_raw(&self, key: &str) -> Result<Option<String>, String> {
+        if let Some(error) = &self.read_error {
+            return Err(error.clone());
+        }
+        Ok(self.entries.borrow().get(key).cloned())
+    }
+
+    async fn set_raw(&self, key: &str, value: &str) -> Result<(), String> {
+        if let Some(error) = &self.write_error {
+            return Err(error.clone());
+        }
+        let mut entries: RefMut<HashMap<String, String>> = self.entries.borrow_mut();
+        if let Some(quota) = self.quota {
+            let used: usize = entries.iter().filter(|(k, _)| *k != key).map(|(k, v)| k.len() + v.len()).sum();
+            if used + key.len() + value.len() > quota {
+                return Err(format!("{}: the quota has been exceeded", QUOTA_EXCEEDED));
+            }
+        }
+        entries.insert(key.to_string(), value.to_string());
+        Ok(())
+    }
+
+    async fn delete(&self, key: &str) -> Result<(), String> {
+        self.entries.borrow_mut().remove(key);
+        Ok(())
+    }
+}
+
+// # Backend
+#[derive(PartialEq, Clone)]
+pub enum Backend {
+    IndexedDb(IndexedDbStore),
+    Local(LocalStore),
+    Memory(MemoryStore),
+}
+
+impl Backend {
+    // IndexedDB when the browser offers it, then local storage, then memory only
+    pub async fn open() -> Backend {
+        match IndexedDbStore::open().await {
+            Ok(store) => {
+                migrate_keys(&LocalStore, &store).await;
+                Backend::IndexedDb(store)
+            },
+            Err(_) if local_storage().is_some() => Backend::Local(LocalStore),
+            Err(_) => Backend::Memory(MemoryStore::default()),
+        }
+    }
+
+    // Local storage has a small fixed quota, IndexedDB shares what the browser gives the origin
+    pub async fn usage(&self) -> Option<StorageUsage> {
+        match self {
+            Backend::IndexedDb(_) => storage_estimate().await,
+            Backend::Local(_)     => Some(StorageUsage { used: local_storage_usage() as f64, quota: LOCAL_STORAGE_LIMIT_BYTES as f64 }),
+            Backend::Memory(_)    => None,
+        }
+    }
+}
+
+impl KeyValueStore for Backend {
+    async fn get_raw(&self, key: &str) -> Result<Option<String>, String> {
+        match self {
+            Backend::IndexedDb(store) => store.get_raw(key).await,
+            Backend::Local(store)     => store.get_raw(key).await,
+            Backend::Memory(store)    => store.get_raw(key).await,
+        }
+    }
+
+    async fn set_raw(&self, key: &str, value: &str) -> Result<(), String> {
+        match self {
+            Backend::IndexedDb(store) => store.set_raw(key, value).await,
+            Backend::Local(store)     => store.set_raw(key, value).await,
+            Backend::Memory(store)    => store.set_raw(key, value).await,
+        }
+    }
+
+    async fn delete(&self, key: &str) -> Result<(), String> {
+        match self {
+            Backend::IndexedDb(store) => store.delete(key).await,
+            Backend::Local(store)     => store.delete(key).await,
+            Backend::Memory(store)    => store.delete(key).await,
+        }
+    }
+}
+
+// Memory stores finish right away, so polling until ready is enough to run them in tests
+#[cfg(test)]
+pub fn block_on<F: std::future::Future>(future: F) -> F::Output {
+    let mut future = std::pin::pin!(future);
+    let mut context: std::task::Context = std::task::Context::from_waker(std::task::Waker::noop());
+    loop {
+        if let std::task::Poll::Ready(output) = future.as_mut().poll(&mut context) {
+            return output;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn memory_store_round_trips() {
+        let store: MemoryStore = MemoryStore::default();
+        block_on(async {
+            assert_eq!(store.get_raw("missing").await, Ok(None));
+            store.set("SpellBooks", &vec!["Fireball", "Fear"]).await.unwrap();
+            assert_eq!(store.get::<Vec<String>>("SpellBooks").await, Ok(Some(vec!["Fireball".to_string(), "Fear".to_string()])));
+            store.set("SpellBooks", &vec!["Fear"]).await.unwrap();
+            assert_eq!(store.get_raw("SpellBooks").await, Ok(Some("[\"Fear\"]".to_string())));
+            store.delete("SpellBooks").await.unwrap();
+            assert_eq!(store.get_raw("SpellBooks").await, Ok(None));
+            // Deleting what is not there is not an error
+            assert_eq!(store.delete("SpellBooks").await, Ok(()));
+        });
+    }
+
+    #[test]
+    fn unreadable_documents_are_errors() {
+        let store: MemoryStore = MemoryStore::default();
+        block_on(async {
+            store.set_raw("SpellBooks", "not json").await.unwrap();
+            assert!(store.get::<Vec<String>>("SpellBooks").await.is_err());
+        });
+    }
+
+    #[test]
+    fn clones_share_their_entries() {
+        let store: MemoryStore = MemoryStore::default();
+        let clone: MemoryStore = store.clone();
+        block_on(store.set_raw("SpellBook", "{}")).unwrap();
+        assert_eq!(block_on(clone.get_raw("SpellBook")), Ok(Some("{}".to_string())));
+        assert!(store == clone);
+        assert!(store != MemoryStore::default());
+    }
+
+    #[test]
+    fn migration_moves_the_old_spellbook() {
+        let old: MemoryStore = MemoryStore::default();
+        let new: MemoryStore = MemoryStore::default();
+        block_on(async {
+            old.set_raw("SpellBook", "{\"spells\":[]}").await.unwrap();
+            old.set_raw("Terminology", "\"Legacy\"").await.unwrap();
+            migrate_keys(&old, &new).await;
+            assert_eq!(new.get_raw("SpellBook").await, Ok(Some("{\"spells\":[]}".to_string())));
+            assert_eq!(old.get_raw("SpellBook").await, Ok(None));
+            // Settings stay where they are
+            assert_eq!(old.get_raw("Terminology").await, Ok(Some("\"Legacy\"".to_string())));
+            assert_eq!(new.get_raw("Terminology").await, Ok(None));
+        });
+    }
+
+    #[test]
+    fn migration_keeps_what_the_new_store_already_has() {
+        let old: MemoryStore = MemoryStore::default();
+        let new: MemoryStore = MemoryStore::default();
+        block_on(async {
+            old.set_raw("SpellBooks", "[\"old\"]").await.unwrap();
+            new.set_raw("SpellBooks", "[\"new\"]").await.unwrap();
+            migrate_keys(&old, &new).await;
+            assert_eq!(new.get_raw("SpellBooks").await, Ok(Some("[\"new\"]".to_string())));
+            assert_eq!(old.get_raw("SpellBooks").await, Ok(Some("[\"old\"]".to_string())));
+        });
+    }
+
+    #[test]
+    fn writes_over_the_quota_are_quota_errors() {
+        let store: MemoryStore = MemoryStore::default().with_quota(20);
+        block_on(async {
+            store.set_raw("SpellBooks", "[1,2,3]").await.unwrap();
+            let error: String = store.set_raw("SpellBooks", "[1,2,3,4,5,6,7,8,9]").await.unwrap_err();
+            assert!(is_quota_error(&error));
+            assert_eq!(store.get_raw("SpellBooks").await, Ok(Some("[1,2,3]".to_string())));
+        });
+        assert!(!is_quota_error("The IndexedDB request failed"));
+    }
+
+    #[test]
+    fn warns_when_storage_is_nearly_full() {
+        assert!(!StorageUsage { used: 1.0, quota: 10.0 }.nearly_full());
+        assert!(StorageUsage { used: 8.0, quota: 10.0 }.nearly_full());
+        assert!(!StorageUsage { used: 8.0, quota: 0.0 }.nearly_full());
+    }
+}
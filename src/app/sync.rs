@@ -0,0 +1,683 @@
+use std::cell::RefCell;
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::rc::Rc;
+use gloo_net::http::{Request, Response};
+use gloo_storage::{LocalStorage, Storage};
+use js_sys::Date;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use wasm_bindgen::JsCast;
+use web_sys::{EventTarget, HtmlInputElement};
+use yew::platform::spawn_local;
+use yew::prelude::*;
+
+use super::localization::Localization;
+use super::spell_book::SpellBookProps;
+use super::spell_card::SpellCard;
+use super::storage::{Backend, KeyValueStore};
+
+const SETTINGS_KEY: &str = "SyncSettings";
+const LOCAL_EDITS_KEY: &str = "SyncLocalEdits";
+// Library as it was after the last sync, the common base both sides changed from
+const BASE_KEY: &str = "SyncBase";
+const PUSH_ATTEMPTS: usize = 3;
+
+#[derive(PartialEq, Clone, Default, Serialize, Deserialize)]
+pub struct SyncSettings {
+    pub server_url: String,
+    pub library_id: String,
+}
+
+impl SyncSettings {
+    pub fn load() -> SyncSettings {
+        LocalStorage::get::<SyncSettings>(SETTINGS_KEY).unwrap_or_default()
+    }
+
+    pub fn save(&self) {
+        _ = LocalStorage::set(SETTINGS_KEY, self);
+    }
+
+    pub fn is_configured(&self) -> bool {
+        !self.server_url.trim().is_empty() && !self.library_id.trim().is_empty()
+    }
+
+    fn library_url(&self) -> String {
+        format!("{}/api/libraries/{}", self.server_url.trim().trim_end_matches('/'), self.library_id.trim())
+    }
+}
+
+#[derive(PartialEq, Clone, Serialize, Deserialize)]
+pub struct SyncedCard {
+    // Empty for cards synced before cards had ids
+    #[serde(default)]
+    pub id: String,
+    pub book: String,
+    pub spell_name: String,
+    // None once the card has been deleted, so the deletion reaches other devices
+    pub card: Option<SpellCard>,
+    pub revision: u32,
+    // Milliseconds since the epoch of the edit that made this revision
+    pub modified_at: f64,
+}
+
+#[derive(PartialEq, Clone, Default, Serialize, Deserialize)]
+pub struct SyncedLibrary {
+    #[serde(default)]
+    pub version: u64,
+    // Book names and characters, the cards are kept separately
+    pub books: Vec<SpellBookProps>,
+    pub cards: Vec<SyncedCard>,
+}
+
+// A card edited on this device and on another one since the last sync
+#[derive(PartialEq, Clone)]
+pub struct SyncConflict {
+    pub book: String,
+    pub spell_name: String,
+    // The version both devices started from, when it is known
+    pub base: Option<SpellCard>,
+    pub local: Option<SpellCard>,
+    pub remote: Option<SpellCard>,
+    pub kept_local: bool,
+}
+
+#[derive(PartialEq, Clone)]
+pub struct SyncOutcome {
+    pub books: Vec<SpellBookProps>,
+    pub library: SyncedLibrary,
+    pub conflicts: Vec<SyncConflict>,
+    // The local books the outcome was merged from, to tell which cards were edited while the sync ran
+    pub synced_from: Vec<SpellBookProps>,
+}
+
+// Cards are told apart by their id, or by their book and name when they have none
+fn name_key(book: &str, spell_name: &str) -> String {
+    format!("{}\u{1f}{}", book, spell_name)
+}
+
+fn card_key(book: &str, card: &SpellCard) -> String {
+    if card.id.is_empty() { name_key(book, &card.spell_name) } else { card.id.clone() }
+}
+
+fn record_key(synced: &SyncedCard) -> String {
+    if synced.id.is_empty() { name_key(&synced.book, &synced.spell_name) } else { synced.id.clone() }
+}
+
+fn book_cards(books: &[SpellBookProps]) -> HashMap<String, (String, SpellCard)> {
+    books.iter()
+        .flat_map(|book| book.spells.iter().map(move |card| {
+            (card_key(&book.display_name(), card), (book.display_name(), card.clone()))
+        }))
+        .collect()
+}
+
+// Every device gave ids to its existing cards on its own, so a card the server does not know by id
+// takes the id the server has for the card with the same name in the same book
+fn adopt_remote_ids(local_books: &[SpellBookProps], remote: &SyncedLibrary) -> Vec<SpellBookProps> {
+    let remote_ids: HashSet<&str> = remote.cards.iter().map(|synced| synced.id.as_str()).filter(|id| !id.is_empty()).collect();
+    let mut taken: HashSet<String> = local_books.iter().flat_map(|book| book.spells.iter().map(|card| card.id.clone())).collect();
+    local_books.iter().map(|book| {
+        let mut book: SpellBookProps = book.clone();
+        let name: String = book.display_name();
+        for card in book.spells.iter_mut().filter(|card| !remote_ids.contains(card.id.as_str())) {
+            let remote_match: Option<&SyncedCard> = remote.cards.iter().find(|synced| {
+                synced.card.is_some() && !synced.id.is_empty() && !taken.contains(&synced.id)
+                    && synced.book == name && synced.spell_name == card.spell_name
+            });
+            if let Some(synced) = remote_match {
+                card.id = synced.id.clone();
+                taken.insert(card.id.clone());
+            }
+        }
+        book
+    }).collect()
+}
+
+// # Local edit times
+// Last-writer-wins needs to know when a card was changed here, so edits are timestamped as they are saved
+fn load_local_edits() -> HashMap<String, f64> {
+    LocalStorage::get::<HashMap<String, f64>>(LOCAL_EDITS_KEY).unwrap_or_default()
+}
+
+pub fn note_local_edits(old: &[SpellBookProps], new: &[SpellBookProps]) {
+    if !SyncSettings::load().is_configured() {
+        return;
+    }
+    let old_cards: HashMap<String, (String, SpellCard)> = book_cards(old);
+    let new_cards: HashMap<String, (String, SpellCard)> = book_cards(new);
+    let now: f64 = Date::now();
+    let mut edits: HashMap<String, f64> = load_local_edits();
+    for key in old_cards.keys().chain(new_cards.keys()) {
+        if old_cards.get(key).map(|(_, card)| card) != new_cards.get(key).map(|(_, card)| card) {
+            edits.insert(key.clone(), now);
+        }
+    }
+    _ = LocalStorage::set(LOCAL_EDITS_KEY, edits);
+}
+
+// The edits still to push once a sync has pushed `synced`, an edit made while it ran has a newer time and stays
+fn remaining_edits(mut edits: HashMap<String, f64>, synced: &HashMap<String, f64>) -> HashMap<String, f64> {
+    edits.retain(|key, edited_at| synced.get(key) != Some(edited_at));
+    edits
+}
+
+// # Merging
+// Three-way merge of every card against the last synced state: a card changed on one side takes that
+// side's version, a card changed on both sides is a conflict settled by the most recent edit
+pub fn merge(local_books: &[SpellBookProps], base: &SyncedLibrary, remote: &SyncedLibrary, edits: &HashMap<String, f64>, now: f64) -> SyncOutcome {
+    let local_books: Vec<SpellBookProps> = adopt_remote_ids(local_books, remote);
+    let local: HashMap<String, (String, SpellCard)> = book_cards(&local_books);
+    // Records from before cards had ids take the id of the local card with their book and name
+    let ids_by_name: HashMap<String, String> = local.iter()
+        .map(|(key, (book, card))| (name_key(book, &card.spell_name), key.clone()))
+        .collect();
+    let index = |library: &SyncedLibrary| -> HashMap<String, SyncedCard> {
+        library.cards.iter().map(|synced| {
+            let mut synced: SyncedCard = synced.clone();
+            if let Some(id) = ids_by_name.get(&name_key(&synced.book, &synced.spell_name)).filter(|_| synced.id.is_empty()) {
+                synced.id = id.clone();
+                if let Some(card) = synced.card.as_mut() {
+                    card.id = id.clone();
+                }
+            }
+            (record_key(&synced), synced)
+        }).collect()
+    };
+    let base_cards: HashMap<String, SyncedCard> = index(base);
+    let remote_cards: HashMap<String, SyncedCard> = index(remote);
+
+    let keys: BTreeSet<String> = local.keys().chain(base_cards.keys()).chain(remote_cards.keys()).cloned().collect();
+    let mut results: HashMap<String, Option<SpellCard>> = HashMap::new();
+    let mut records: Vec<SyncedCard> = Vec::new();
+    let mut conflicts: Vec<SyncConflict> = Vec::new();
+
+    for key in &keys {
+        let local_entry: Option<&(String, SpellCard)> = local.get(key);
+        let local_card: Option<&SpellCard> = local_entry.map(|(_, card)| card);
+        let base_entry: Option<&SyncedCard> = base_cards.get(key);
+        let remote_entry: Option<&SyncedCard> = remote_cards.get(key);
+        let remote_card: Option<&SpellCard> = remote_entry.and_then(|r| r.card.as_ref());
+        let (id, book, spell_name): (String, String, String) = match (local_entry, remote_entry.or(base_entry)) {
+            (Some((book, card)), _) => (card.id.clone(), book.clone(), card.spell_name.clone()),
+            (None, Some(synced))    => (synced.id.clone(), synced.book.clone(), synced.spell_name.clone()),
+            (None, None)            => continue,
+        };
+
+        let local_changed: bool = local_card != base_entry.and_then(|b| b.card.as_ref());
+        let remote_changed: bool = remote_entry.map(|r| r.revision) != base_entry.map(|b| b.revision);
+        let next_revision: u32 = base_entry.map(|b| b.revision).max(remote_entry.map(|r| r.revision)).unwrap_or(0) + 1;
+        // A card deleted here is pushed without its content, so other devices drop it too
+        let local_record = || SyncedCard {
+            id: id.clone(),
+            book: book.clone(),
+            spell_name: spell_name.clone(),
+            card: local_card.cloned(),
+            revision: next_revision,
+            modified_at: edits.get(key).copied().unwrap_or(now),
+        };
+
+        let (result, record): (Option<SpellCard>, Option<SyncedCard>) = match (local_changed, remote_changed) {
+            (false, false) => (local_card.cloned(), remote_entry.cloned().or_else(|| local_card.map(|_| local_record()))),
+            (true, false)  => (local_card.cloned(), Some(local_record())),
+            (false, true)  => (remote_card.cloned(), remote_entry.cloned()),
+            (true, true) if local_card == remote_card => (local_card.cloned(), remote_entry.cloned()),
+            (true, true) => {
+                let local_time: f64 = edits.get(key).copied().unwrap_or(0.0);
+                let remote_time: f64 = remote_entry.map(|r| r.modified_at).unwrap_or(0.0);
+                let kept_local: bool = local_time >= remote_time;
+                conflicts.push(SyncConflict {
+                    book: book.clone(),
+                    spell_name: spell_name.clone(),
+                    base: base_entry.and_then(|b| b.card.clone()),
+                    local: local_card.cloned(),
+                    remote: remote_card.cloned(),
+                    kept_local,
+                });
+                if kept_local {
+                    (local_card.cloned(), Some(local_record()))
+                } else {
+                    (remote_card.cloned(), remote_entry.cloned())
+                }
+            },
+        };
+        records.extend(record);
+        results.insert(key.clone(), result);
+    }
+
+    // Local books keep their order and card order, cards only known remotely are added at the end
+    let mut books: Vec<SpellBookProps> = local_books.iter().map(|book| {
+        let mut book: SpellBookProps = book.clone();
+        let name: String = book.display_name();
+        book.spells = book.spells.iter()
+            .filter_map(|card| results.get(&card_key(&name, card)).cloned().unwrap_or(Some(card.clone())))
+            .collect();
+        book
+    }).collect();
+    for key in &keys {
+        if local.contains_key(key) {
+            continue;
+        }
+        if let (Some(Some(card)), Some(remote_entry)) = (results.get(key), remote_cards.get(key)) {
+            let book_index: usize = match books.iter().position(|b| b.display_name() == remote_entry.book) {
+                Some(index) => index,
+                None => {
+                    let details: SpellBookProps = remote.books.iter()
+                        .find(|b| b.display_name() == remote_entry.book)
+                        .cloned()
+                        .unwrap_or_else(|| SpellBookProps { name: remote_entry.book.clone(), ..Default::default() });
+                    books.push(SpellBookProps { spells: Vec::new(), ..details });
+                    books.len() - 1
+                },
+            };
+            books[book_index].spells.push(card.clone());
+        }
+    }
+
+    let library: SyncedLibrary = SyncedLibrary {
+        version: remote.version,
+        books: books.iter().map(|book| SpellBookProps { spells: Vec::new(), ..book.clone() }).collect(),
+        cards: records,
+    };
+    SyncOutcome { books, library, conflicts, synced_from: local_books }
+}
+
+// Cards edited, added or deleted here while a sync ran are put back over its outcome.
+// Their edit times are still noted, so the next sync pushes them
+pub fn keep_edits_made_during_sync(outcome: &SyncOutcome, current: &[SpellBookProps]) -> Vec<SpellBookProps> {
+    // Cards given the server's id by the sync are known by that id here too
+    let current: Vec<SpellBookProps> = adopt_remote_ids(current, &outcome.library);
+    if current == outcome.synced_from {
+        return outcome.books.clone();
+    }
+    let before: HashMap<String, (String, SpellCard)> = book_cards(&outcome.synced_from);
+    let after: HashMap<String, (String, SpellCard)> = book_cards(&current);
+    let edited: HashSet<&String> = before.keys().chain(after.keys())
+        .filter(|key| before.get(*key).map(|(_, card)| card) != after.get(*key).map(|(_, card)| card))
+        .collect();
+
+    let mut books: Vec<SpellBookProps> = outcome.books.iter().map(|book| {
+        let mut book: SpellBookProps = book.clone();
+        let name: String = book.display_name();
+        book.spells = book.spells.iter()
+            .filter_map(|card| {
+                let key: String = card_key(&name, card);
+                if edited.contains(&key) { after.get(&key).map(|(_, card)| card.clone()) } else { Some(card.clone()) }
+            })
+            .collect();
+        book
+    }).collect();
+    let in_outcome: HashMap<String, (String, SpellCard)> = book_cards(&outcome.books);
+    for book in &current {
+        let name: String = book.display_name();
+        let added: Vec<SpellCard> = book.spells.iter()
+            .filter(|card| {
+                let key: String = card_key(&name, card);
+                edited.contains(&key) && !in_outcome.contains_key(&key)
+            })
+            .cloned()
+            .collect();
+        if added.is_empty() {
+            continue;
+        }
+        match books.iter_mut().find(|b| b.display_name() == name) {
+            Some(existing) => existing.spells.extend(added),
+            None => books.push(SpellBookProps { spells: added, ..book.clone() }),
+        }
+    }
+    books
+}
+
+// # Server
+async fn fetch_library(settings: &SyncSettings, localization: Localization) -> Result<SyncedLibrary, String> {
+    let response: Response = Request::get(&settings.library_url()).send().await.map_err(|err| err.to_string())?;
+    match response.status() {
+        200 => response.json::<SyncedLibrary>().await.map_err(|err| err.to_string()),
+        404 => Ok(SyncedLibrary::default()),
+        status => Err(localization.tf("The server answered with status {}", &[&status])),
+    }
+}
+
+// Returns the stored library, or None when another device pushed first
+async fn push_library(settings: &SyncSettings, base_version: u64, library: &SyncedLibrary, localization: Localization) -> Result<Option<SyncedLibrary>, String> {
+    let response: Response = Request::put(&settings.library_url())
+        .json(&json!({ "base_version": base_version, "library": library }))
+        .map_err(|err| err.to_string())?
+        .send().await
+        .map_err(|err| err.to_string())?;
+    match response.status() {
+        200 => response.json::<SyncedLibrary>().await.map(Some).map_err(|err| err.to_string()),
+        409 => Ok(None),
+        status => Err(localization.tf("The server answered with status {}", &[&status])),
+    }
+}
+
+pub async fn sync_books(store: &Backend, settings: &SyncSettings, local_books: &[SpellBookProps], localization: Localization) -> Result<SyncOutcome, String> {
+    let base: SyncedLibrary = store.get::<SyncedLibrary>(BASE_KEY).await.ok().flatten().unwrap_or_default();
+    for _ in 0..PUSH_ATTEMPTS {
+        let remote: SyncedLibrary = fetch_library(settings, localization).await?;
+        let edits: HashMap<String, f64> = load_local_edits();
+        let outcome: SyncOutcome = merge(local_books, &base, &remote, &edits, Date::now());
+        if let Some(stored) = push_library(settings, remote.version, &outcome.library, localization).await? {
+            store.set(BASE_KEY, &stored).await?;
+            _ = LocalStorage::set(LOCAL_EDITS_KEY, remaining_edits(load_local_edits(), &edits));
+            return Ok(SyncOutcome { library: stored, ..outcome });
+        }
+    }
+    Err(localization.t("Other devices kept syncing at the same time, please try again"))
+}
+
+// The cards as of the last sync, the versions this device and the others last agreed on
+pub async fn last_synced_cards(store: &Backend) -> Vec<SpellCard> {
+    let base: SyncedLibrary = store.get::<SyncedLibrary>(BASE_KEY).await.ok().flatten().unwrap_or_default();
+    base.cards.into_iter().filter_map(|synced| synced.card).collect()
+}
+
+#[derive(PartialEq, Properties)]
+pub struct SyncPanelProps {
+    pub store: Backend,
+    pub books: Vec<SpellBookProps>,
+    pub on_synced: Callback<SyncOutcome>,
+    #[prop_or_default]
+    pub on_review: Callback<Vec<SyncConflict>>,
+}
+
+#[derive(PartialEq, Clone)]
+enum SyncStatus {
+    Idle,
+    Syncing,
+    Done(Vec<SyncConflict>),
+    Failed(String),
+}
+
+#[function_component]
+pub fn SyncPanel(props: &SyncPanelProps) -> Html {
+    let SyncPanelProps {
+        store,
+        books,
+        on_synced,
+        on_review
+    } = props;
+
+    let localization: Localization = use_context::<Localization>().unwrap_or_default();
+
+    let settings: UseStateHandle<SyncSettings> = use_state(SyncSettings::load);
+    let status: UseStateHandle<SyncStatus> = use_state(|| SyncStatus::Idle);
+    // A sync can outlast several renders, its outcome goes to the callback of the latest one
+    let latest_on_synced: Rc<RefCell<Callback<SyncOutcome>>> = use_mut_ref(|| on_synced.clone());
+    *latest_on_synced.borrow_mut() = on_synced.clone();
+
+    // # Callback functions
+    let settings_change = |update: fn(&mut SyncSettings, String)| {
+        let settings: UseStateHandle<SyncSettings> = settings.clone();
+
+        Callback::from(move |e: Event| {
+            let target: Option<EventTarget> = e.target();
+
+            let input: Option<HtmlInputElement> = target.and_then(|t: EventTarget| t.dyn_into::<HtmlInputElement>().ok());
+            if let Some(input) = input {
+                let mut new_settings: SyncSettings = (*settings).clone();
+                update(&mut new_settings, input.value().trim().to_string());
+                new_settings.save();
+                settings.set(new_settings)
+            }
+        })
+    };
+    let sync_now: Callback<MouseEvent> = {
+        let settings: UseStateHandle<SyncSettings> = settings.clone();
+        let status: UseStateHandle<SyncStatus> = status.clone();
+        let store: Backend = store.clone();
+        let books: Vec<SpellBookProps> = books.clone();
+        let latest_on_synced: Rc<RefCell<Callback<SyncOutcome>>> = latest_on_synced.clone();
+
+        Callback::from(move |_| {
+            let settings: SyncSettings = (*settings).clone();
+            let status: UseStateHandle<SyncStatus> = status.clone();
+            let store: Backend = store.clone();
+            let books: Vec<SpellBookProps> = books.clone();
+            let latest_on_synced: Rc<RefCell<Callback<SyncOutcome>>> = latest_on_synced.clone();
+            status.set(SyncStatus::Syncing);
+            spawn_local(async move {
+                match sync_books(&store, &settings, &books, localization).await {
+                    Ok(outcome) => {
+                        status.set(SyncStatus::Done(outcome.conflicts.clone()));
+                        let on_synced: Callback<SyncOutcome> = latest_on_synced.borrow().clone();
+                        on_synced.emit(outcome)
+                    },
+                    Err(err) => status.set(SyncStatus::Failed(err)),
+                }
+            });
+        })
+    };
+
+    html! {
+        <div class="sync-panel">
+            <b>{localization.t("Sync")}</b>
+            <label>
+                {localization.t("Server:")}
+                <input
+                    onchange={settings_change(|s, v| s.server_url = v)}
+                    type="url"
+                    placeholder={"http://localhost:8787"}
+                    value={settings.server_url.clone()}
+                />
+            </label>
+            <label>
+                {localization.t("Library id:")}
+                <input
+                    onchange={settings_change(|s, v| s.library_id = v)}
+                    type="text"
+                    placeholder={localization.t("Same on every device")}
+                    value={settings.library_id.clone()}
+                />
+            </label>
+            <button onclick={sync_now} disabled={!settings.is_configured() || *status == SyncStatus::Syncing}>
+                {localization.t("Sync now")}
+            </button>
+            { match &*status {
+                SyncStatus::Idle => html!{},
+                SyncStatus::Syncing => html!{<span>{localization.t("Syncing…")}</span>},
+                SyncStatus::Done(conflicts) if conflicts.is_empty() => html!{<span>{localization.t("Up to date")}</span>},
+                SyncStatus::Done(conflicts) => html!{
+                    <div class="import-warnings">
+                        {localization.t("Edited on this device and another one since the last sync:")}
+                        <ul>
+                            { conflicts.iter().map(|conflict| {
+                                let kept: String = if conflict.kept_local {
+                                    localization.t("kept this device's version")
+                                } else {
+                                    localization.t("took the other device's version")
+                                };
+                                html!{<li>{localization.tf("{} ({}): {}", &[&conflict.spell_name, &conflict.book, &kept])}</li>}
+                            }).collect::<Html>() }
+                        </ul>
+                        <button onclick={on_review.reform({
+                            let conflicts: Vec<SyncConflict> = conflicts.clone();
+                            move |_: MouseEvent| conflicts.clone()
+                        })}>
+                            {localization.t("Review field by field")}
+                        </button>
+                    </div>
+                },
+                SyncStatus::Failed(err) => html!{<span class="import-warnings">{err}</span>},
+            } }
+        </div>
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn card(id: &str, spell_name: &str, spell_effect: &str) -> SpellCard {
+        SpellCard { id: id.to_string(), spell_name: spell_name.to_string(), spell_effect: spell_effect.to_string(), ..SpellCard::empty() }
+    }
+
+    fn book(spells: Vec<SpellCard>) -> SpellBookProps {
+        SpellBookProps { spells, name: "Wizard".to_string(), character: None }
+    }
+
+    fn synced(card: &SpellCard, revision: u32, modified_at: f64) -> SyncedCard {
+        SyncedCard {
+            id: card.id.clone(),
+            book: "Wizard".to_string(),
+            spell_name: card.spell_name.clone(),
+            card: Some(card.clone()),
+            revision,
+            modified_at,
+        }
+    }
+
+    fn deleted(card: &SpellCard, revision: u32) -> SyncedCard {
+        SyncedCard { card: None, ..synced(card, revision, 0.0) }
+    }
+
+    fn library(version: u64, cards: Vec<SyncedCard>) -> SyncedLibrary {
+        SyncedLibrary { version, books: vec![book(Vec::new())], cards }
+    }
+
+    fn record<'a>(outcome: &'a SyncOutcome, id: &str) -> &'a SyncedCard {
+        outcome.library.cards.iter().find(|synced| synced.id == id).unwrap()
+    }
+
+    #[test]
+    fn unchanged_cards_stay_as_they_are() {
+        let fireball: SpellCard = card("a", "Fireball", "Boom");
+        let base: SyncedLibrary = library(1, vec![synced(&fireball, 1, 10.0)]);
+        let outcome: SyncOutcome = merge(&[book(vec![fireball.clone()])], &base, &base, &HashMap::new(), 100.0);
+        assert!(outcome.books == vec![book(vec![fireball])]);
+        assert!(outcome.conflicts.is_empty());
+        assert!(outcome.library.cards == base.cards);
+    }
+
+    #[test]
+    fn a_card_edited_here_only_is_pushed_as_the_next_revision() {
+        let fireball: SpellCard = card("a", "Fireball", "Boom");
+        let edited: SpellCard = card("a", "Fireball", "Bigger boom");
+        let base: SyncedLibrary = library(1, vec![synced(&fireball, 1, 10.0)]);
+        let edits: HashMap<String, f64> = HashMap::from([("a".to_string(), 50.0)]);
+        let outcome: SyncOutcome = merge(&[book(vec![edited.clone()])], &base, &base, &edits, 100.0);
+        assert!(outcome.books == vec![book(vec![edited.clone()])]);
+        assert!(outcome.conflicts.is_empty());
+        assert!(*record(&outcome, "a") == synced(&edited, 2, 50.0));
+    }
+
+    #[test]
+    fn a_card_edited_elsewhere_only_is_taken_from_the_server() {
+        let fireball: SpellCard = card("a", "Fireball", "Boom");
+        let edited: SpellCard = card("a", "Fireball", "Bigger boom");
+        let base: SyncedLibrary = library(1, vec![synced(&fireball, 1, 10.0)]);
+        let remote: SyncedLibrary = library(2, vec![synced(&edited, 2, 60.0)]);
+        let outcome: SyncOutcome = merge(&[book(vec![fireball])], &base, &remote, &HashMap::new(), 100.0);
+        assert!(outcome.books == vec![book(vec![edited.clone()])]);
+        assert!(outcome.conflicts.is_empty());
+        assert!(*record(&outcome, "a") == synced(&edited, 2, 60.0));
+    }
+
+    #[test]
+    fn a_card_edited_on_both_sides_keeps_the_latest_edit_and_lists_the_conflict() {
+        let fireball: SpellCard = card("a", "Fireball", "Boom");
+        let local: SpellCard = card("a", "Fireball", "Local boom");
+        let remote_card: SpellCard = card("a", "Fireball", "Remote boom");
+        let base: SyncedLibrary = library(1, vec![synced(&fireball, 1, 10.0)]);
+        let remote: SyncedLibrary = library(2, vec![synced(&remote_card, 2, 60.0)]);
+
+        let later_here: HashMap<String, f64> = HashMap::from([("a".to_string(), 70.0)]);
+        let outcome: SyncOutcome = merge(&[book(vec![local.clone()])], &base, &remote, &later_here, 100.0);
+        assert!(outcome.books == vec![book(vec![local.clone()])]);
+        assert!(*record(&outcome, "a") == synced(&local, 3, 70.0));
+        assert_eq!(outcome.conflicts.len(), 1);
+        let conflict: &SyncConflict = &outcome.conflicts[0];
+        assert!(conflict.kept_local);
+        assert!(conflict.base == Some(fireball.clone()));
+        assert!(conflict.local == Some(local.clone()));
+        assert!(conflict.remote == Some(remote_card.clone()));
+
+        let earlier_here: HashMap<String, f64> = HashMap::from([("a".to_string(), 20.0)]);
+        let outcome: SyncOutcome = merge(&[book(vec![local])], &base, &remote, &earlier_here, 100.0);
+        assert!(outcome.books == vec![book(vec![remote_card.clone()])]);
+        assert!(*record(&outcome, "a") == synced(&remote_card, 2, 60.0));
+        assert_eq!(outcome.conflicts.len(), 1);
+        assert!(!outcome.conflicts[0].kept_local);
+    }
+
+    #[test]
+    fn the_same_edit_on_both_sides_is_no_conflict() {
+        let fireball: SpellCard = card("a", "Fireball", "Boom");
+        let edited: SpellCard = card("a", "Fireball", "Bigger boom");
+        let base: SyncedLibrary = library(1, vec![synced(&fireball, 1, 10.0)]);
+        let remote: SyncedLibrary = library(2, vec![synced(&edited, 2, 60.0)]);
+        let edits: HashMap<String, f64> = HashMap::from([("a".to_string(), 70.0)]);
+        let outcome: SyncOutcome = merge(&[book(vec![edited.clone()])], &base, &remote, &edits, 100.0);
+        assert!(outcome.books == vec![book(vec![edited])]);
+        assert!(outcome.conflicts.is_empty());
+    }
+
+    #[test]
+    fn a_card_deleted_here_is_pushed_as_deleted() {
+        let fireball: SpellCard = card("a", "Fireball", "Boom");
+        let fear: SpellCard = card("b", "Fear", "Boo");
+        let base: SyncedLibrary = library(1, vec![synced(&fireball, 1, 10.0), synced(&fear, 1, 10.0)]);
+        let edits: HashMap<String, f64> = HashMap::from([("a".to_string(), 50.0)]);
+        let outcome: SyncOutcome = merge(&[book(vec![fear.clone()])], &base, &base, &edits, 100.0);
+        assert!(outcome.books == vec![book(vec![fear])]);
+        let record: &SyncedCard = record(&outcome, "a");
+        assert!(record.card.is_none());
+        assert_eq!(record.revision, 2);
+    }
+
+    #[test]
+    fn a_card_deleted_elsewhere_is_removed_here() {
+        let fireball: SpellCard = card("a", "Fireball", "Boom");
+        let fear: SpellCard = card("b", "Fear", "Boo");
+        let base: SyncedLibrary = library(1, vec![synced(&fireball, 1, 10.0), synced(&fear, 1, 10.0)]);
+        let remote: SyncedLibrary = library(2, vec![deleted(&fireball, 2), synced(&fear, 1, 10.0)]);
+        let outcome: SyncOutcome = merge(&[book(vec![fireball, fear.clone()])], &base, &remote, &HashMap::new(), 100.0);
+        assert!(outcome.books == vec![book(vec![fear])]);
+        assert!(outcome.conflicts.is_empty());
+        assert!(record(&outcome, "a").card.is_none());
+    }
+
+    #[test]
+    fn new_cards_from_either_side_end_up_in_both() {
+        let fireball: SpellCard = card("a", "Fireball", "Boom");
+        let local_new: SpellCard = card("b", "Fear", "Boo");
+        let remote_new: SpellCard = card("c", "Heal", "Better");
+        let base: SyncedLibrary = library(1, vec![synced(&fireball, 1, 10.0)]);
+        let remote: SyncedLibrary = library(2, vec![synced(&fireball, 1, 10.0), synced(&remote_new, 1, 60.0)]);
+        let edits: HashMap<String, f64> = HashMap::from([("b".to_string(), 50.0)]);
+        let outcome: SyncOutcome = merge(&[book(vec![fireball.clone(), local_new.clone()])], &base, &remote, &edits, 100.0);
+        assert!(outcome.books == vec![book(vec![fireball, local_new.clone(), remote_new.clone()])]);
+        assert!(outcome.conflicts.is_empty());
+        assert!(*record(&outcome, "b") == synced(&local_new, 1, 50.0));
+        assert!(*record(&outcome, "c") == synced(&remote_new, 1, 60.0));
+    }
+
+    #[test]
+    fn cards_edited_while_a_sync_ran_are_put_back_over_its_outcome() {
+        let fireball: SpellCard = card("a", "Fireball", "Boom");
+        let fear: SpellCard = card("b", "Fear", "Boo");
+        let heal: SpellCard = card("c", "Heal", "Better");
+        let remote_fireball: SpellCard = card("a", "Fireball", "Remote boom");
+        let edited_fear: SpellCard = card("b", "Fear", "Boo, edited while syncing");
+        let added: SpellCard = card("d", "Light", "Added while syncing");
+        let base: SyncedLibrary = library(1, vec![synced(&fireball, 1, 10.0), synced(&fear, 1, 10.0), synced(&heal, 1, 10.0)]);
+        let remote: SyncedLibrary = library(2, vec![synced(&remote_fireball, 2, 60.0), synced(&fear, 1, 10.0), synced(&heal, 1, 10.0)]);
+        let outcome: SyncOutcome = merge(&[book(vec![fireball.clone(), fear.clone(), heal.clone()])], &base, &remote, &HashMap::new(), 100.0);
+        assert!(outcome.books == vec![book(vec![remote_fireball.clone(), fear.clone(), heal.clone()])]);
+
+        // Unchanged since the sync started, so the outcome is taken as it is
+        assert!(keep_edits_made_during_sync(&outcome, &outcome.synced_from) == outcome.books);
+
+        let current: Vec<SpellBookProps> = vec![book(vec![fireball, edited_fear.clone(), added.clone()])];
+        let books: Vec<SpellBookProps> = keep_edits_made_during_sync(&outcome, &current);
+        assert!(books == vec![book(vec![remote_fireball, edited_fear, added])]);
+    }
+
+    #[test]
+    fn edits_made_during_a_sync_are_kept() {
+        let synced: HashMap<String, f64> = HashMap::from([("a".to_string(), 50.0), ("b".to_string(), 60.0)]);
+        let current: HashMap<String, f64> = HashMap::from([("a".to_string(), 50.0), ("b".to_string(), 90.0), ("c".to_string(), 95.0)]);
+        let remaining: HashMap<String, f64> = remaining_edits(current, &synced);
+        assert_eq!(remaining, HashMap::from([("b".to_string(), 90.0), ("c".to_string(), 95.0)]));
+    }
+}
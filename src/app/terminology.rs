@@ -0,0 +1,171 @@
+use gloo_storage::{LocalStorage, Storage};
+use serde::{Deserialize, Serialize};
+use wasm_bindgen::JsCast;
+use web_sys::{EventTarget, HtmlSelectElement};
+use yew::prelude::*;
+
+use super::localization::Localization;
+use super::spell_card::{Defence, Heightened, SpellOverview};
+
+const TERMINOLOGY_KEY: &str = "Terminology";
+
+// Spells the remaster renamed, legacy name first
+const RENAMED_SPELLS: [(&str, &str); 23] = [
+    ("Acid Splash", "Caustic Blast"),
+    ("Burning Hands", "Breathe Fire"),
+    ("Chill Touch", "Void Warp"),
+    ("Color Spray", "Dizzying Colors"),
+    ("Dimension Door", "Translocate"),
+    ("Disrupt Undead", "Vitality Lash"),
+    ("Feather Fall", "Gentle Landing"),
+    ("Finger of Death", "Execute"),
+    ("Flaming Sphere", "Floating Flame"),
+    ("Ghost Sound", "Figment"),
+    ("Hideous Laughter", "Laughing Fit"),
+    ("Mage Armor", "Mystic Armor"),
+    ("Mage Hand", "Telekinetic Hand"),
+    ("Magic Fang", "Runic Body"),
+    ("Magic Missile", "Force Barrage"),
+    ("Magic Weapon", "Runic Weapon"),
+    ("Meteor Swarm", "Falling Stars"),
+    ("Produce Flame", "Ignition"),
+    ("Ray of Frost", "Frostbite"),
+    ("Remove Curse", "Cleanse Affliction"),
+    ("Sound Burst", "Noise Blast"),
+    ("Tanglefoot", "Tangle Vine"),
+    ("True Strike", "Sure Strike"),
+];
+
+// The spell's name from the other side of the remaster, if it was renamed
+pub fn renamed_spell(name: &str) -> Option<&'static str> {
+    let name: &str = name.trim();
+    RENAMED_SPELLS.iter().find_map(|(legacy, remaster)| {
+        if legacy.eq_ignore_ascii_case(name) {
+            Some(*remaster)
+        } else if remaster.eq_ignore_ascii_case(name) {
+            Some(*legacy)
+        } else {
+            None
+        }
+    })
+}
+
+// Whether two names are the same spell, possibly from either side of the remaster
+pub fn same_spell_name(a: &str, b: &str) -> bool {
+    a.trim().eq_ignore_ascii_case(b.trim()) || renamed_spell(a).is_some_and(|renamed| renamed.eq_ignore_ascii_case(b.trim()))
+}
+
+// Wording of the rules the cards are shown in, the remaster's or the Core Rulebook's
+#[derive(PartialEq, Eq, Clone, Copy, Default, Serialize, Deserialize)]
+pub enum Terminology {
+    #[default]
+    Remaster,
+    Legacy,
+}
+
+impl Terminology {
+    pub fn load() -> Terminology {
+        LocalStorage::get::<Terminology>(TERMINOLOGY_KEY).unwrap_or_default()
+    }
+
+    pub fn save(&self) {
+        _ = LocalStorage::set(TERMINOLOGY_KEY, self);
+    }
+
+    // What a spell's power is measured in
+    pub fn rank_label(&self) -> &'static str {
+        match self {
+            Terminology::Remaster => "Rank",
+            Terminology::Legacy   => "Level",
+        }
+    }
+
+    // Title of the card's header, such as "Spell rank 3"
+    pub fn rank_title(&self, localization: Localization, spell_level: u8) -> String {
+        match self {
+            Terminology::Remaster => localization.tf("Spell rank {}", &[&spell_level]),
+            Terminology::Legacy   => localization.tf("Spell level {}", &[&spell_level]),
+        }
+    }
+
+    // Label of an overview element, only the defence was worded differently
+    pub fn overview_label(&self, elem: &SpellOverview) -> String {
+        match (self, elem) {
+            (Terminology::Remaster, SpellOverview::Defence(_))                    => "Defense".to_string(),
+            (Terminology::Legacy,   SpellOverview::Defence(Defence::ArmourClass)) => "Attack".to_string(),
+            (Terminology::Legacy,   SpellOverview::Defence(_))                    => "Saving Throw".to_string(),
+            _ => elem.to_string(),
+        }
+    }
+
+    pub fn defence_label(&self) -> &'static str {
+        match self {
+            Terminology::Remaster => "Defense",
+            Terminology::Legacy   => "Saving throw",
+        }
+    }
+
+    pub fn defence_name(&self, defence: &Defence) -> String {
+        match (self, defence) {
+            (Terminology::Legacy, Defence::ArmourClass) => "Spell attack vs. AC".to_string(),
+            _ => defence.to_string(),
+        }
+    }
+
+    // How heightening is explained when hovering over it
+    pub fn heightened_title(&self, localization: Localization, heightened: &Heightened) -> String {
+        match (self, heightened) {
+            (Terminology::Remaster, Heightened::Repeat(1, _))    => localization.t("For every rank above the spell's own"),
+            (Terminology::Remaster, Heightened::Repeat(step, _)) => localization.tf("For every {} ranks above the spell's own", &[step]),
+            (Terminology::Remaster, Heightened::Single(lvl, _))  => localization.tf("When cast at rank {} or higher", &[lvl]),
+            (Terminology::Legacy,   Heightened::Repeat(1, _))    => localization.t("For every spell level above the spell's own"),
+            (Terminology::Legacy,   Heightened::Repeat(step, _)) => localization.tf("For every {} spell levels above the spell's own", &[step]),
+            (Terminology::Legacy,   Heightened::Single(lvl, _))  => localization.tf("When cast at spell level {} or higher", &[lvl]),
+        }
+    }
+}
+
+#[derive(PartialEq, Properties)]
+pub struct TerminologySettingProps {
+    pub terminology: Terminology,
+    pub on_change: Callback<Terminology>,
+}
+
+#[function_component]
+pub fn TerminologySetting(props: &TerminologySettingProps) -> Html {
+    let TerminologySettingProps {
+        terminology,
+        on_change
+    } = props;
+
+    let localization: Localization = use_context::<Localization>().unwrap_or_default();
+
+    // # Callback functions
+    let terminology_change: Callback<Event> = {
+        let on_change: Callback<Terminology> = on_change.clone();
+
+        Callback::from(move |e: Event| {
+            let target: Option<EventTarget> = e.target();
+
+            let select: Option<HtmlSelectElement> = target.and_then(|t: EventTarget| t.dyn_into::<HtmlSelectElement>().ok());
+            if let Some(select) = select {
+                let terminology: Terminology = match select.value().as_str() {
+                    "legacy" => Terminology::Legacy,
+                    _        => Terminology::Remaster,
+                };
+                terminology.save();
+                on_change.emit(terminology)
+            }
+        })
+    };
+
+    html! {
+        <label>
+            {localization.t("Terminology:")}
+            <select onchange={terminology_change}>
+                <option value={"remaster"} selected={*terminology == Terminology::Remaster}>{localization.t("Remaster (Player Core)")}</option>
+                <option value={"legacy"} selected={*terminology == Terminology::Legacy}>{localization.t("Legacy (Core Rulebook)")}</option>
+            </select>
+        </label>
+    }
+}
@@ -0,0 +1,198 @@
+use web_sys::window;
+use yew::prelude::*;
+
+use super::file_io::{download_file, file_name_for};
+use super::localization::Localization;
+use super::spell_card::{CastTime, SpellCard, SpellSource};
+use super::terminology::Terminology;
+
+// Action glyphs written out the way Archives of Nethys and Foundry spell them in text
+pub fn cast_time_text(cast_time: &CastTime) -> String {
+    let action = |actions: &u8| match actions {
+        1 => "[one-action]",
+        2 => "[two-actions]",
+        _ => "[three-actions]",
+    };
+    match cast_time {
+        CastTime::Free     => "[free-action]".to_string(),
+        CastTime::Reaction => "[reaction]".to_string(),
+        CastTime::Single   => action(&1).to_string(),
+        CastTime::Double   => action(&2).to_string(),
+        CastTime::Triple   => action(&3).to_string(),
+        CastTime::Longer(duration) => duration.clone(),
+        CastTime::Range(min, max) => format!("{} to {}", action(min), action(max)),
+    }
+}
+
+pub fn card_to_markdown(card: &SpellCard, terminology: Terminology, localization: Localization) -> String {
+    let mut sections: Vec<String> = Vec::new();
+
+    // # Header
+    let title: String = if card.link.is_empty() {
+        card.spell_name.clone()
+    } else {
+        format!("[{}]({})", card.spell_name, card.link)
+    };
+    sections.push(format!("### {} {}\n**{} {}**", title, cast_time_text(&card.cast_time), localization.t(&card.spell_type.to_string()), card.spell_level));
+
+    // # Middle
+    let mut middle: Vec<String> = Vec::new();
+    if !card.traits.is_empty() {
+        middle.push(card.traits.iter().map(|t| format!("`{}`", t)).collect::<Vec<String>>().join(" "));
+    }
+    if !card.traditions.is_empty() {
+        middle.push(format!("**{}:** {}", localization.t("Traditions"), card.traditions.join(", ")));
+    }
+    middle.extend(card.overview.iter().map(|elem| format!("**{}:** {}", localization.overview_label(terminology, elem), localization.overview_value(terminology, elem, card.basic_save))));
+    if !middle.is_empty() {
+        // Two trailing spaces keep the lines apart without starting new paragraphs
+        sections.push(middle.join("  \n"));
+    }
+
+    // # Bottom
+    sections.push(card.spell_effect.split('\n').filter(|p| !p.trim().is_empty()).collect::<Vec<&str>>().join("\n\n"));
+    if !card.roll_effect.is_empty() {
+        sections.push(card.roll_effect.iter().map(|rr| format!("**{}:** {}", localization.t(&rr.to_string()), rr.effect())).collect::<Vec<String>>().join("  \n"));
+    }
+    if !card.heightened.is_empty() {
+        sections.push(card.heightened.iter().map(|h| format!("**{}:** {}", localization.heightened(h), h.effect())).collect::<Vec<String>>().join("  \n"));
+    }
+
+    // # Footer
+    if card.source != SpellSource::Unknown {
+        sections.push(format!("*{}: {}*", localization.t("Source"), localization.source(&card.source)));
+    }
+    sections.join("\n\n---\n\n")
+}
+
+pub fn card_to_plain_text(card: &SpellCard, terminology: Terminology, localization: Localization) -> String {
+    let mut sections: Vec<String> = Vec::new();
+
+    // # Header
+    let mut header: String = format!(
+        "{} {} ({} {})",
+        card.spell_name.to_uppercase(), cast_time_text(&card.cast_time), localization.t(&card.spell_type.to_string()), card.spell_level
+    );
+    if !card.link.is_empty() {
+        header.push_str(&format!("\n{}", card.link));
+    }
+    sections.push(header);
+
+    // # Middle
+    let mut middle: Vec<String> = Vec::new();
+    if !card.traits.is_empty() {
+        middle.push(format!("{}: {}", localization.t("Traits"), card.traits.join(", ")));
+    }
+    if !card.traditions.is_empty() {
+        middle.push(format!("{}: {}", localization.t("Traditions"), card.traditions.join(", ")));
+    }
+    middle.extend(card.overview.iter().map(|elem| format!("{}: {}", localization.overview_label(terminology, elem), localization.overview_value(terminology, elem, card.basic_save))));
+    if !middle.is_empty() {
+        sections.push(middle.join("\n"));
+    }
+
+    // # Bottom
+    sections.push(card.spell_effect.clone());
+    if !card.roll_effect.is_empty() {
+        sections.push(card.roll_effect.iter().map(|rr| format!("{}: {}", localization.t(&rr.to_string()), rr.effect())).collect::<Vec<String>>().join("\n"));
+    }
+    if !card.heightened.is_empty() {
+        sections.push(card.heightened.iter().map(|h| format!("{}: {}", localization.heightened(h), h.effect())).collect::<Vec<String>>().join("\n"));
+    }
+
+    // # Footer
+    if card.source != SpellSource::Unknown {
+        sections.push(format!("{}: {}", localization.t("Source"), localization.source(&card.source)));
+    }
+    sections.join("\n\n")
+}
+
+pub fn book_to_markdown(name: &str, cards: &[SpellCard], terminology: Terminology, localization: Localization) -> String {
+    let mut markdown: String = format!("# {}\n\n", name);
+    markdown.push_str(&cards.iter().map(|card| card_to_markdown(card, terminology, localization)).collect::<Vec<String>>().join("\n\n***\n\n"));
+    markdown.push('\n');
+    markdown
+}
+
+pub fn book_to_plain_text(name: &str, cards: &[SpellCard], terminology: Terminology, localization: Localization) -> String {
+    let separator: String = format!("\n\n{}\n\n", "=".repeat(40));
+    let mut text: String = format!("{}{}", name, separator);
+    text.push_str(&cards.iter().map(|card| card_to_plain_text(card, terminology, localization)).collect::<Vec<String>>().join(&separator));
+    text.push('\n');
+    text
+}
+
+pub fn copy_to_clipboard(text: &str) {
+    if let Some(window) = window() {
+        // The returned promise is not awaited, a failed copy leaves the clipboard as it was
+        _ = window.navigator().clipboard().write_text(text);
+    }
+}
+
+#[derive(PartialEq, Properties)]
+pub struct CopyCardTextProps {
+    pub card: SpellCard,
+}
+
+#[function_component]
+pub fn CopyCardText(props: &CopyCardTextProps) -> Html {
+    let CopyCardTextProps {
+        card
+    } = props;
+
+    let terminology: Terminology = use_context::<Terminology>().unwrap_or_default();
+    let localization: Localization = use_context::<Localization>().unwrap_or_default();
+    let copy_markdown: Callback<MouseEvent> = {
+        let card: SpellCard = card.clone();
+        Callback::from(move |_| copy_to_clipboard(&card_to_markdown(&card, terminology, localization)))
+    };
+    let copy_plain_text: Callback<MouseEvent> = {
+        let card: SpellCard = card.clone();
+        Callback::from(move |_| copy_to_clipboard(&card_to_plain_text(&card, terminology, localization)))
+    };
+
+    html! {
+        <div class="copy-card-text">
+            <button onclick={copy_markdown}>{localization.t("Copy Markdown")}</button>
+            <button onclick={copy_plain_text}>{localization.t("Copy text")}</button>
+        </div>
+    }
+}
+
+#[derive(PartialEq, Properties)]
+pub struct TextExporterProps {
+    pub name: String,
+    pub spells: Vec<SpellCard>,
+}
+
+#[function_component]
+pub fn TextExporter(props: &TextExporterProps) -> Html {
+    let TextExporterProps {
+        name,
+        spells
+    } = props;
+
+    let terminology: Terminology = use_context::<Terminology>().unwrap_or_default();
+    let localization: Localization = use_context::<Localization>().unwrap_or_default();
+    let export_markdown: Callback<MouseEvent> = {
+        let name: String = name.clone();
+        let spells: Vec<SpellCard> = spells.clone();
+        Callback::from(move |_| {
+            _ = download_file(&file_name_for(&name, "md"), "text/markdown", &book_to_markdown(&name, &spells, terminology, localization));
+        })
+    };
+    let export_plain_text: Callback<MouseEvent> = {
+        let name: String = name.clone();
+        let spells: Vec<SpellCard> = spells.clone();
+        Callback::from(move |_| {
+            _ = download_file(&file_name_for(&name, "txt"), "text/plain", &book_to_plain_text(&name, &spells, terminology, localization));
+        })
+    };
+
+    html! {
+        <div class="exporter">
+            <button onclick={export_markdown}>{localization.t("Export as Markdown")}</button>
+            <button onclick={export_plain_text}>{localization.t("Export as text")}</button>
+        </div>
+    }
+}
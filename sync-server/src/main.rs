@@ -0,0 +1,164 @@
+use std::env;
+use std::fs;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::{TcpListener, TcpStream};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use serde_json::{json, Value};
+
+// Reference sync server for the spellbook app.
+//
+//   GET /api/libraries/<id>  returns the stored library, 404 when there is none yet
+//   PUT /api/libraries/<id>  takes {"base_version": n, "library": {...}} and stores the library
+//                            as version n + 1, or answers 409 with the stored library when
+//                            someone else pushed since version n
+//
+// Libraries are kept as <data dir>/<id>.json. Usage: sync-server [address] [data dir]
+
+const DEFAULT_ADDRESS: &str = "127.0.0.1:8787";
+const DEFAULT_DATA_DIR: &str = "sync-data";
+const MAX_BODY_BYTES: usize = 16 * 1024 * 1024;
+const LIBRARY_PREFIX: &str = "/api/libraries/";
+
+struct Response {
+    status: u16,
+    body: Option<Value>,
+}
+
+impl Response {
+    fn new(status: u16, body: Option<Value>) -> Response {
+        Response { status, body }
+    }
+
+    fn error(status: u16, message: &str) -> Response {
+        Response::new(status, Some(json!({ "error": message })))
+    }
+}
+
+fn main() {
+    let mut args = env::args().skip(1);
+    let address: String = args.next().unwrap_or_else(|| DEFAULT_ADDRESS.to_string());
+    let data_dir: PathBuf = PathBuf::from(args.next().unwrap_or_else(|| DEFAULT_DATA_DIR.to_string()));
+    fs::create_dir_all(&data_dir).expect("Could not create the data directory");
+
+    let listener: TcpListener = TcpListener::bind(&address).expect("Could not bind the address");
+    println!("Serving spellbook sync on http://{} with data in {}", address, data_dir.display());
+    // Writes are checked against the stored version, so they must not interleave
+    let write_lock: Arc<Mutex<()>> = Arc::new(Mutex::new(()));
+    for stream in listener.incoming().flatten() {
+        let data_dir: PathBuf = data_dir.clone();
+        let write_lock: Arc<Mutex<()>> = write_lock.clone();
+        thread::spawn(move || {
+            if let Err(err) = handle_connection(stream, &data_dir, &write_lock) {
+                eprintln!("Request failed: {}", err);
+            }
+        });
+    }
+}
+
+fn handle_connection(stream: TcpStream, data_dir: &Path, write_lock: &Mutex<()>) -> std::io::Result<()> {
+    let mut reader: BufReader<TcpStream> = BufReader::new(stream.try_clone()?);
+    let mut request_line: String = String::new();
+    reader.read_line(&mut request_line)?;
+    let mut parts = request_line.split_whitespace();
+    let method: String = parts.next().unwrap_or_default().to_string();
+    let path: String = parts.next().unwrap_or_default().to_string();
+
+    let mut content_length: usize = 0;
+    loop {
+        let mut header: String = String::new();
+        if reader.read_line(&mut header)? == 0 || header.trim().is_empty() {
+            break;
+        }
+        if let Some((name, value)) = header.split_once(':') {
+            if name.trim().eq_ignore_ascii_case("content-length") {
+                content_length = value.trim().parse::<usize>().unwrap_or(0);
+            }
+        }
+    }
+
+    let response: Response = if content_length > MAX_BODY_BYTES {
+        Response::error(413, "The library is too large")
+    } else {
+        let mut body: Vec<u8> = vec![0; content_length];
+        reader.read_exact(&mut body)?;
+        route(&method, &path, &body, data_dir, write_lock)
+    };
+    write_response(stream, response)
+}
+
+fn route(method: &str, path: &str, body: &[u8], data_dir: &Path, write_lock: &Mutex<()>) -> Response {
+    if method == "OPTIONS" {
+        return Response::new(204, None);
+    }
+    let Some(id) = path.strip_prefix(LIBRARY_PREFIX).filter(|id| is_valid_id(id)) else {
+        return Response::error(404, "Unknown path");
+    };
+    let file: PathBuf = data_dir.join(format!("{}.json", id));
+    match method {
+        "GET" => match read_library(&file) {
+            Some(library) => Response::new(200, Some(library)),
+            None => Response::error(404, "No library with this id"),
+        },
+        "PUT" => {
+            let Ok(request) = serde_json::from_slice::<Value>(body) else {
+                return Response::error(400, "The body is not JSON");
+            };
+            let (Some(base_version), Some(library)) = (request["base_version"].as_u64(), request.get("library")) else {
+                return Response::error(400, "Expected base_version and library");
+            };
+            let _guard = write_lock.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+            let stored: Option<Value> = read_library(&file);
+            let stored_version: u64 = stored.as_ref().and_then(|s| s["version"].as_u64()).unwrap_or(0);
+            if stored_version != base_version {
+                return Response::new(409, stored);
+            }
+            let mut library: Value = library.clone();
+            library["version"] = json!(stored_version + 1);
+            match fs::write(&file, library.to_string()) {
+                Ok(()) => Response::new(200, Some(library)),
+                Err(_) => Response::error(500, "The library could not be written"),
+            }
+        },
+        _ => Response::error(405, "Method not allowed"),
+    }
+}
+
+// Ids become file names, so only a safe set of characters is accepted
+fn is_valid_id(id: &str) -> bool {
+    !id.is_empty() && id.len() <= 64 && id.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+}
+
+fn read_library(file: &Path) -> Option<Value> {
+    fs::read(file).ok().and_then(|bytes| serde_json::from_slice::<Value>(&bytes).ok())
+}
+
+fn write_response(mut stream: TcpStream, response: Response) -> std::io::Result<()> {
+    let reason: &str = match response.status {
+        200 => "OK",
+        204 => "No Content",
+        400 => "Bad Request",
+        404 => "Not Found",
+        405 => "Method Not Allowed",
+        409 => "Conflict",
+        413 => "Payload Too Large",
+        _   => "Internal Server Error",
+    };
+    let body: String = response.body.map(|b| b.to_string()).unwrap_or_default();
+    // The app is usually served from another origin, such as GitHub Pages
+    write!(
+        stream,
+        concat!(
+            "HTTP/1.1 {} {}\r\n",
+            "Content-Type: application/json\r\n",
+            "Content-Length: {}\r\n",
+            "Access-Control-Allow-Origin: *\r\n",
+            "Access-Control-Allow-Methods: GET, PUT, OPTIONS\r\n",
+            "Access-Control-Allow-Headers: Content-Type\r\n",
+            "Connection: close\r\n\r\n{}"
+        ),
+        response.status, reason, body.len(), body
+    )?;
+    stream.flush()
+}